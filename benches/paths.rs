@@ -0,0 +1,92 @@
+//! Timings of the everyday path operations, over paths from `SyntheticPaths`.
+//!
+//! Run with `cargo bench --features synthetic`.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use os_path::{BasePath, OsPath, SyntheticPaths};
+use std::hint::black_box;
+
+const COUNT: usize = 1_000;
+
+fn construction(c: &mut Criterion) {
+    let strings = SyntheticPaths::new(1).absolute(true).strings(COUNT);
+    c.bench_function("from_str", |b| {
+        b.iter(|| {
+            for s in &strings {
+                black_box(OsPath::from(s.as_str()));
+            }
+        })
+    });
+}
+
+fn join(c: &mut Criterion) {
+    let dir = OsPath::from("/srv/www/static/assets/");
+    let names = SyntheticPaths::new(2).names(COUNT);
+    c.bench_function("join_name", |b| {
+        b.iter(|| {
+            for name in &names {
+                black_box(dir.join(name));
+            }
+        })
+    });
+    let base = BasePath::new(dir.clone());
+    c.bench_function("base_append", |b| {
+        b.iter(|| {
+            for name in &names {
+                black_box(base.append(name));
+            }
+        })
+    });
+    let relative = SyntheticPaths::new(3).paths(COUNT);
+    c.bench_function("join_os", |b| {
+        b.iter(|| {
+            for path in &relative {
+                black_box(dir.join_os(path));
+            }
+        })
+    });
+}
+
+fn resolve(c: &mut Criterion) {
+    let paths = SyntheticPaths::new(4)
+        .max_depth(10)
+        .parent_refs(20)
+        .paths(COUNT);
+    c.bench_function("resolve", |b| {
+        b.iter_batched(
+            || paths.clone(),
+            |mut paths| {
+                for path in &mut paths {
+                    path.resolve();
+                }
+                paths
+            },
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+fn display(c: &mut Criterion) {
+    let paths = SyntheticPaths::new(5).absolute(true).dirs(30).paths(COUNT);
+    c.bench_function("to_string", |b| {
+        b.iter(|| {
+            for path in &paths {
+                black_box(path.to_string());
+            }
+        })
+    });
+}
+
+fn serde(c: &mut Criterion) {
+    let paths = SyntheticPaths::new(6).absolute(true).paths(COUNT);
+    let json = serde_json::to_string(&paths).unwrap();
+    c.bench_function("serialize_json", |b| {
+        b.iter(|| black_box(serde_json::to_string(&paths).unwrap()))
+    });
+    c.bench_function("deserialize_json", |b| {
+        b.iter(|| black_box(serde_json::from_str::<Vec<OsPath>>(&json).unwrap()))
+    });
+}
+
+criterion_group!(benches, construction, join, resolve, display, serde);
+criterion_main!(benches);
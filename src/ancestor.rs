@@ -0,0 +1,79 @@
+//! Finding the deepest directory a group of paths shares.
+
+use crate::{OsPath, PurePath};
+
+/// Ancestor Methods
+impl OsPath {
+    /// Returns the deepest directory that holds both paths, compared component by component after `resolve()`. A
+    /// file is held by its parent, while a directory can be the answer itself.
+    ///
+    /// Returns `None` when one path is absolute and the other is not, when they are on different Windows drives or
+    /// shares, or when two relative paths share no leading directory.
+    /// ```rust
+    /// #[cfg(unix)]
+    /// {
+    /// use os_path::OsPath;
+    ///
+    /// let lib = OsPath::from("/work/app/src/lib.rs");
+    /// let test = OsPath::from("/work/app/tests/it.rs");
+    /// assert_eq!(lib.common_ancestor(&test).unwrap().to_string(), "/work/app/");
+    /// assert_eq!(lib.common_ancestor(&OsPath::from("/work/app/src/")).unwrap().to_string(), "/work/app/src/");
+    /// assert_eq!(lib.common_ancestor(&OsPath::from("/etc/hosts")), Some(OsPath::from("/")));
+    /// assert_eq!(OsPath::from("src/a.rs").common_ancestor(&OsPath::from("tests/b.rs")), None);
+    /// }
+    /// ```
+    pub fn common_ancestor(&self, other: &OsPath) -> Option<OsPath> {
+        if self.absolute != other.absolute {
+            return None;
+        }
+        let (a, b) = (self.dir_components(), other.dir_components());
+        let common = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+        // An absolute path with nothing in common is fine below the Unix root, but not across Windows drives.
+        let prefixed = |c: &[String]| c.first().is_some_and(|c| PurePath::is_prefix(c));
+        if common == 0 && (!self.absolute || prefixed(&a) || prefixed(&b)) {
+            return None;
+        }
+        let components = a[..common].to_vec();
+        Some(OsPath {
+            path: Self::build_pathbuf(&components, self.absolute),
+            components,
+            absolute: self.absolute,
+            directory: true,
+            style: self.style,
+        })
+    }
+
+    /// Returns the resolved components of the directory the path is or is in.
+    fn dir_components(&self) -> Vec<String> {
+        let mut path = self.clone();
+        path.resolve();
+        if !path.directory {
+            path.components.pop();
+        }
+        path.components
+    }
+}
+
+/// Returns the deepest directory holding every one of the paths, as `OsPath::common_ancestor()` finds it for two, such
+/// as the root of a workspace from its source files. A single file gives its parent. Returns `None` for no paths, or
+/// when some pair of them has no common ancestor.
+/// ```rust
+/// #[cfg(unix)]
+/// {
+/// use os_path::common_ancestor;
+///
+/// let root = common_ancestor(["/ws/core/src/lib.rs", "/ws/cli/src/main.rs", "/ws/core/build.rs"]).unwrap();
+/// assert_eq!(root.to_string(), "/ws/");
+/// assert_eq!(common_ancestor(Vec::<&str>::new()), None);
+/// }
+/// ```
+pub fn common_ancestor<I>(paths: I) -> Option<OsPath>
+where
+    I: IntoIterator,
+    I::Item: Into<OsPath>,
+{
+    let mut paths = paths.into_iter().map(Into::into);
+    let first = paths.next()?;
+    let start = first.common_ancestor(&first);
+    paths.fold(start, |ancestor, path| ancestor?.common_ancestor(&path))
+}
@@ -0,0 +1,72 @@
+//! Async versions of the filesystem queries, run on tokio's blocking pool so they do not stall the runtime.
+
+use crate::error::IoResultExt;
+use crate::fs::Metadata;
+use crate::OsPath;
+use std::io;
+
+/// Async Methods
+impl OsPath {
+    /// Returns true if the path exists on the real filesystem, following symbolic links. Errors other than the path
+    /// not existing, such as a permission error, also count as not existing.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// assert!(OsPath::from("src/lib.rs").exists_async().await);
+    /// assert!(!OsPath::from("src/missing.rs").exists_async().await);
+    /// # });
+    /// ```
+    pub async fn exists_async(&self) -> bool {
+        tokio::fs::try_exists(self).await.unwrap_or(false)
+    }
+
+    /// Returns the metadata of the path on the real filesystem, following symbolic links.
+    pub async fn metadata_async(&self) -> io::Result<Metadata> {
+        tokio::fs::metadata(self)
+            .await
+            .map(Metadata::from)
+            .with_path_context("read metadata of", self)
+    }
+
+    /// Lists the entries of the directory on the real filesystem, sorted by name. As with `read_dir()`,
+    /// subdirectories and symbolic links leading to directories come back as directory paths.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let entries = OsPath::from("src").read_dir_async().await.unwrap();
+    /// assert!(entries.iter().any(|p| p.name().unwrap() == "lib.rs" && p.is_file()));
+    /// # });
+    /// ```
+    pub async fn read_dir_async(&self) -> io::Result<Vec<OsPath>> {
+        let read = async {
+            let mut entries = Vec::new();
+            let mut dir = tokio::fs::read_dir(self).await?;
+            while let Some(entry) = dir.next_entry().await? {
+                let mut path = self.join(entry.file_name());
+                let kind = entry.file_type().await?;
+                let is_dir = match kind.is_symlink() {
+                    true => tokio::fs::metadata(&path).await.is_ok_and(|m| m.is_dir()),
+                    false => kind.is_dir(),
+                };
+                if is_dir {
+                    path.force_dir();
+                }
+                entries.push(path);
+            }
+            entries.sort_by(|a, b| a.name().cmp(&b.name()));
+            Ok(entries)
+        };
+        read.await.with_path_context("read directory", self)
+    }
+
+    /// Returns the absolute path with all links and `..` resolved, as `canonicalize()` does.
+    pub async fn canonicalize_async(&self) -> io::Result<OsPath> {
+        let real = tokio::fs::canonicalize(self)
+            .await
+            .with_path_context("canonicalize", self)?;
+        let is_dir = tokio::fs::metadata(&real).await.is_ok_and(|m| m.is_dir());
+        Ok(OsPath::from_canonical(&real, is_dir))
+    }
+}
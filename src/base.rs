@@ -0,0 +1,75 @@
+//! A directory prepared for joining many names onto, as a server does with its document root.
+
+use crate::{OsPath, PurePath};
+use std::path::PathBuf;
+
+/// A directory that names are appended to over and over, such as the root a server resolves request paths under.
+///
+/// `append()` copies the components and the `PathBuf` of the base as they are and adds the new names after them,
+/// sizing both once, rather than parsing and merging the whole path again as `join()` does. Names holding `.` or
+/// `..` components, or anything else that needs resolving, are joined with `join()` instead, so the result is always
+/// the same as `base.join(name)`.
+/// ```rust
+/// use os_path::{BasePath, OsPath};
+///
+/// #[cfg(unix)]
+/// {
+/// let root = BasePath::new("/srv/www");
+/// assert_eq!(root.path().to_string(), "/srv/www/");
+/// assert_eq!(root.append("css/site.css").to_string(), "/srv/www/css/site.css");
+/// assert_eq!(root.append("/img/").to_string(), "/srv/www/img/");
+/// assert_eq!(root.append("../secret").to_string(), "/srv/secret");
+/// }
+/// let root = BasePath::new("static");
+/// assert_eq!(root.append("app.js"), OsPath::from("static").join("app.js"));
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct BasePath {
+    base: OsPath,
+}
+
+impl BasePath {
+    /// Prepares the path for appending to. It is treated as a directory even if it does not end with a separator.
+    pub fn new<P: Into<OsPath>>(base: P) -> Self {
+        let mut base = base.into();
+        base.force_dir();
+        Self { base }
+    }
+
+    /// Returns the base directory.
+    pub fn path(&self) -> &OsPath {
+        &self.base
+    }
+
+    /// Returns the base with the relative path appended, as `join()` would. A leading separator does not make the
+    /// name absolute, and a trailing one makes the result a directory.
+    pub fn append(&self, name: &str) -> OsPath {
+        let parts = || name.split(['/', '\\']).filter(|p| !p.is_empty());
+        let plain =
+            |p: &str| p != "." && p != crate::UP && !p.contains('\0') && !PurePath::is_prefix(p);
+        let empty = self.base.components.is_empty() && !self.base.absolute;
+        if empty || !parts().all(plain) {
+            return self.base.join(name);
+        }
+        let count = parts().count();
+        if count == 0 {
+            return self.base.clone();
+        }
+
+        let mut components = Vec::with_capacity(self.base.components.len() + count);
+        components.extend_from_slice(&self.base.components);
+        let mut path = PathBuf::with_capacity(self.base.path.as_os_str().len() + name.len() + 1);
+        path.push(&self.base.path);
+        for part in parts() {
+            components.push(part.to_string());
+            path.push(part);
+        }
+        OsPath {
+            components,
+            absolute: self.base.absolute,
+            directory: name.ends_with(['/', '\\']),
+            path,
+            style: self.base.style,
+        }
+    }
+}
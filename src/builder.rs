@@ -0,0 +1,158 @@
+//! Building paths one part at a time, with the file or directory kind stated rather than read from the text.
+
+use crate::flavor::Style;
+use crate::{Flavor, OsPath};
+use std::fmt;
+
+/// Builds a path from its parts, made by `OsPath::builder()`. Each part is a single name, checked when the path is
+/// built, and whether the path names a file or a directory is set by the last part added.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct OsPathBuilder {
+    root: bool,
+    drive: Option<char>,
+    components: Vec<String>,
+    file: bool,
+    style: Option<Flavor>,
+    error: Option<BuildError>,
+}
+
+impl OsPathBuilder {
+    /// Creates an empty builder for a relative path.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts the path at the root, making it absolute. On Windows an absolute path also needs a `drive()`.
+    pub fn root(mut self) -> Self {
+        self.root = true;
+        self
+    }
+
+    /// Starts the path at the root of a Windows drive, making it absolute. On Unix this is the same as `root()`.
+    pub fn drive(mut self, letter: char) -> Self {
+        if !letter.is_ascii_alphabetic() {
+            self.fail(BuildError::InvalidName(letter.to_string()));
+        }
+        self.root = true;
+        self.drive = Some(letter.to_ascii_uppercase());
+        self
+    }
+
+    /// Adds a directory. `..` and `.` are accepted and kept as they are.
+    pub fn dir<S: AsRef<str>>(mut self, name: S) -> Self {
+        self.push(name.as_ref(), false);
+        self
+    }
+
+    /// Adds a file, which must be the last part of the path.
+    pub fn file<S: AsRef<str>>(mut self, name: S) -> Self {
+        let name = name.as_ref();
+        if name == "." || name == crate::UP {
+            self.fail(BuildError::InvalidName(name.to_string()));
+        }
+        self.push(name, true);
+        self
+    }
+
+    /// Displays the built path in the notation of the given platform. See `OsPath::with_style()`.
+    pub fn style(mut self, flavor: Flavor) -> Self {
+        self.style = Some(flavor);
+        self
+    }
+
+    /// Builds the path, or returns the first problem found with its parts.
+    /// ```rust
+    /// use os_path::{BuildError, OsPath};
+    ///
+    /// #[cfg(unix)]
+    /// {
+    /// let path = OsPath::builder().root().dir("foo").dir("bar").file("baz.txt").build().unwrap();
+    /// assert_eq!(path.to_string(), "/foo/bar/baz.txt");
+    /// assert!(path.is_file());
+    /// }
+    /// #[cfg(windows)]
+    /// {
+    /// let path = OsPath::builder().drive('C').dir("foo").dir("bar").file("baz.txt").build().unwrap();
+    /// assert_eq!(path.to_string(), "C:\\foo\\bar\\baz.txt");
+    /// }
+    ///
+    /// let dir = OsPath::builder().dir("src").dir("bin").build().unwrap();
+    /// assert!(dir.is_dir());
+    ///
+    /// let result = OsPath::builder().dir("a/b").build();
+    /// assert_eq!(result, Err(BuildError::InvalidName("a/b".to_string())));
+    ///
+    /// let result = OsPath::builder().file("notes.txt").dir("more").build();
+    /// assert_eq!(result, Err(BuildError::FileNotLast("notes.txt".to_string())));
+    /// ```
+    pub fn build(self) -> Result<OsPath, BuildError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        let components = match self.drive {
+            Some(letter) if cfg!(windows) => std::iter::once(format!("{}:", letter))
+                .chain(self.components)
+                .collect(),
+            None if cfg!(windows) && self.root => return Err(BuildError::MissingDrive),
+            _ => self.components,
+        };
+        let path = OsPath::build_pathbuf(&components, self.root);
+        Ok(OsPath {
+            directory: !self.file && (self.root || !components.is_empty()),
+            components,
+            absolute: self.root,
+            path,
+            style: Style::of(self.style),
+        })
+    }
+
+    fn push(&mut self, name: &str, file: bool) {
+        if self.file {
+            let last = self.components.last().cloned().unwrap_or_default();
+            self.fail(BuildError::FileNotLast(last));
+        }
+        if name.is_empty() || name.contains(['/', '\\', '\0']) {
+            self.fail(BuildError::InvalidName(name.to_string()));
+        }
+        self.components.push(name.to_string());
+        self.file = file;
+    }
+
+    /// Records the problem unless an earlier one was already found.
+    fn fail(&mut self, error: BuildError) {
+        self.error.get_or_insert(error);
+    }
+}
+
+/// The error returned by `OsPathBuilder::build()`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum BuildError {
+    /// A part was empty, held a separator or NUL, or was `.` or `..` given as a file.
+    InvalidName(String),
+    /// Another part was added after this file.
+    FileNotLast(String),
+    /// On Windows, the path was started at the root without a drive.
+    MissingDrive,
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::InvalidName(name) => write!(f, "'{}' is not a valid path name", name),
+            BuildError::FileNotLast(name) => {
+                write!(f, "file '{}' must be the last part of the path", name)
+            }
+            BuildError::MissingDrive => f.write_str("absolute path has no drive"),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+/// Builder Methods
+impl OsPath {
+    /// Returns a builder for making a path from its parts.
+    pub fn builder() -> OsPathBuilder {
+        OsPathBuilder::new()
+    }
+}
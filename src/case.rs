@@ -0,0 +1,142 @@
+//! Rewriting path components in a consistent letter case or naming convention.
+
+use crate::{OsPath, PurePath};
+
+/// A naming convention applied to each component by `OsPath::map_case()`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CaseStyle {
+    /// `user_profile`
+    Snake,
+    /// `user-profile`
+    Kebab,
+    /// `userProfile`
+    Camel,
+}
+
+impl CaseStyle {
+    fn apply(self, text: &str) -> String {
+        let words = split_words(text);
+        if words.is_empty() {
+            return text.to_string();
+        }
+        match self {
+            CaseStyle::Snake => words.join("_").to_lowercase(),
+            CaseStyle::Kebab => words.join("-").to_lowercase(),
+            CaseStyle::Camel => {
+                let mut camel = words[0].to_lowercase();
+                for word in &words[1..] {
+                    let mut chars = word.chars();
+                    if let Some(first) = chars.next() {
+                        camel.extend(first.to_uppercase());
+                        camel.push_str(&chars.as_str().to_lowercase());
+                    }
+                }
+                camel
+            }
+        }
+    }
+}
+
+/// Splits text into words at anything that is not a letter or digit, and where the case changes: `fooBar` and
+/// `HTTPServer` each hold two words.
+fn split_words(text: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let mut start: Option<usize> = None;
+    for (i, &(at, c)) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if let Some(s) = start.take() {
+                words.push(&text[s..at]);
+            }
+            continue;
+        }
+        if let Some(s) = start {
+            let prev = chars[i - 1].1;
+            let next_lower = chars.get(i + 1).is_some_and(|(_, n)| n.is_lowercase());
+            let boundary = c.is_uppercase()
+                && (prev.is_lowercase()
+                    || prev.is_numeric()
+                    || (prev.is_uppercase() && next_lower));
+            if boundary {
+                words.push(&text[s..at]);
+                start = Some(at);
+            }
+        } else {
+            start = Some(at);
+        }
+    }
+    if let Some(s) = start {
+        words.push(&text[s..]);
+    }
+    words
+}
+
+/// Case Methods
+impl OsPath {
+    /// Returns the path with every component in lower case. A Windows drive or share is left as it is.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// assert_eq!(OsPath::from("Docs/READ ME.TXT").to_lowercase_path(), OsPath::from("docs/read me.txt"));
+    /// ```
+    pub fn to_lowercase_path(&self) -> Self {
+        self.map_components(|c, _| c.to_lowercase())
+    }
+
+    /// Returns the path with every component in upper case. A Windows drive or share is left as it is.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// assert_eq!(OsPath::from("docs/readme.txt").to_uppercase_path(), OsPath::from("DOCS/README.TXT"));
+    /// ```
+    pub fn to_uppercase_path(&self) -> Self {
+        self.map_components(|c, _| c.to_uppercase())
+    }
+
+    /// Returns the path with every component rewritten in the naming convention, splitting names into words at
+    /// spaces, punctuation, and changes of case. The extension of a file and the leading dot of a hidden name are kept
+    /// as they are, as are `.`, `..`, a Windows drive or share, and names that are not valid Unicode.
+    /// ```rust
+    /// use os_path::{CaseStyle, OsPath};
+    ///
+    /// let path = OsPath::from("User Profiles/HTTPServer/myConfig-v2.JSON");
+    /// assert_eq!(path.map_case(CaseStyle::Snake), OsPath::from("user_profiles/http_server/my_config_v2.JSON"));
+    /// assert_eq!(path.map_case(CaseStyle::Kebab), OsPath::from("user-profiles/http-server/my-config-v2.JSON"));
+    /// assert_eq!(path.map_case(CaseStyle::Camel), OsPath::from("userProfiles/httpServer/myConfigV2.JSON"));
+    /// assert_eq!(OsPath::from(".github/").map_case(CaseStyle::Snake), OsPath::from(".github/"));
+    /// ```
+    pub fn map_case(&self, style: CaseStyle) -> Self {
+        self.map_components(|c, is_file| {
+            // Names that are not valid Unicode cannot be split into words safely.
+            if c.contains('\0') {
+                return c.to_string();
+            }
+            let hidden = c.len() - c.trim_start_matches('.').len();
+            let (dots, name) = c.split_at(hidden);
+            let (stem, ext) = match name.rsplit_once('.') {
+                Some((stem, ext)) if is_file && !stem.is_empty() => (stem, Some(ext)),
+                _ => (name, None),
+            };
+            let mut mapped = format!("{}{}", dots, style.apply(stem));
+            if let Some(ext) = ext {
+                mapped.push('.');
+                mapped.push_str(ext);
+            }
+            mapped
+        })
+    }
+
+    /// Rewrites each ordinary component with the function, which is also told whether the component names a file.
+    fn map_components<F: Fn(&str, bool) -> String>(&self, f: F) -> Self {
+        let mut new_self = self.clone();
+        let last = new_self.components.len().saturating_sub(1);
+        for (i, c) in new_self.components.iter_mut().enumerate() {
+            if c == "." || c == crate::UP || (i == 0 && self.absolute && PurePath::is_prefix(c)) {
+                continue;
+            }
+            *c = f(c, i == last && !self.directory);
+        }
+        new_self.path = Self::build_pathbuf(&new_self.components, new_self.absolute);
+        new_self
+    }
+}
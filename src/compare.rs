@@ -0,0 +1,415 @@
+//! Comparing and ordering paths by the rules of particular filesystems and locales.
+
+use crate::{OsPath, PurePath};
+use std::cmp::Ordering;
+use std::path::Path;
+
+/// Whether letter case tells paths apart.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum CaseSensitivity {
+    /// `Report.txt` and `report.txt` are different paths, as on most Linux filesystems.
+    #[default]
+    Sensitive,
+    /// `Report.txt` and `report.txt` are the same path, as on NTFS and the default APFS.
+    Insensitive,
+}
+
+impl CaseSensitivity {
+    /// Returns the usual behavior of the platform's filesystems: insensitive on Windows and macOS, sensitive
+    /// elsewhere. A particular volume may be set up differently.
+    pub fn native() -> Self {
+        if cfg!(any(windows, target_os = "macos")) {
+            CaseSensitivity::Insensitive
+        } else {
+            CaseSensitivity::Sensitive
+        }
+    }
+}
+
+/// Comparison Methods
+impl OsPath {
+    /// Returns true if the paths are equal when letter case is ignored, as `==` compares them otherwise.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// assert!(OsPath::from("Docs/Bar.TXT").eq_ignore_case(&OsPath::from("docs/bar.txt")));
+    /// assert!(!OsPath::from("Docs/").eq_ignore_case(&OsPath::from("docs")));
+    /// ```
+    pub fn eq_ignore_case(&self, other: &OsPath) -> bool {
+        self.absolute == other.absolute
+            && self.directory == other.directory
+            && self.components.len() == other.components.len()
+            && self
+                .components
+                .iter()
+                .zip(&other.components)
+                .all(|(a, b)| Self::component_eq(a, b, CaseSensitivity::Insensitive))
+    }
+
+    /// Returns true if the paths are equal under the case sensitivity given.
+    /// ```rust
+    /// use os_path::{CaseSensitivity, OsPath};
+    ///
+    /// let (a, b) = (OsPath::from("Foo/Bar.TXT"), OsPath::from("foo/bar.txt"));
+    /// assert!(!a.eq_with_case(&b, CaseSensitivity::Sensitive));
+    /// assert!(a.eq_with_case(&b, CaseSensitivity::Insensitive));
+    /// assert_eq!(a.eq_with_case(&b, CaseSensitivity::native()), cfg!(any(windows, target_os = "macos")));
+    /// ```
+    pub fn eq_with_case(&self, other: &OsPath, case: CaseSensitivity) -> bool {
+        match case {
+            CaseSensitivity::Sensitive => self == other,
+            CaseSensitivity::Insensitive => self.eq_ignore_case(other),
+        }
+    }
+
+    /// Returns true if `base` is a leading part of the path, compared whole component by whole component, so
+    /// `/srv/www/img` starts with `/srv/www` but not with `/srv/ww`. Letter case counts as `CaseSensitivity::native()`
+    /// says, and the directory marker is ignored. A `..` after `base` that climbs back out of it makes the answer
+    /// false, so `/srv/www/../etc` does not start with `/srv/www`, which makes this fit for checking a path against
+    /// an allowlist of directories.
+    /// ```rust
+    /// #[cfg(unix)]
+    /// {
+    /// use os_path::OsPath;
+    ///
+    /// let path = OsPath::from("/srv/www/img/logo.png");
+    /// assert!(path.starts_with("/srv/www/"));
+    /// assert!(path.starts_with(&OsPath::from("/srv")));
+    /// assert!(!path.starts_with("/srv/ww"));
+    /// assert!(!path.starts_with("srv/www"));
+    /// assert!(!OsPath::from("/srv/www/../etc/passwd").starts_with("/srv/www"));
+    /// }
+    /// ```
+    pub fn starts_with<P: AsRef<Path>>(&self, base: P) -> bool {
+        self.starts_with_case(base, CaseSensitivity::native())
+    }
+
+    /// Returns true if the path starts with `base` as `starts_with()` decides, under the case sensitivity given.
+    /// ```rust
+    /// use os_path::{CaseSensitivity, OsPath};
+    ///
+    /// let path = OsPath::from("Users/Alice/Documents/");
+    /// assert!(path.starts_with_case("users/alice", CaseSensitivity::Insensitive));
+    /// assert!(!path.starts_with_case("users/alice", CaseSensitivity::Sensitive));
+    /// ```
+    pub fn starts_with_case<P: AsRef<Path>>(&self, base: P, case: CaseSensitivity) -> bool {
+        let base = OsPath::from(base.as_ref());
+        let n = base.components.len();
+        self.absolute == base.absolute
+            && self.components.len() >= n
+            && self
+                .components
+                .iter()
+                .zip(&base.components)
+                .all(|(a, b)| Self::component_eq(a, b, case))
+            && !PurePath::climbs_out(&self.components[n..])
+    }
+
+    /// Returns true if `tail` is a trailing part of the path, compared whole component by whole component, so
+    /// `docs/guide.md` ends with `guide.md` but not with `ide.md`. An absolute `tail` has to be the whole path.
+    /// Letter case counts as `CaseSensitivity::native()` says, and the directory marker is ignored.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// let path = OsPath::from("project/docs/guide.md");
+    /// assert!(path.ends_with("docs/guide.md"));
+    /// assert!(path.ends_with(&OsPath::from("guide.md")));
+    /// assert!(!path.ends_with("ide.md"));
+    /// assert!(!path.ends_with("/docs/guide.md"));
+    /// ```
+    pub fn ends_with<P: AsRef<Path>>(&self, tail: P) -> bool {
+        self.ends_with_case(tail, CaseSensitivity::native())
+    }
+
+    /// Returns true if the path ends with `tail` as `ends_with()` decides, under the case sensitivity given.
+    pub fn ends_with_case<P: AsRef<Path>>(&self, tail: P, case: CaseSensitivity) -> bool {
+        let tail = OsPath::from(tail.as_ref());
+        let n = tail.components.len();
+        if tail.absolute && (!self.absolute || self.components.len() != n) {
+            return false;
+        }
+        self.components.len() >= n
+            && self.components[self.components.len() - n..]
+                .iter()
+                .zip(&tail.components)
+                .all(|(a, b)| Self::component_eq(a, b, case))
+    }
+
+    /// Returns the path with its case folded, so that two paths are `eq_ignore_case()` exactly when their folded
+    /// forms are `==`. Letters are made lower case, apart from a Windows drive letter, which stays upper case. Use it
+    /// as the key when deduplicating paths for a case-insensitive filesystem.
+    /// ```rust
+    /// use os_path::OsPath;
+    /// use std::collections::HashSet;
+    ///
+    /// let paths = ["src/Main.rs", "SRC/main.RS", "src/lib.rs"].map(OsPath::from);
+    /// let unique: HashSet<OsPath> = paths.iter().map(OsPath::normalize_case).collect();
+    /// assert_eq!(unique.len(), 2);
+    /// ```
+    pub fn normalize_case(&self) -> Self {
+        let mut new_self = self.clone();
+        for (i, c) in new_self.components.iter_mut().enumerate() {
+            if i == 0 && self.drive().is_some() {
+                continue;
+            }
+            *c = c.to_lowercase();
+        }
+        new_self.path = Self::build_pathbuf(&new_self.components, new_self.absolute);
+        new_self
+    }
+
+    /// Returns true if the two paths name the same file under the default rules of NTFS, whatever platform this runs
+    /// on: letters compare without regard to case, `/` and `\` are the same separator, and trailing dots and spaces
+    /// on each component are ignored.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// let path = OsPath::from("Users\\Alice\\Documents\\Report.DOCX");
+    /// assert!(path.windows_eq("users/alice/documents/report.docx"));
+    /// assert!(path.windows_eq("USERS\\ALICE. \\Documents\\Report.docx..."));
+    /// assert!(!path.windows_eq("Users/Alice/Report.docx"));
+    /// ```
+    pub fn windows_eq<P: AsRef<Path>>(&self, other: P) -> bool {
+        let other = OsPath::from(other.as_ref());
+        self.absolute == other.absolute
+            && self.components.len() == other.components.len()
+            && self
+                .components
+                .iter()
+                .zip(&other.components)
+                .all(|(a, b)| Self::windows_component_eq(a, b))
+    }
+
+    /// Orders two paths component by component, reading runs of digits in a name as numbers, so `page2.md` sorts
+    /// before `page10.md`. Everything else compares as `Ord` does, and relative paths come before absolute ones. Unlike
+    /// `cmp_locale()`, letter case and accents are not ignored.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// let mut paths: Vec<OsPath> = ["v10/a.txt", "v9/b.txt", "v9/a2.txt", "v9/a01.txt"]
+    ///     .into_iter()
+    ///     .map(OsPath::from)
+    ///     .collect();
+    /// paths.sort_by(OsPath::cmp_natural);
+    /// let sorted: Vec<String> = paths.iter().map(|p| p.to_string()).collect();
+    /// assert_eq!(sorted, vec!["v9/a01.txt", "v9/a2.txt", "v9/b.txt", "v10/a.txt"]);
+    /// ```
+    pub fn cmp_natural(&self, other: &OsPath) -> Ordering {
+        self.absolute
+            .cmp(&other.absolute)
+            .then_with(|| {
+                self.components
+                    .iter()
+                    .zip(&other.components)
+                    .map(|(a, b)| Self::natural_compare(a, b))
+                    .find(|o| o.is_ne())
+                    .unwrap_or_else(|| self.components.len().cmp(&other.components.len()))
+            })
+            .then_with(|| self.cmp(other))
+    }
+
+    fn natural_compare(a: &str, b: &str) -> Ordering {
+        let (mut a, mut b) = (a, b);
+        loop {
+            let (Some(x), Some(y)) = (a.chars().next(), b.chars().next()) else {
+                return a.len().cmp(&b.len());
+            };
+            if x.is_ascii_digit() && y.is_ascii_digit() {
+                let (run_a, rest_a) = Self::split_digits(a);
+                let (run_b, rest_b) = Self::split_digits(b);
+                let (num_a, num_b) = (run_a.trim_start_matches('0'), run_b.trim_start_matches('0'));
+                let order = num_a.len().cmp(&num_b.len()).then_with(|| num_a.cmp(num_b));
+                if order.is_ne() {
+                    return order;
+                }
+                (a, b) = (rest_a, rest_b);
+            } else if x != y {
+                return x.cmp(&y);
+            } else {
+                (a, b) = (&a[x.len_utf8()..], &b[y.len_utf8()..]);
+            }
+        }
+    }
+
+    /// Splits the text after its leading run of ASCII digits.
+    fn split_digits(text: &str) -> (&str, &str) {
+        let end = text
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(text.len());
+        text.split_at(end)
+    }
+
+    fn component_eq(a: &str, b: &str, case: CaseSensitivity) -> bool {
+        match case {
+            CaseSensitivity::Sensitive => a == b,
+            CaseSensitivity::Insensitive => {
+                a.eq_ignore_ascii_case(b) || a.to_lowercase() == b.to_lowercase()
+            }
+        }
+    }
+
+    fn windows_component_eq(a: &str, b: &str) -> bool {
+        let (a, b) = (Self::windows_trim(a), Self::windows_trim(b));
+        Self::component_eq(a, b, CaseSensitivity::Insensitive)
+    }
+
+    /// Drops the trailing dots and spaces Windows ignores, leaving `.` and `..` alone.
+    fn windows_trim(component: &str) -> &str {
+        if component == "." || component == crate::UP {
+            return component;
+        }
+        component.trim_end_matches(['.', ' '])
+    }
+}
+
+/// Locale-Aware Ordering Methods
+#[cfg(feature = "unicode")]
+impl OsPath {
+    /// Orders two paths for display the way file managers do, comparing component by component.
+    ///
+    /// Names are compared first by their letters alone, ignoring case and accents and reading runs of digits as
+    /// numbers, so `file2` sorts before `file10`. Ties are broken by accents, then by case with lowercase first. The
+    /// locale is a language tag such as `en` or `sv-SE`; Swedish, Finnish, Danish, and Norwegian sort their extra
+    /// letters after `z`, and other languages use the default order.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// let mut names: Vec<OsPath> = ["Zebra", "apple", "résumé", "file10", "file2", "Apple", "resume"]
+    ///     .into_iter()
+    ///     .map(OsPath::from)
+    ///     .collect();
+    /// names.sort_by(|a, b| a.cmp_locale(b, "en"));
+    /// let sorted: Vec<String> = names.iter().map(|p| p.to_string()).collect();
+    /// assert_eq!(sorted, vec!["apple", "Apple", "file2", "file10", "resume", "résumé", "Zebra"]);
+    ///
+    /// let a_ring = OsPath::from("Ångström");
+    /// assert!(a_ring.cmp_locale(&OsPath::from("Zulu"), "en").is_lt());
+    /// assert!(a_ring.cmp_locale(&OsPath::from("Zulu"), "sv").is_gt());
+    /// ```
+    pub fn cmp_locale(&self, other: &OsPath, locale: &str) -> std::cmp::Ordering {
+        let tailoring = collation::Tailoring::for_locale(locale);
+        self.components
+            .iter()
+            .zip(&other.components)
+            .map(|(a, b)| collation::compare(a, b, tailoring))
+            .find(|o| o.is_ne())
+            .unwrap_or_else(|| self.components.len().cmp(&other.components.len()))
+    }
+}
+
+#[cfg(feature = "unicode")]
+mod collation {
+    use std::cmp::Ordering;
+    use unicode_normalization::char::is_combining_mark;
+    use unicode_normalization::UnicodeNormalization;
+
+    #[derive(Clone, Copy)]
+    pub(super) enum Tailoring {
+        Default,
+        /// Swedish and Finnish: å, ä, ö after z, with æ and ø as ä and ö.
+        SwedishFinnish,
+        /// Danish and Norwegian: æ, ø, å after z, with ä and ö as æ and ø.
+        DanishNorwegian,
+    }
+
+    impl Tailoring {
+        pub(super) fn for_locale(locale: &str) -> Self {
+            let language = locale
+                .split(['-', '_'])
+                .next()
+                .unwrap_or_default()
+                .to_ascii_lowercase();
+            match language.as_str() {
+                "sv" | "fi" => Tailoring::SwedishFinnish,
+                "da" | "nb" | "nn" | "no" => Tailoring::DanishNorwegian,
+                _ => Tailoring::Default,
+            }
+        }
+
+        /// Returns the rank after `z` of a letter this tailoring treats as its own.
+        fn extra_letter(self, c: char) -> Option<u8> {
+            let lower = c.to_lowercase().next()?;
+            match (self, lower) {
+                (Tailoring::SwedishFinnish, 'å') => Some(1),
+                (Tailoring::SwedishFinnish, 'ä' | 'æ') => Some(2),
+                (Tailoring::SwedishFinnish, 'ö' | 'ø') => Some(3),
+                (Tailoring::DanishNorwegian, 'æ' | 'ä') => Some(1),
+                (Tailoring::DanishNorwegian, 'ø' | 'ö') => Some(2),
+                (Tailoring::DanishNorwegian, 'å') => Some(3),
+                _ => None,
+            }
+        }
+    }
+
+    #[derive(PartialEq, Eq)]
+    enum Unit {
+        /// A run of digits with leading zeros removed.
+        Number(String),
+        /// A base letter, and its rank after `z` if the locale treats it as a separate letter.
+        Letter(char, u8),
+    }
+
+    impl Ord for Unit {
+        fn cmp(&self, other: &Self) -> Ordering {
+            match (self, other) {
+                (Unit::Number(a), Unit::Number(b)) => a.len().cmp(&b.len()).then_with(|| a.cmp(b)),
+                (Unit::Number(_), Unit::Letter(..)) => Ordering::Less,
+                (Unit::Letter(..), Unit::Number(_)) => Ordering::Greater,
+                (Unit::Letter(a, 0), Unit::Letter(b, 0)) => a.cmp(b),
+                (Unit::Letter(a, 0), Unit::Letter(..)) => a.cmp(&'z').then(Ordering::Less),
+                (Unit::Letter(..), Unit::Letter(b, 0)) => 'z'.cmp(b).then(Ordering::Greater),
+                (Unit::Letter(_, x), Unit::Letter(_, y)) => x.cmp(y),
+            }
+        }
+    }
+
+    impl PartialOrd for Unit {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    fn primary(text: &str, tailoring: Tailoring) -> Vec<Unit> {
+        let mut units = Vec::new();
+        let mut digits = String::new();
+        let flush = |digits: &mut String, units: &mut Vec<Unit>| {
+            if !digits.is_empty() {
+                let trimmed = digits.trim_start_matches('0');
+                units.push(Unit::Number(
+                    if trimmed.is_empty() { "0" } else { trimmed }.to_string(),
+                ));
+                digits.clear();
+            }
+        };
+        for c in text.nfc() {
+            if c.is_ascii_digit() {
+                digits.push(c);
+                continue;
+            }
+            flush(&mut digits, &mut units);
+            if let Some(rank) = tailoring.extra_letter(c) {
+                units.push(Unit::Letter('z', rank));
+                continue;
+            }
+            for d in std::iter::once(c).nfd().filter(|d| !is_combining_mark(*d)) {
+                units.extend(d.to_lowercase().map(|l| Unit::Letter(l, 0)));
+            }
+        }
+        flush(&mut digits, &mut units);
+        units
+    }
+
+    pub(super) fn compare(a: &str, b: &str, tailoring: Tailoring) -> Ordering {
+        primary(a, tailoring)
+            .cmp(&primary(b, tailoring))
+            .then_with(|| {
+                let accents = |s: &str| s.nfd().flat_map(char::to_lowercase).collect::<String>();
+                accents(a).cmp(&accents(b))
+            })
+            .then_with(|| {
+                let cases = |s: &str| s.chars().map(char::is_uppercase).collect::<Vec<_>>();
+                cases(a).cmp(&cases(b))
+            })
+            .then_with(|| a.cmp(b))
+    }
+}
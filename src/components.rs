@@ -0,0 +1,72 @@
+//! Walking the components of a path without going through its string form.
+
+#[cfg(feature = "std")]
+use crate::OsPath;
+use alloc::string::String;
+use core::iter::FusedIterator;
+
+/// An iterator over the components of a path as `&str`, returned by `OsPath::components()` and `PurePath::components()`.
+#[derive(Clone, Debug)]
+pub struct Components<'a> {
+    inner: core::slice::Iter<'a, String>,
+}
+
+impl<'a> Components<'a> {
+    pub(crate) fn new(components: &'a [String]) -> Self {
+        Self {
+            inner: components.iter(),
+        }
+    }
+}
+
+impl<'a> Iterator for Components<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        self.inner.next().map(String::as_str)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Components<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(String::as_str)
+    }
+}
+
+impl ExactSizeIterator for Components<'_> {}
+
+impl FusedIterator for Components<'_> {}
+
+/// Component Methods
+#[cfg(feature = "std")]
+impl OsPath {
+    /// Returns an iterator over the components of the path, from the first to the name. The Unix root is not a
+    /// component, while a Windows drive or UNC share is the first one. `..` components are returned as they are.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// let path = OsPath::from("/home/user/notes.txt");
+    /// assert_eq!(path.components().collect::<Vec<_>>(), ["home", "user", "notes.txt"]);
+    /// assert_eq!(path.components().next_back(), Some("notes.txt"));
+    /// assert_eq!(path.components().rev().nth(1), Some("user"));
+    /// assert_eq!(path.components().len(), 3);
+    /// ```
+    pub fn components(&self) -> Components<'_> {
+        Components::new(&self.components)
+    }
+
+    /// Consumes the path and returns its components as owned strings, in the same order as `components()`.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// let names: Vec<String> = OsPath::from("src/../lib.rs").into_components().collect();
+    /// assert_eq!(names, ["src", "..", "lib.rs"]);
+    /// ```
+    pub fn into_components(self) -> std::vec::IntoIter<String> {
+        self.components.into_iter()
+    }
+}
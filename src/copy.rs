@@ -0,0 +1,339 @@
+//! Recursive copying of directory trees with progress reporting.
+
+use crate::error::IoResultExt;
+use crate::filter::PathFilter;
+use crate::fs::{Budget, FileId, LinkCycle, TraversalLimits};
+use crate::OsPath;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+const CHUNK: usize = 64 * 1024;
+
+/// What `copy_tree_to()` does when a file already exists at the destination.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Overwrite {
+    /// Fail with `io::ErrorKind::AlreadyExists`.
+    #[default]
+    Error,
+    /// Leave the existing file alone.
+    Skip,
+    /// Replace the existing file.
+    Always,
+    /// Replace the existing file only if the source was modified more recently.
+    IfNewer,
+}
+
+/// What `copy_tree_to()` does with symbolic links in the source tree.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Symlinks {
+    /// Copy what the link points to.
+    #[default]
+    Follow,
+    /// Recreate the link itself, pointing at the same target.
+    Preserve,
+    /// Leave links out of the copy.
+    Skip,
+}
+
+/// Controls the behavior of `copy_tree_to()`.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct CopyOptions {
+    filter: Option<PathFilter>,
+    overwrite: Overwrite,
+    symlinks: Symlinks,
+    limits: TraversalLimits,
+}
+
+impl CopyOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only copies entries matching the filter, relative to the source directory. Directories the filter does not
+    /// rule out entirely are created so their matching contents have somewhere to go.
+    pub fn filter(mut self, filter: PathFilter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Sets what happens when a file already exists at the destination.
+    pub fn overwrite(mut self, overwrite: Overwrite) -> Self {
+        self.overwrite = overwrite;
+        self
+    }
+
+    /// Sets how symbolic links are handled.
+    pub fn symlinks(mut self, symlinks: Symlinks) -> Self {
+        self.symlinks = symlinks;
+        self
+    }
+
+    /// Refuses to copy a source tree that goes past one of the limits. The limits are checked before anything is
+    /// copied, and count every source entry looked at, including ones the filter leaves out.
+    pub fn limits(mut self, limits: TraversalLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+}
+
+enum Item {
+    Dir(OsPath),
+    File(OsPath, OsPath, u64),
+    Link(OsPath, PathBuf),
+}
+
+/// What `plan_copy()` gathers and tracks on its way through the source tree.
+struct Plan {
+    items: Vec<Item>,
+    budget: Budget,
+    ancestors: Vec<(FileId, OsPath)>,
+}
+
+/// Copy Methods
+impl OsPath {
+    /// Copies the contents of this directory into `dest`, creating it if needed, and returns the number of bytes
+    /// copied.
+    ///
+    /// The sizes of all files are gathered before copying starts, and `progress` is called as each file is copied
+    /// with the file's source path, the bytes done so far, and the total bytes. Skipped files count as done. With
+    /// `Overwrite::Error`, existing targets are reported before anything is copied. When following symbolic links, a
+    /// link leading back to a directory being copied fails the copy with a `LinkCycle` error before anything is copied.
+    /// ```rust
+    /// use os_path::{CopyOptions, OsPath};
+    ///
+    /// let tmp = OsPath::from(std::env::temp_dir()).join("os_path_copy_doc/");
+    /// std::fs::create_dir_all(tmp.join("src/sub/")).unwrap();
+    /// std::fs::write(tmp.join("src/a.txt"), "hello").unwrap();
+    /// std::fs::write(tmp.join("src/sub/b.txt"), "world!").unwrap();
+    ///
+    /// let mut last = (0, 0);
+    /// let copied = tmp
+    ///     .join("src/")
+    ///     .copy_tree_to(tmp.join("dest/"), &CopyOptions::new(), |_, done, total| last = (done, total))
+    ///     .unwrap();
+    ///
+    /// assert_eq!(copied, 11);
+    /// assert_eq!(last, (11, 11));
+    /// assert_eq!(std::fs::read_to_string(tmp.join("dest/sub/b.txt")).unwrap(), "world!");
+    /// # std::fs::remove_dir_all(&tmp).unwrap();
+    /// ```
+    pub fn copy_tree_to<P, F>(
+        &self,
+        dest: P,
+        options: &CopyOptions,
+        mut progress: F,
+    ) -> io::Result<u64>
+    where
+        P: AsRef<Path>,
+        F: FnMut(&OsPath, u64, u64),
+    {
+        let mut dest = OsPath::from(dest.as_ref());
+        dest.force_dir();
+        let mut plan = Plan {
+            items: vec![Item::Dir(dest.clone())],
+            budget: Budget::new(options.limits),
+            ancestors: vec![(FileId::of(self)?, self.clone())],
+        };
+        self.plan_copy(self, &dest, 0, options, &mut plan)?;
+        let items = plan.items;
+        let total = items
+            .iter()
+            .map(|item| match item {
+                Item::File(_, _, len) => *len,
+                _ => 0,
+            })
+            .sum();
+        if options.overwrite == Overwrite::Error {
+            for item in &items {
+                if let Item::File(_, to, _) | Item::Link(to, _) = item {
+                    if std::fs::symlink_metadata(to).is_ok() {
+                        return Err(Self::already_exists(to));
+                    }
+                }
+            }
+        }
+
+        let mut done = 0;
+        let mut copied = 0;
+        for item in items {
+            match item {
+                Item::Dir(to) => {
+                    std::fs::create_dir_all(&to).with_path_context("create directory", &to)?
+                }
+                Item::File(from, to, len) => {
+                    if Self::should_write(&from, &to, options.overwrite)? {
+                        copied += Self::copy_file(&from, &to, |n| {
+                            done += n;
+                            progress(&from, done, total);
+                        })?;
+                    } else {
+                        done += len;
+                        progress(&from, done, total);
+                    }
+                }
+                Item::Link(to, target) => {
+                    if std::fs::symlink_metadata(&to).is_ok() {
+                        match options.overwrite {
+                            Overwrite::Skip => continue,
+                            Overwrite::Error => return Err(Self::already_exists(&to)),
+                            Overwrite::Always | Overwrite::IfNewer => std::fs::remove_file(&to)?,
+                        }
+                    }
+                    make_symlink(&target, &to)?;
+                }
+            }
+        }
+        Ok(copied)
+    }
+
+    fn plan_copy(
+        &self,
+        root: &OsPath,
+        dest: &OsPath,
+        depth: usize,
+        options: &CopyOptions,
+        plan: &mut Plan,
+    ) -> io::Result<()> {
+        let mut entries = Vec::new();
+        for entry in std::fs::read_dir(self).with_path_context("read directory", self)? {
+            entries.push(entry.with_path_context("read directory", self)?);
+        }
+        entries.sort_by_key(|e| e.file_name());
+
+        for entry in entries {
+            let from = self.join(entry.file_name());
+            let link =
+                std::fs::symlink_metadata(&from).with_path_context("read metadata of", &from)?;
+            if link.file_type().is_symlink() {
+                match options.symlinks {
+                    Symlinks::Skip => {
+                        plan.budget.charge(depth + 1, 0)?;
+                        continue;
+                    }
+                    Symlinks::Preserve => {
+                        plan.budget.charge(depth + 1, 0)?;
+                        let relative = from.tail_from(root.components.len());
+                        if options
+                            .filter
+                            .as_ref()
+                            .is_none_or(|f| f.matches_at(&relative, depth + 1))
+                        {
+                            let to = dest.join(entry.file_name());
+                            let target =
+                                std::fs::read_link(&from).with_path_context("read link", &from)?;
+                            plan.items.push(Item::Link(to, target));
+                        }
+                        continue;
+                    }
+                    Symlinks::Follow => {}
+                }
+            }
+            let meta = std::fs::metadata(&from).with_path_context("read metadata of", &from)?;
+            plan.budget
+                .charge(depth + 1, if meta.is_dir() { 0 } else { meta.len() })?;
+            let mut from = from;
+            let mut to = dest.join(entry.file_name());
+            if meta.is_dir() {
+                from.force_dir();
+                to.force_dir();
+            }
+            let relative = from.tail_from(root.components.len());
+            let (copied, descend) = match &options.filter {
+                Some(filter) if meta.is_dir() => (
+                    filter.admits(&relative, depth + 1),
+                    filter.descends_at(&relative, depth + 1),
+                ),
+                Some(filter) => (filter.matches_at(&relative, depth + 1), false),
+                None => (true, meta.is_dir()),
+            };
+            if meta.is_dir() {
+                if copied {
+                    plan.items.push(Item::Dir(to.clone()));
+                }
+                if descend {
+                    plan.ancestors.truncate(depth + 1);
+                    let id = FileId::of(&from)?;
+                    if let Some((_, target)) = plan.ancestors.iter().find(|(seen, _)| *seen == id) {
+                        return Err(LinkCycle::into_io(from, target.clone()));
+                    }
+                    plan.ancestors.push((id, from.clone()));
+                    from.plan_copy(root, &to, depth + 1, options, plan)?;
+                }
+            } else if copied {
+                plan.items.push(Item::File(from, to, meta.len()));
+            }
+        }
+        Ok(())
+    }
+
+    fn should_write(from: &OsPath, to: &OsPath, overwrite: Overwrite) -> io::Result<bool> {
+        let existing = match std::fs::metadata(to) {
+            Ok(meta) => meta,
+            Err(_) => return Ok(true),
+        };
+        match overwrite {
+            Overwrite::Error => Err(Self::already_exists(to)),
+            Overwrite::Skip => Ok(false),
+            Overwrite::Always => Ok(true),
+            Overwrite::IfNewer => {
+                let source = std::fs::metadata(from)?.modified()?;
+                Ok(existing.modified().is_ok_and(|target| source > target))
+            }
+        }
+    }
+
+    fn copy_file<F: FnMut(u64)>(from: &OsPath, to: &OsPath, mut advance: F) -> io::Result<u64> {
+        let mut reader = File::open(from).with_path_context("open", from)?;
+        let mut writer = File::create(to).with_path_context("create", to)?;
+        let mut buf = vec![0; CHUNK];
+        let mut copied = 0;
+        loop {
+            let n = match reader.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e).with_path_context("read", from),
+            };
+            writer.write_all(&buf[..n]).with_path_context("write", to)?;
+            copied += n as u64;
+            advance(n as u64);
+        }
+        writer.flush()?;
+        std::fs::set_permissions(to, reader.metadata()?.permissions())?;
+        Ok(copied)
+    }
+
+    fn already_exists(path: &OsPath) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", path),
+        )
+    }
+}
+
+/// Creates a symbolic link at `link` pointing to `target`. On Windows the link is made a directory link when the
+/// target, resolved against the link's directory, is a directory.
+#[cfg(unix)]
+pub(crate) fn make_symlink(target: &Path, link: &OsPath) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link).with_path_context("create link", link)
+}
+
+#[cfg(windows)]
+pub(crate) fn make_symlink(target: &Path, link: &OsPath) -> io::Result<()> {
+    let resolved = link.path.parent().map(|p| p.join(target));
+    if resolved.is_some_and(|p| p.is_dir()) {
+        std::os::windows::fs::symlink_dir(target, link)
+    } else {
+        std::os::windows::fs::symlink_file(target, link)
+    }
+    .with_path_context("create link", link)
+}
+
+#[cfg(not(any(unix, windows)))]
+pub(crate) fn make_symlink(_target: &Path, _link: &OsPath) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "symbolic links are not supported on this platform",
+    ))
+}
@@ -0,0 +1,99 @@
+//! Choosing what happens to bytes in a platform path that are not valid UTF-8.
+
+use crate::{OsPath, ParseError};
+use std::ffi::OsStr;
+use std::fmt::Write;
+
+/// What `OsPath::from_os_str_with()` does with bytes that are not valid UTF-8.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum InvalidBytes {
+    /// Keep the bytes exactly, as `OsPath::from()` does, so that `as_os_str()` gives them back.
+    #[default]
+    Keep,
+    /// Replace each invalid sequence with U+FFFD. Different names can become the same.
+    Replace,
+    /// Write each invalid byte as `%` and two upper-case hex digits, such as `%80`, so that different names stay
+    /// different. Valid `%` characters are left alone, so a name that already reads `%80` is not told apart.
+    PercentEscape,
+    /// Fail with `ParseError::InvalidUtf8`.
+    Error,
+}
+
+/// Options for turning platform strings into paths with `OsPath::from_os_str_with()`.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct DecodeOptions {
+    invalid: InvalidBytes,
+}
+
+impl DecodeOptions {
+    /// Creates options that keep invalid bytes exactly.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets what to do with bytes that are not valid UTF-8.
+    pub fn invalid(mut self, policy: InvalidBytes) -> Self {
+        self.invalid = policy;
+        self
+    }
+}
+
+/// Decoding Methods
+impl OsPath {
+    /// Creates a path from a platform string, decoding any bytes that are not valid UTF-8 as the options say. The
+    /// bytes are those of the platform's own encoding, which on Windows is WTF-8.
+    /// ```rust
+    /// #[cfg(unix)]
+    /// {
+    /// use os_path::{DecodeOptions, InvalidBytes, OsPath, ParseError};
+    /// use std::ffi::OsStr;
+    /// use std::os::unix::ffi::OsStrExt;
+    ///
+    /// let raw = OsStr::from_bytes(b"/data/caf\xe9.txt");
+    ///
+    /// let kept = OsPath::from_os_str_with(raw, &DecodeOptions::new()).unwrap();
+    /// assert_eq!(kept.as_os_str(), raw);
+    ///
+    /// let lossy = DecodeOptions::new().invalid(InvalidBytes::Replace);
+    /// let lossy = OsPath::from_os_str_with(raw, &lossy).unwrap();
+    /// assert_ne!(lossy.as_os_str(), raw);
+    /// assert_eq!(lossy.to_string(), "/data/caf\u{FFFD}.txt");
+    ///
+    /// let escaped = DecodeOptions::new().invalid(InvalidBytes::PercentEscape);
+    /// let escaped = OsPath::from_os_str_with(raw, &escaped).unwrap();
+    /// assert_eq!(escaped.to_string(), "/data/caf%E9.txt");
+    ///
+    /// let strict = DecodeOptions::new().invalid(InvalidBytes::Error);
+    /// assert_eq!(OsPath::from_os_str_with(raw, &strict), Err(ParseError::InvalidUtf8(9)));
+    /// }
+    /// ```
+    pub fn from_os_str_with<S: AsRef<OsStr>>(
+        path: S,
+        options: &DecodeOptions,
+    ) -> Result<Self, ParseError> {
+        if options.invalid == InvalidBytes::Keep {
+            return Ok(Self::build_self(path.as_ref()));
+        }
+        let bytes = path.as_ref().as_encoded_bytes();
+        let mut decoded = String::with_capacity(bytes.len());
+        for chunk in bytes.utf8_chunks() {
+            decoded.push_str(chunk.valid());
+            let invalid = chunk.invalid();
+            if invalid.is_empty() {
+                continue;
+            }
+            match options.invalid {
+                InvalidBytes::Keep | InvalidBytes::Replace => {
+                    decoded.push(char::REPLACEMENT_CHARACTER)
+                }
+                InvalidBytes::PercentEscape => {
+                    for b in invalid {
+                        let _ = write!(decoded, "%{:02X}", b);
+                    }
+                }
+                InvalidBytes::Error => return Err(ParseError::InvalidUtf8(decoded.len())),
+            }
+        }
+        Ok(Self::build_from_str(&decoded))
+    }
+}
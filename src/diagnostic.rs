@@ -0,0 +1,184 @@
+//! `miette` diagnostics for the crate's errors, so command line tools can report a bad path by pointing into it.
+
+use crate::{
+    BuildError, FileUrlError, GlobError, LimitExceeded, LinkCycle, OsPath, OsPathError, ParseError,
+    PathError, PathEscape, PathspecError, PrefixError, RelIdError, ResolveError,
+};
+use miette::{Diagnostic, LabeledSpan, MietteError, SourceCode, SourceSpan, SpanContents};
+use std::fmt;
+
+/// An `OsPath` is its own source code, as the platform writes it, so a diagnostic can underline part of it.
+impl SourceCode for OsPath {
+    fn read_span<'a>(
+        &'a self,
+        span: &SourceSpan,
+        context_lines_before: usize,
+        context_lines_after: usize,
+    ) -> Result<Box<dyn SpanContents<'a> + 'a>, MietteError> {
+        self.path.as_os_str().as_encoded_bytes().read_span(
+            span,
+            context_lines_before,
+            context_lines_after,
+        )
+    }
+}
+
+/// Returns the span of the last component of the path as the platform writes it, or of the whole path if it has no
+/// name, such as a root.
+fn last_component(path: &OsPath) -> SourceSpan {
+    let len = path.path.as_os_str().len();
+    match path.path.file_name() {
+        Some(name) => (len - name.len(), name.len()).into(),
+        None => (0, len).into(),
+    }
+}
+
+fn code<'a>(code: &'static str) -> Option<Box<dyn fmt::Display + 'a>> {
+    Some(Box::new(code))
+}
+
+fn help<'a>(help: &'static str) -> Option<Box<dyn fmt::Display + 'a>> {
+    Some(Box::new(help))
+}
+
+impl Diagnostic for PathError {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        code("os_path::io")
+    }
+
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        Some(&self.path)
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        let label = LabeledSpan::at(last_component(&self.path), self.source.kind().to_string());
+        Some(Box::new(std::iter::once(label)))
+    }
+}
+
+impl Diagnostic for LinkCycle {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        code("os_path::link_cycle")
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        help("stop following symbolic links, or remove the link")
+    }
+
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        Some(self.path())
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        let target = format!("leads back to `{}`", self.target());
+        let label = LabeledSpan::at(last_component(self.path()), target);
+        Some(Box::new(std::iter::once(label)))
+    }
+}
+
+impl Diagnostic for GlobError {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        code("os_path::glob")
+    }
+
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        Some(&self.pattern)
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        let component = self.component.as_deref()?;
+        let mut offset = 0;
+        for part in self.pattern.split('/') {
+            if part == component {
+                let label = LabeledSpan::at((offset, part.len()), self.reason);
+                return Some(Box::new(std::iter::once(label)));
+            }
+            offset += part.len() + 1;
+        }
+        None
+    }
+}
+
+impl Diagnostic for ParseError {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        code("os_path::parse")
+    }
+}
+
+impl Diagnostic for PrefixError {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        code("os_path::prefix")
+    }
+}
+
+impl Diagnostic for BuildError {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        code("os_path::build")
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        match self {
+            BuildError::MissingDrive => help("start the path with `drive()` instead of `root()`"),
+            _ => None,
+        }
+    }
+}
+
+impl Diagnostic for FileUrlError {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        code("os_path::file_url")
+    }
+}
+
+impl Diagnostic for ResolveError {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        code("os_path::resolve")
+    }
+}
+
+impl Diagnostic for PathspecError {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        code("os_path::pathspec")
+    }
+}
+
+impl Diagnostic for LimitExceeded {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        code("os_path::limit_exceeded")
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        help("raise the limit with `TraversalLimits`")
+    }
+}
+
+impl Diagnostic for OsPathError {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        code("os_path::invalid")
+    }
+}
+
+impl Diagnostic for PathEscape {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        code("os_path::escape")
+    }
+
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        Some(self.path())
+    }
+}
+
+impl Diagnostic for RelIdError {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        code("os_path::rel_id")
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        match self {
+            RelIdError::NotCanonical => {
+                help("write the id with single `/` separators and no trailing `/`")
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,252 @@
+//! EditorConfig section names matched against paths below the directory holding the `.editorconfig` file.
+
+use crate::glob::GlobError;
+use crate::OsPath;
+use regex::Regex;
+use std::fmt;
+
+/// A compiled EditorConfig section name, the text between the brackets of a header such as `[*.{js,py}]`.
+///
+/// `*` matches any run of characters but `/`, `**` matches any run including `/`, `?` matches one character but `/`,
+/// `[abc]`, `[a-z]` and `[!abc]` match one character in or not in the class, `{js,py}` matches any of its
+/// alternatives, `{1..10}` matches a whole number in the range, and `\` escapes the character after it. A name
+/// without a `/` matches file names at any depth; one with a `/` is matched from the directory of the
+/// `.editorconfig` file, with or without a leading `/`.
+/// ```rust
+/// use os_path::{EditorConfigGlob, OsPath};
+///
+/// let section = EditorConfigGlob::new("*.{js,py}").unwrap();
+/// assert!(section.matches(&OsPath::from("app.js")));
+/// assert!(section.matches(&OsPath::from("tools/build/gen.py")));
+/// assert!(!section.matches(&OsPath::from("style.css")));
+///
+/// let section = EditorConfigGlob::new("lib/**.rs").unwrap();
+/// assert!(section.matches(&OsPath::from("lib/a/b.rs")));
+/// assert!(!section.matches(&OsPath::from("src/lib/b.rs")));
+///
+/// let section = EditorConfigGlob::new("test{1..12}.txt").unwrap();
+/// assert!(section.matches(&OsPath::from("test7.txt")));
+/// assert!(!section.matches(&OsPath::from("test13.txt")));
+/// ```
+#[derive(Clone, Debug)]
+pub struct EditorConfigGlob {
+    section: String,
+    regex: Regex,
+    ranges: Vec<(i64, i64)>,
+}
+
+impl EditorConfigGlob {
+    /// Compiles a section name, given without the brackets of its header.
+    pub fn new(section: &str) -> Result<Self, GlobError> {
+        let chars: Vec<char> = section.chars().collect();
+        let mut ranges = Vec::new();
+        let mut body = String::new();
+        translate(&chars, &mut body, &mut ranges);
+        let anchored = if chars.contains(&'/') {
+            format!("(?s)^{}$", body.strip_prefix('/').unwrap_or(&body))
+        } else {
+            format!("(?s)^(?:.*/)?{}$", body)
+        };
+        let regex =
+            Regex::new(&anchored).map_err(|_| GlobError::new(section, "pattern is too large"))?;
+        Ok(Self {
+            section: section.to_string(),
+            regex,
+            ranges,
+        })
+    }
+
+    /// Returns the section name as it was written.
+    pub fn as_str(&self) -> &str {
+        &self.section
+    }
+
+    /// Returns true if the path, relative to the directory of the `.editorconfig` file, is matched. Absolute paths
+    /// are never matched; use `matches_from()` for them.
+    pub fn matches(&self, path: &OsPath) -> bool {
+        if path.absolute {
+            return false;
+        }
+        let text = path.components.join("/");
+        let Some(captures) = self.regex.captures(&text) else {
+            return false;
+        };
+        self.ranges.iter().enumerate().all(|(i, (lo, hi))| {
+            captures
+                .get(i + 1)
+                .and_then(|n| n.as_str().parse::<i64>().ok())
+                .is_none_or(|n| (*lo..=*hi).contains(&n))
+        })
+    }
+
+    /// Returns true if the path is below `root`, the directory of the `.editorconfig` file, and is matched.
+    /// ```rust
+    /// #[cfg(unix)]
+    /// {
+    /// use os_path::{EditorConfigGlob, OsPath};
+    ///
+    /// let section = EditorConfigGlob::new("/docs/*.md").unwrap();
+    /// let root = OsPath::from("/home/me/project/");
+    /// assert!(section.matches_from(&root, &OsPath::from("/home/me/project/docs/intro.md")));
+    /// assert!(!section.matches_from(&root, &OsPath::from("/home/me/other/docs/intro.md")));
+    /// }
+    /// ```
+    pub fn matches_from(&self, root: &OsPath, path: &OsPath) -> bool {
+        path.strip_prefix(root).is_ok_and(|p| self.matches(&p))
+    }
+}
+
+impl PartialEq for EditorConfigGlob {
+    fn eq(&self, other: &Self) -> bool {
+        self.section == other.section
+    }
+}
+
+impl Eq for EditorConfigGlob {}
+
+impl fmt::Display for EditorConfigGlob {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.section)
+    }
+}
+
+/// Appends the regular expression for the pattern. Each numeric range becomes a capture group, recorded in order.
+fn translate(pattern: &[char], out: &mut String, ranges: &mut Vec<(i64, i64)>) {
+    let mut i = 0;
+    while i < pattern.len() {
+        let c = pattern[i];
+        i += 1;
+        match c {
+            '*' if pattern.get(i) == Some(&'*') => {
+                i += 1;
+                out.push_str(".*");
+            }
+            '*' => out.push_str("[^/]*"),
+            '?' => out.push_str("[^/]"),
+            '\\' if i < pattern.len() => {
+                push_literal(out, pattern[i]);
+                i += 1;
+            }
+            '[' => match class_end(&pattern[i..]) {
+                Some(len) => {
+                    let class = &pattern[i..i + len];
+                    i += len + 1;
+                    let (negated, class) = match class.split_first() {
+                        Some(('!', rest)) | Some(('^', rest)) => (true, rest),
+                        _ => (false, class),
+                    };
+                    out.push_str(if negated { "[^/" } else { "[" });
+                    let mut j = 0;
+                    while j < class.len() {
+                        match class[j] {
+                            '-' if j > 0 && j + 1 < class.len() => out.push('-'),
+                            '\\' if j + 1 < class.len() => {
+                                j += 1;
+                                out.push_str(&regex::escape(&class[j].to_string()));
+                            }
+                            c => out.push_str(&regex::escape(&c.to_string())),
+                        }
+                        j += 1;
+                    }
+                    out.push(']');
+                }
+                None => push_literal(out, c),
+            },
+            '{' => match brace_end(&pattern[i..]) {
+                Some(len) => {
+                    let inner = &pattern[i..i + len];
+                    i += len + 1;
+                    let text: String = inner.iter().collect();
+                    let alternatives = split_alternatives(inner);
+                    if let Some(range) = parse_range(&text) {
+                        ranges.push(range);
+                        out.push_str("([+-]?[0-9]+)");
+                    } else if alternatives.len() > 1 {
+                        out.push_str("(?:");
+                        for (n, alternative) in alternatives.into_iter().enumerate() {
+                            if n > 0 {
+                                out.push('|');
+                            }
+                            translate(alternative, out, ranges);
+                        }
+                        out.push(')');
+                    } else {
+                        // A brace without a comma or range is taken literally, as in `{single}`.
+                        push_literal(out, '{');
+                        translate(inner, out, ranges);
+                        push_literal(out, '}');
+                    }
+                }
+                None => push_literal(out, c),
+            },
+            c => push_literal(out, c),
+        }
+    }
+}
+
+fn push_literal(out: &mut String, c: char) {
+    out.push_str(&regex::escape(c.encode_utf8(&mut [0; 4])));
+}
+
+/// Returns the length of a character class after its `[`, up to its `]`, or None if it is not closed or would span
+/// a `/`.
+fn class_end(pattern: &[char]) -> Option<usize> {
+    let mut i = usize::from(matches!(pattern.first(), Some('!') | Some('^')));
+    let start = i;
+    while let Some(&c) = pattern.get(i) {
+        match c {
+            ']' if i > start => return Some(i),
+            '/' => return None,
+            '\\' => i += 2,
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+/// Returns the length of a brace group after its `{`, up to its matching `}`, or None if it is not closed.
+fn brace_end(pattern: &[char]) -> Option<usize> {
+    let mut depth = 0;
+    let mut i = 0;
+    while let Some(&c) = pattern.get(i) {
+        match c {
+            '\\' => i += 1,
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some(i),
+            '}' => depth -= 1,
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Splits the inside of a brace group at the commas that are not nested in another group.
+fn split_alternatives(inner: &[char]) -> Vec<&[char]> {
+    let mut alternatives = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    let mut i = 0;
+    while i < inner.len() {
+        match inner[i] {
+            '\\' => i += 1,
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                alternatives.push(&inner[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    alternatives.push(&inner[start..]);
+    alternatives
+}
+
+/// Parses a numeric range such as `1..10` or `-5..5`, in either order.
+fn parse_range(text: &str) -> Option<(i64, i64)> {
+    let (lo, hi) = text.split_once("..")?;
+    let (lo, hi) = (lo.parse::<i64>().ok()?, hi.parse::<i64>().ok()?);
+    Some((lo.min(hi), lo.max(hi)))
+}
@@ -0,0 +1,507 @@
+//! Paths taken from environment variables.
+
+use crate::OsPath;
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::OsString;
+use std::path::Path;
+
+/// A source of environment variables, so code that reads them can be tested without changing the real process
+/// environment.
+///
+/// `ProcessEnv` reads the real environment, and maps of names to values serve as fakes.
+/// ```rust
+/// #[cfg(unix)]
+/// {
+/// use os_path::OsPath;
+/// use std::collections::HashMap;
+///
+/// let env = HashMap::from([("HOME".to_string(), "/home/tester".to_string())]);
+/// assert_eq!(OsPath::from("~/notes.txt").expand_user_in(&env), OsPath::from("/home/tester/notes.txt"));
+/// }
+/// ```
+pub trait Environment {
+    /// Returns the value of the variable, or None if it is not set.
+    fn var_os(&self, name: &str) -> Option<OsString>;
+}
+
+/// The environment of the current process.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ProcessEnv;
+
+impl Environment for ProcessEnv {
+    fn var_os(&self, name: &str) -> Option<OsString> {
+        std::env::var_os(name)
+    }
+}
+
+impl Environment for HashMap<String, String> {
+    fn var_os(&self, name: &str) -> Option<OsString> {
+        self.get(name).map(OsString::from)
+    }
+}
+
+impl Environment for BTreeMap<String, String> {
+    fn var_os(&self, name: &str) -> Option<OsString> {
+        self.get(name).map(OsString::from)
+    }
+}
+
+impl Environment for EnvPaths {
+    fn var_os(&self, name: &str) -> Option<OsString> {
+        self.var(name).cloned()
+    }
+}
+
+/// A snapshot of the paths named by the environment: `PATH`, the home and temporary directories, and the XDG base
+/// directories.
+///
+/// Values are read once, when captured or refreshed, so the rest of an application sees one consistent view. Any
+/// variable can be overridden, which is kept across refreshes; this makes the snapshot easy to inject in tests.
+/// ```rust
+/// #[cfg(unix)]
+/// {
+/// use os_path::{EnvPaths, OsPath};
+///
+/// let mut env = EnvPaths::capture();
+/// env.set_override("HOME", "/home/tester").unset_override("XDG_CONFIG_HOME");
+///
+/// assert_eq!(env.home(), Some(OsPath::from("/home/tester/")));
+/// assert_eq!(env.config_home(), Some(OsPath::from("/home/tester/.config/")));
+/// }
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct EnvPaths {
+    overrides: BTreeMap<String, Option<OsString>>,
+    vars: BTreeMap<String, OsString>,
+}
+
+const VARS: [&str; 15] = [
+    "PATH",
+    "HOME",
+    "USERPROFILE",
+    "TMPDIR",
+    "TMP",
+    "TEMP",
+    "XDG_CONFIG_HOME",
+    "XDG_DATA_HOME",
+    "XDG_CACHE_HOME",
+    "XDG_STATE_HOME",
+    "XDG_RUNTIME_DIR",
+    "XDG_CONFIG_DIRS",
+    "XDG_DATA_DIRS",
+    "APPDATA",
+    "LOCALAPPDATA",
+];
+
+impl EnvPaths {
+    /// Reads the current process environment.
+    pub fn capture() -> Self {
+        Self::capture_from(&ProcessEnv)
+    }
+
+    /// Reads the given environment.
+    pub fn capture_from(env: &dyn Environment) -> Self {
+        let mut paths = Self {
+            overrides: BTreeMap::new(),
+            vars: BTreeMap::new(),
+        };
+        paths.refresh_from(env);
+        paths
+    }
+
+    /// Reads the process environment again, keeping any overrides.
+    pub fn refresh(&mut self) -> &mut Self {
+        self.refresh_from(&ProcessEnv)
+    }
+
+    /// Reads the given environment again, keeping any overrides.
+    pub fn refresh_from(&mut self, env: &dyn Environment) -> &mut Self {
+        self.vars.clear();
+        for name in VARS {
+            if let Some(value) = env.var_os(name) {
+                self.vars.insert(name.to_string(), value);
+            }
+        }
+        self
+    }
+
+    /// Uses `value` for the variable instead of what the environment holds.
+    pub fn set_override<V: Into<OsString>>(&mut self, name: &str, value: V) -> &mut Self {
+        self.overrides.insert(name.to_string(), Some(value.into()));
+        self
+    }
+
+    /// Treats the variable as unset, whatever the environment holds.
+    pub fn unset_override(&mut self, name: &str) -> &mut Self {
+        self.overrides.insert(name.to_string(), None);
+        self
+    }
+
+    /// Removes every override.
+    pub fn clear_overrides(&mut self) -> &mut Self {
+        self.overrides.clear();
+        self
+    }
+
+    /// Returns the value of a variable as the snapshot sees it, overrides included.
+    pub fn var(&self, name: &str) -> Option<&OsString> {
+        match self.overrides.get(name) {
+            Some(value) => value.as_ref(),
+            None => self.vars.get(name),
+        }
+        .filter(|v| !v.is_empty())
+    }
+
+    /// Returns the directories listed in `PATH`, in order.
+    pub fn path(&self) -> Vec<OsPath> {
+        self.dir_list("PATH")
+    }
+
+    /// Returns the home directory from `HOME`, or `USERPROFILE` on Windows.
+    pub fn home(&self) -> Option<OsPath> {
+        let home = self.dir("HOME");
+        #[cfg(windows)]
+        let home = home.or_else(|| self.dir("USERPROFILE"));
+        home
+    }
+
+    /// Returns the temporary directory from `TMPDIR`, `TMP`, or `TEMP`, falling back to the platform default.
+    pub fn temp(&self) -> OsPath {
+        self.dir("TMPDIR")
+            .or_else(|| self.dir("TMP"))
+            .or_else(|| self.dir("TEMP"))
+            .unwrap_or_else(|| Self::as_dir(std::env::temp_dir().into()))
+    }
+
+    /// Returns `XDG_CONFIG_HOME`, defaulting to `~/.config/`.
+    pub fn config_home(&self) -> Option<OsPath> {
+        self.xdg_dir("XDG_CONFIG_HOME", ".config")
+    }
+
+    /// Returns `XDG_DATA_HOME`, defaulting to `~/.local/share/`.
+    pub fn data_home(&self) -> Option<OsPath> {
+        self.xdg_dir("XDG_DATA_HOME", ".local/share")
+    }
+
+    /// Returns `XDG_CACHE_HOME`, defaulting to `~/.cache/`.
+    pub fn cache_home(&self) -> Option<OsPath> {
+        self.xdg_dir("XDG_CACHE_HOME", ".cache")
+    }
+
+    /// Returns `XDG_STATE_HOME`, defaulting to `~/.local/state/`.
+    pub fn state_home(&self) -> Option<OsPath> {
+        self.xdg_dir("XDG_STATE_HOME", ".local/state")
+    }
+
+    /// Returns `XDG_RUNTIME_DIR`, which has no default.
+    pub fn runtime_dir(&self) -> Option<OsPath> {
+        self.dir("XDG_RUNTIME_DIR").filter(|p| p.absolute)
+    }
+
+    /// Returns the directories listed in `XDG_CONFIG_DIRS`, defaulting to `/etc/xdg/`.
+    pub fn config_dirs(&self) -> Vec<OsPath> {
+        self.xdg_dirs("XDG_CONFIG_DIRS", &["/etc/xdg"])
+    }
+
+    /// Returns the directories listed in `XDG_DATA_DIRS`, defaulting to `/usr/local/share/` and `/usr/share/`.
+    pub fn data_dirs(&self) -> Vec<OsPath> {
+        self.xdg_dirs("XDG_DATA_DIRS", &["/usr/local/share", "/usr/share"])
+    }
+
+    fn as_dir(mut path: OsPath) -> OsPath {
+        path.force_dir();
+        path
+    }
+
+    fn dir(&self, name: &str) -> Option<OsPath> {
+        self.var(name)
+            .map(|v| Self::as_dir(OsPath::from(Path::new(v))))
+    }
+
+    fn dir_list(&self, name: &str) -> Vec<OsPath> {
+        match self.var(name) {
+            Some(value) => std::env::split_paths(value)
+                .filter(|p| !p.as_os_str().is_empty())
+                .map(|p| Self::as_dir(p.into()))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// XDG variables holding relative paths are invalid and ignored, as the specification requires.
+    fn xdg_dir(&self, name: &str, default: &str) -> Option<OsPath> {
+        match self.dir(name).filter(|p| p.absolute) {
+            Some(dir) => Some(dir),
+            None => self.home().map(|home| Self::as_dir(home.join(default))),
+        }
+    }
+
+    fn xdg_dirs(&self, name: &str, defaults: &[&str]) -> Vec<OsPath> {
+        let dirs: Vec<OsPath> = self
+            .dir_list(name)
+            .into_iter()
+            .filter(|p| p.absolute)
+            .collect();
+        if dirs.is_empty() {
+            defaults
+                .iter()
+                .map(|d| Self::as_dir(OsPath::from(*d)))
+                .collect()
+        } else {
+            dirs
+        }
+    }
+}
+
+/// Environment Methods
+impl OsPath {
+    /// Returns the home directory of the current user. See `home_dir_in()`.
+    pub fn home_dir() -> Option<Self> {
+        Self::home_dir_in(&ProcessEnv)
+    }
+
+    /// Returns the home directory from `HOME`, or `USERPROFILE` on Windows, in the given environment.
+    /// ```rust
+    /// #[cfg(unix)]
+    /// {
+    /// use os_path::OsPath;
+    /// use std::collections::HashMap;
+    ///
+    /// let env = HashMap::from([("HOME".to_string(), "/home/tester".to_string())]);
+    /// assert_eq!(OsPath::home_dir_in(&env), Some(OsPath::from("/home/tester/")));
+    /// assert_eq!(OsPath::home_dir_in(&HashMap::new()), None);
+    /// }
+    /// ```
+    pub fn home_dir_in(env: &dyn Environment) -> Option<Self> {
+        EnvPaths::capture_from(env).home()
+    }
+
+    /// Returns the directory for user configuration files. See `config_dir_in()`.
+    pub fn config_dir() -> Option<Self> {
+        Self::config_dir_in(&ProcessEnv)
+    }
+
+    /// Returns the directory for user configuration files in the given environment: `APPDATA` on Windows, and
+    /// `XDG_CONFIG_HOME` or `~/.config/` elsewhere.
+    /// ```rust
+    /// #[cfg(unix)]
+    /// {
+    /// use os_path::OsPath;
+    /// use std::collections::HashMap;
+    ///
+    /// let env = HashMap::from([("HOME".to_string(), "/home/tester".to_string())]);
+    /// assert_eq!(OsPath::config_dir_in(&env), Some(OsPath::from("/home/tester/.config/")));
+    /// }
+    /// ```
+    pub fn config_dir_in(env: &dyn Environment) -> Option<Self> {
+        let paths = EnvPaths::capture_from(env);
+        #[cfg(windows)]
+        return paths.dir("APPDATA");
+        #[cfg(not(windows))]
+        return paths.config_home();
+    }
+
+    /// Returns the directory for user data files. See `data_dir_in()`.
+    pub fn data_dir() -> Option<Self> {
+        Self::data_dir_in(&ProcessEnv)
+    }
+
+    /// Returns the directory for user data files in the given environment: `APPDATA` on Windows, and
+    /// `XDG_DATA_HOME` or `~/.local/share/` elsewhere.
+    pub fn data_dir_in(env: &dyn Environment) -> Option<Self> {
+        let paths = EnvPaths::capture_from(env);
+        #[cfg(windows)]
+        return paths.dir("APPDATA");
+        #[cfg(not(windows))]
+        return paths.data_home();
+    }
+
+    /// Returns the directory for user cache files. See `cache_dir_in()`.
+    pub fn cache_dir() -> Option<Self> {
+        Self::cache_dir_in(&ProcessEnv)
+    }
+
+    /// Returns the directory for user cache files in the given environment: `LOCALAPPDATA` on Windows, and
+    /// `XDG_CACHE_HOME` or `~/.cache/` elsewhere.
+    pub fn cache_dir_in(env: &dyn Environment) -> Option<Self> {
+        let paths = EnvPaths::capture_from(env);
+        #[cfg(windows)]
+        return paths.dir("LOCALAPPDATA");
+        #[cfg(not(windows))]
+        return paths.cache_home();
+    }
+
+    /// Parses a path typed by a user or read from a configuration file, expanding a leading `~` or `~user` to a home
+    /// directory. See `expand_user()`.
+    /// ```rust
+    /// #[cfg(target_os = "linux")]
+    /// {
+    /// use os_path::OsPath;
+    ///
+    /// assert_eq!(OsPath::from_user_input("~root/.profile"), OsPath::from("/root/.profile"));
+    /// assert_eq!(OsPath::from_user_input("/etc/hosts"), OsPath::from("/etc/hosts"));
+    /// }
+    /// ```
+    pub fn from_user_input(input: &str) -> Self {
+        Self::from(input).expand_user()
+    }
+
+    /// Replaces a leading `~` or `~user` with a home directory. See `expand_user_in()`.
+    pub fn expand_user(&self) -> Self {
+        self.expand_user_in(&ProcessEnv)
+    }
+
+    /// Replaces a leading `~` with the home directory from the given environment, which is `HOME`, or `USERPROFILE`
+    /// on Windows.
+    ///
+    /// A leading `~user` is replaced with that user's home directory: on Unix the one in `/etc/passwd`, and on Windows
+    /// the directory of that name beside the current user's home. The path is returned unchanged if it does not start
+    /// with `~` or the home directory is unknown.
+    /// ```rust
+    /// #[cfg(unix)]
+    /// {
+    /// use os_path::OsPath;
+    /// use std::collections::HashMap;
+    ///
+    /// let env = HashMap::from([("HOME".to_string(), "/home/tester".to_string())]);
+    /// assert_eq!(OsPath::from("~/src/").expand_user_in(&env), OsPath::from("/home/tester/src/"));
+    /// assert_eq!(OsPath::from("~nobody-here/x").expand_user_in(&env), OsPath::from("~nobody-here/x"));
+    /// assert_eq!(OsPath::from("src/~/x").expand_user_in(&env), OsPath::from("src/~/x"));
+    /// }
+    /// ```
+    pub fn expand_user_in(&self, env: &dyn Environment) -> Self {
+        let user = match self.components.first() {
+            Some(first) if !self.absolute => match first.strip_prefix('~') {
+                Some(user) => user,
+                None => return self.clone(),
+            },
+            _ => return self.clone(),
+        };
+        let home = if user.is_empty() {
+            Self::home_dir_in(env)
+        } else {
+            Self::user_home_dir(user, env)
+        };
+        match home {
+            Some(home) => {
+                let mut expanded = home;
+                expanded.components.extend_from_slice(&self.components[1..]);
+                expanded.directory = self.directory || self.components.len() == 1;
+                expanded.path = Self::build_pathbuf(&expanded.components, expanded.absolute);
+                expanded
+            }
+            None => self.clone(),
+        }
+    }
+
+    /// Looks up the home directory of the named user in the password database.
+    #[cfg(unix)]
+    fn user_home_dir(user: &str, _env: &dyn Environment) -> Option<Self> {
+        let passwd = std::fs::read_to_string("/etc/passwd").ok()?;
+        passwd.lines().find_map(|line| {
+            let fields: Vec<&str> = line.split(':').collect();
+            match fields.as_slice() {
+                [name, _, _, _, _, home, ..] if *name == user && !home.is_empty() => {
+                    Some(EnvPaths::as_dir(OsPath::from(*home)))
+                }
+                _ => None,
+            }
+        })
+    }
+
+    /// Guesses the home directory of the named user as the directory beside the current user's home, since profiles
+    /// usually share a parent such as `C:\Users`.
+    #[cfg(not(unix))]
+    fn user_home_dir(user: &str, env: &dyn Environment) -> Option<Self> {
+        let home = Self::home_dir_in(env)?;
+        let mut other = home.parent()?.join(user);
+        other.force_dir();
+        Some(other)
+    }
+
+    /// Replaces environment variables in the path. See `expand_vars_in()`.
+    pub fn expand_vars(&self) -> Self {
+        self.expand_vars_in(&ProcessEnv)
+    }
+
+    /// Replaces `$NAME`, `${NAME}`, and `%NAME%` with the values of the variables in the given environment. Unset
+    /// variables are left as written. Values may contain separators, so `$HOME/notes` becomes an absolute path.
+    /// ```rust
+    /// #[cfg(unix)]
+    /// {
+    /// use os_path::OsPath;
+    /// use std::collections::BTreeMap;
+    ///
+    /// let env = BTreeMap::from([
+    ///     ("APP".to_string(), "editor".to_string()),
+    ///     ("DATA".to_string(), "/srv/data".to_string()),
+    /// ]);
+    /// let path = OsPath::from("$DATA/${APP}_%APP%/$MISSING");
+    /// assert_eq!(path.expand_vars_in(&env), OsPath::from("/srv/data/editor_editor/$MISSING"));
+    /// }
+    /// ```
+    pub fn expand_vars_in(&self, env: &dyn Environment) -> Self {
+        let mut expanded = self.clone();
+        let mut changed = false;
+        for component in expanded.components.iter_mut() {
+            if let Some(new) = expand_component(component, env) {
+                *component = new;
+                changed = true;
+            }
+        }
+        if !changed {
+            return expanded;
+        }
+        let mut reparsed = Self::from(expanded.build_string());
+        if self.directory {
+            reparsed.force_dir();
+        }
+        reparsed
+    }
+}
+
+/// Expands the variables in one component, returning None if there were none to expand.
+fn expand_component(text: &str, env: &dyn Environment) -> Option<String> {
+    let lookup = |name: &str| -> Option<String> {
+        if name.is_empty() {
+            return None;
+        }
+        env.var_os(name).map(|v| v.to_string_lossy().into_owned())
+    };
+    let mut out = String::with_capacity(text.len());
+    let mut changed = false;
+    let mut rest = text;
+    while let Some(i) = rest.find(['$', '%']) {
+        out.push_str(&rest[..i]);
+        let after = &rest[i + 1..];
+        let (name, consumed) = if rest[i..].starts_with('%') {
+            match after.find('%') {
+                Some(end) => (&after[..end], end + 2),
+                None => ("", 1),
+            }
+        } else if let Some(braced) = after.strip_prefix('{') {
+            match braced.find('}') {
+                Some(end) => (&braced[..end], end + 3),
+                None => ("", 1),
+            }
+        } else {
+            let end = after
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(after.len());
+            (&after[..end], end + 1)
+        };
+        match lookup(name) {
+            Some(value) => {
+                out.push_str(&value);
+                changed = true;
+                rest = &rest[i + consumed..];
+            }
+            None => {
+                out.push_str(&rest[i..i + 1]);
+                rest = &rest[i + 1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    changed.then_some(out)
+}
@@ -0,0 +1,119 @@
+//! I/O errors that say which path they were about.
+
+use crate::OsPath;
+use std::fmt;
+use std::io;
+
+/// An I/O error together with the path and the operation it came from.
+///
+/// The filesystem methods of `OsPath` keep returning `io::Error`, with the same `kind()` as before, but wrap a
+/// `PathError` inside it, so printing the error names the path. `from_io()` gets the details back out.
+/// ```rust
+/// use os_path::{OsPath, PathError};
+///
+/// let missing = OsPath::from("no/such/dir/");
+/// let err = missing.read_dir().err().unwrap();
+/// assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+/// assert!(err.to_string().starts_with("failed to read directory no/such/dir/: "));
+///
+/// let context = PathError::from_io(&err).unwrap();
+/// assert_eq!(context.path, missing);
+/// assert_eq!(context.op, "read directory");
+/// ```
+#[derive(Debug)]
+pub struct PathError {
+    /// The path the operation was working on.
+    pub path: OsPath,
+    /// What was being done, such as `"open"` or `"read directory"`.
+    pub op: &'static str,
+    /// The second path of an operation on two, such as where a rename was headed.
+    pub target: Option<OsPath>,
+    /// The error the operation failed with.
+    pub source: io::Error,
+}
+
+impl PathError {
+    pub fn new(op: &'static str, path: OsPath, source: io::Error) -> Self {
+        Self {
+            path,
+            op,
+            target: None,
+            source,
+        }
+    }
+
+    /// Returns the path error an I/O error carries, or None if it carries none.
+    pub fn from_io(error: &io::Error) -> Option<&Self> {
+        error.get_ref()?.downcast_ref()
+    }
+
+    /// Wraps the error in an `io::Error` of the same kind as its source.
+    pub fn into_io(self) -> io::Error {
+        io::Error::new(self.source.kind(), self)
+    }
+}
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to {} {}", self.op, self.path)?;
+        if let Some(target) = &self.target {
+            write!(f, " to {}", target)?;
+        }
+        write!(f, ": {}", self.source)
+    }
+}
+
+impl std::error::Error for PathError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// Adds the path to the error of an I/O result, so code doing its own I/O reports errors the way the crate's
+/// filesystem methods do. Errors that already carry something of their own, such as another `PathError`, are left
+/// alone, so the innermost path wins.
+/// ```rust
+/// use os_path::{IoResultExt, OsPath, PathError};
+///
+/// let config = OsPath::from("no/such/config.toml");
+/// let err = std::fs::read_to_string(&config).path_context(&config).unwrap_err();
+/// assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+/// assert_eq!(PathError::from_io(&err).unwrap().path, config);
+///
+/// let err = std::fs::read(&config).with_path_context("load", &config).unwrap_err();
+/// assert!(err.to_string().starts_with("failed to load no/such/config.toml: "));
+/// ```
+pub trait IoResultExt<T> {
+    /// Attaches the path, describing the operation as `"access"`.
+    fn path_context(self, path: &OsPath) -> io::Result<T>;
+
+    /// Attaches the path and the operation, such as `"open"` or `"read directory"`.
+    fn with_path_context(self, op: &'static str, path: &OsPath) -> io::Result<T>;
+
+    /// Attaches both paths of an operation on two, such as `"rename"` or `"copy"`.
+    fn with_paths_context(self, op: &'static str, from: &OsPath, to: &OsPath) -> io::Result<T>;
+}
+
+impl<T> IoResultExt<T> for io::Result<T> {
+    fn path_context(self, path: &OsPath) -> io::Result<T> {
+        self.with_path_context("access", path)
+    }
+
+    fn with_path_context(self, op: &'static str, path: &OsPath) -> io::Result<T> {
+        self.map_err(|e| match e.get_ref() {
+            Some(_) => e,
+            None => PathError::new(op, path.clone(), e).into_io(),
+        })
+    }
+
+    fn with_paths_context(self, op: &'static str, from: &OsPath, to: &OsPath) -> io::Result<T> {
+        self.map_err(|e| match e.get_ref() {
+            Some(_) => e,
+            None => {
+                let mut error = PathError::new(op, from.clone(), e);
+                error.target = Some(to.clone());
+                error.into_io()
+            }
+        })
+    }
+}
@@ -0,0 +1,88 @@
+//! Turning a selection of files and directories, such as items dropped onto an application, into a list of files.
+
+use crate::fs::{FsBackend, StdFs};
+use crate::{OsPath, PathFilter};
+use std::collections::HashSet;
+use std::io;
+
+/// Expands a selection of files and directories on the real filesystem into the files it covers. See
+/// `expand_to_files_in()`.
+/// ```rust
+/// use os_path::{expand_to_files, OsPath, PathFilter};
+///
+/// let filter = PathFilter::new().extensions(["rs"]);
+/// let files = expand_to_files(["src/", "src/lib.rs", "Cargo.toml"], &filter).unwrap();
+/// assert!(files.contains(&OsPath::from("src/lib.rs")));
+/// assert_eq!(files.iter().filter(|p| p.name().unwrap() == "lib.rs").count(), 1);
+/// assert!(files.iter().all(|p| p.extension().unwrap() == "rs"));
+/// ```
+pub fn expand_to_files<I>(paths: I, filter: &PathFilter) -> io::Result<Vec<OsPath>>
+where
+    I: IntoIterator,
+    I::Item: Into<OsPath>,
+{
+    expand_to_files_in(paths, filter, &StdFs)
+}
+
+/// Expands a selection of files and directories in the given backend into the files it covers.
+///
+/// Each directory is walked with the filter, whose globs are written relative to that directory, and the files found
+/// are kept. A file named directly is checked against the filter by its name alone, as if found in its parent. Every
+/// file comes back once, compared after `resolve()`, in the order the selection and then the walks first reach it.
+/// A path that does not exist fails the whole expansion.
+/// ```rust
+/// #[cfg(unix)]
+/// {
+/// use os_path::{expand_to_files_in, MemoryFs, OsPath, PathFilter};
+///
+/// let mut fs = MemoryFs::new();
+/// fs.add_file("/drop/photos/a.jpg", "").unwrap()
+///     .add_file("/drop/photos/.thumbs/a.jpg", "").unwrap()
+///     .add_file("/drop/photos/notes.txt", "").unwrap()
+///     .add_file("/drop/b.jpg", "").unwrap();
+///
+/// let filter = PathFilter::new().extensions(["jpg"]);
+/// let files = expand_to_files_in(["/drop/b.jpg", "/drop/photos", "/drop/photos/a.jpg"], &filter, &fs).unwrap();
+/// let files: Vec<String> = files.iter().map(|p| p.to_string()).collect();
+/// assert_eq!(files, vec!["/drop/b.jpg", "/drop/photos/a.jpg"]);
+/// }
+/// ```
+pub fn expand_to_files_in<I>(
+    paths: I,
+    filter: &PathFilter,
+    fs: &dyn FsBackend,
+) -> io::Result<Vec<OsPath>>
+where
+    I: IntoIterator,
+    I::Item: Into<OsPath>,
+{
+    let mut seen = HashSet::new();
+    let mut files = Vec::new();
+    let mut keep = |path: OsPath| {
+        let mut key = path.clone();
+        key.resolve();
+        key.directory = false;
+        if seen.insert(key) {
+            files.push(path);
+        }
+    };
+    for path in paths {
+        let mut path: OsPath = path.into();
+        if path.metadata_in(fs)?.is_dir() {
+            path.force_dir();
+            for entry in path.walk_in(fs).with_filter(filter.clone()) {
+                let entry = entry?;
+                if entry.is_file() {
+                    keep(entry);
+                }
+            }
+        } else {
+            path.directory = false;
+            let name = path.tail_from(path.components.len().saturating_sub(1));
+            if filter.matches_at(&name, 1) {
+                keep(path);
+            }
+        }
+    }
+    Ok(files)
+}
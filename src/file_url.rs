@@ -0,0 +1,170 @@
+//! Converting between paths and `file://` URLs.
+
+use crate::web::{percent_decode, percent_encode};
+use crate::OsPath;
+use std::fmt;
+
+/// The reasons `OsPath::from_file_url()` can refuse a URL.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum FileUrlError {
+    /// The URL does not use the `file` scheme, or has no path.
+    NotFileUrl,
+    /// A `%` escape was malformed, decoded to bytes that are not UTF-8, or produced a separator or NUL inside a
+    /// single name.
+    InvalidEncoding,
+    /// The URL names a file on another host, which only Windows can reach, as a UNC path.
+    UnsupportedHost(String),
+    /// On Windows, the URL has neither a drive nor a host.
+    MissingDrive,
+}
+
+impl fmt::Display for FileUrlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileUrlError::NotFileUrl => f.write_str("not a file URL"),
+            FileUrlError::InvalidEncoding => {
+                f.write_str("file URL is not valid percent-encoded UTF-8")
+            }
+            FileUrlError::UnsupportedHost(host) => {
+                write!(f, "file URL names a file on another host '{}'", host)
+            }
+            FileUrlError::MissingDrive => f.write_str("file URL has no drive"),
+        }
+    }
+}
+
+impl std::error::Error for FileUrlError {}
+
+/// File URL Methods
+impl OsPath {
+    /// Returns the path as a `file://` URL, or None if it is relative. Each name is percent-encoded, Windows drives
+    /// become `file:///C:/...`, and UNC paths become `file://server/share/...`. A directory ends with `/`.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// #[cfg(unix)]
+    /// {
+    /// assert_eq!(
+    ///     OsPath::from("/home/me/My Files/100%.txt").to_file_url().unwrap(),
+    ///     "file:///home/me/My%20Files/100%25.txt"
+    /// );
+    /// assert_eq!(OsPath::from("/srv/").to_file_url().unwrap(), "file:///srv/");
+    /// }
+    /// #[cfg(windows)]
+    /// {
+    /// assert_eq!(OsPath::from("C:\\Users\\me").to_file_url().unwrap(), "file:///C:/Users/me");
+    /// assert_eq!(OsPath::from(r"\\server\share\a b.txt").to_file_url().unwrap(), "file://server/share/a%20b.txt");
+    /// }
+    /// assert_eq!(OsPath::from("docs/intro.md").to_file_url(), None);
+    /// ```
+    pub fn to_file_url(&self) -> Option<String> {
+        if !self.absolute {
+            return None;
+        }
+        let mut url = String::from("file://");
+        let mut components = self.components.iter();
+        if let Some((server, share)) = self.unc_prefix().filter(|_| cfg!(windows)) {
+            url.push_str(&encode(server));
+            url.push('/');
+            url.push_str(&encode(share));
+            components.next();
+        }
+        for c in components {
+            url.push('/');
+            url.push_str(&encode(c));
+        }
+        if self.directory || self.components.is_empty() {
+            url.push('/');
+        }
+        Some(url)
+    }
+
+    /// Parses a `file://` URL into a path, decoding each name and dropping any query or fragment. A URL whose path
+    /// ends with `/` gives a directory. The host may be empty or `localhost`; on Windows any other host gives a UNC
+    /// path, and elsewhere it is refused.
+    /// ```rust
+    /// use os_path::{FileUrlError, OsPath};
+    ///
+    /// #[cfg(unix)]
+    /// {
+    /// let path = OsPath::from_file_url("file:///home/me/My%20Files/").unwrap();
+    /// assert_eq!(path.to_string(), "/home/me/My Files/");
+    /// assert!(path.is_dir());
+    /// assert_eq!(OsPath::from_file_url("file://localhost/etc/hosts").unwrap().to_string(), "/etc/hosts");
+    /// assert_eq!(
+    ///     OsPath::from_file_url("file://nas/share/a.txt"),
+    ///     Err(FileUrlError::UnsupportedHost("nas".to_string()))
+    /// );
+    /// }
+    /// #[cfg(windows)]
+    /// {
+    /// assert_eq!(OsPath::from_file_url("file:///C:/Users/me").unwrap().to_string(), "C:\\Users\\me");
+    /// assert_eq!(OsPath::from_file_url("file://nas/share/a.txt").unwrap().to_string(), r"\\nas\share\a.txt");
+    /// }
+    /// assert_eq!(OsPath::from_file_url("https://example.com/"), Err(FileUrlError::NotFileUrl));
+    /// assert_eq!(OsPath::from_file_url("file:///a%2Fb"), Err(FileUrlError::InvalidEncoding));
+    /// ```
+    pub fn from_file_url(url: &str) -> Result<Self, FileUrlError> {
+        let rest = url
+            .get(..5)
+            .filter(|scheme| scheme.eq_ignore_ascii_case("file:"))
+            .map(|_| &url[5..])
+            .ok_or(FileUrlError::NotFileUrl)?;
+        let rest = rest.split(['?', '#']).next().unwrap_or_default();
+        let (host, path) = match rest.strip_prefix("//") {
+            Some(authority) => match authority.find('/') {
+                Some(i) => authority.split_at(i),
+                None => (authority, "/"),
+            },
+            None if rest.starts_with('/') => ("", rest),
+            None => return Err(FileUrlError::NotFileUrl),
+        };
+        let host = match host {
+            "localhost" => "",
+            host => host,
+        };
+
+        let mut names = Vec::new();
+        for segment in path.split('/').filter(|s| !s.is_empty()) {
+            let name = percent_decode(segment).map_err(|_| FileUrlError::InvalidEncoding)?;
+            if name.contains(['/', '\\', '\0']) {
+                return Err(FileUrlError::InvalidEncoding);
+            }
+            names.push(name);
+        }
+        let trailing = match path.ends_with('/') && !names.is_empty() {
+            true => "/",
+            false => "",
+        };
+
+        let text = if cfg!(windows) {
+            match names.first_mut() {
+                _ if !host.is_empty() => format!(r"\\{}\{}", host, names.join(r"\")),
+                Some(first) if is_url_drive(first) => {
+                    first.replace_range(1..2, ":");
+                    names.join(r"\")
+                }
+                _ => return Err(FileUrlError::MissingDrive),
+            }
+        } else {
+            if !host.is_empty() {
+                return Err(FileUrlError::UnsupportedHost(host.to_string()));
+            }
+            format!("/{}", names.join("/"))
+        };
+        Ok(OsPath::from(text + trailing))
+    }
+}
+
+/// Returns true if the name is a drive as written in a URL, such as `C:` or the older `C|`.
+fn is_url_drive(name: &str) -> bool {
+    let bytes = name.as_bytes();
+    bytes.len() == 2 && bytes[0].is_ascii_alphabetic() && matches!(bytes[1], b':' | b'|')
+}
+
+/// Percent-encodes everything in a name that is not allowed as it is in the path of a URL.
+fn encode(name: &str) -> String {
+    percent_encode(name, |b| {
+        b.is_ascii_alphanumeric() || b"-._~!$&'()*+,;=:@".contains(&b)
+    })
+}
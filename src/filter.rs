@@ -0,0 +1,214 @@
+//! Composable include and exclude filtering of paths.
+
+use crate::glob::{Glob, GlobError};
+use crate::OsPath;
+use std::collections::BTreeSet;
+
+/// Decides which names count as hidden. By default that is the Unix dot-file convention alone, as in
+/// `OsPath::is_hidden()`; other conventions add prefixes and suffixes of their own.
+/// ```rust
+/// use os_path::{HiddenPolicy, OsPath};
+///
+/// let policy = HiddenPolicy::new().prefix("_").prefix("~$").suffix("~");
+/// assert!(policy.is_hidden(&OsPath::from("site/_drafts/")));
+/// assert!(policy.is_hidden(&OsPath::from("docs/~$report.docx")));
+/// assert!(policy.is_hidden(&OsPath::from("notes.txt~")));
+/// assert!(policy.is_hidden(&OsPath::from(".git/")));
+/// assert!(!policy.is_hidden(&OsPath::from("site/index.md")));
+///
+/// let policy = HiddenPolicy::none().prefix("_");
+/// assert!(!policy.is_hidden(&OsPath::from(".well-known/")));
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct HiddenPolicy {
+    dot_files: bool,
+    prefixes: Vec<String>,
+    suffixes: Vec<String>,
+}
+
+impl Default for HiddenPolicy {
+    fn default() -> Self {
+        Self {
+            dot_files: true,
+            prefixes: Vec::new(),
+            suffixes: Vec::new(),
+        }
+    }
+}
+
+impl HiddenPolicy {
+    /// Creates a policy that hides dot files only.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a policy that hides nothing, to build up from.
+    pub fn none() -> Self {
+        Self {
+            dot_files: false,
+            ..Self::default()
+        }
+    }
+
+    /// Sets whether names starting with `.` are hidden.
+    pub fn dot_files(mut self, yes: bool) -> Self {
+        self.dot_files = yes;
+        self
+    }
+
+    /// Hides names starting with the prefix.
+    pub fn prefix<S: Into<String>>(mut self, prefix: S) -> Self {
+        self.prefixes.push(prefix.into());
+        self
+    }
+
+    /// Hides names ending with the suffix.
+    pub fn suffix<S: Into<String>>(mut self, suffix: S) -> Self {
+        self.suffixes.push(suffix.into());
+        self
+    }
+
+    /// Returns true if the last item of the path is hidden. `.` and `..` never are.
+    pub fn is_hidden(&self, path: &OsPath) -> bool {
+        let Some(name) = path.name() else {
+            return false;
+        };
+        if name == "." || name == crate::UP {
+            return false;
+        }
+        (self.dot_files && name.starts_with('.'))
+            || self.prefixes.iter().any(|p| name.starts_with(p.as_str()))
+            || self.suffixes.iter().any(|s| name.ends_with(s.as_str()))
+    }
+}
+
+/// A reusable combination of the checks usually applied to directory listings: include and exclude globs, a depth
+/// limit, hidden-file handling, and an extension allowlist.
+///
+/// A path matches when it is not hidden (unless hidden files are enabled), is within the depth limit, matches no
+/// exclude glob, matches at least one include glob (if any were given), and has one of the listed extensions (if
+/// any were given). Include globs and extensions only constrain which entries are yielded; directories that fail
+/// them are still descended into by `Walk::with_filter()`, while hidden, excluded, and too deep directories are not.
+/// ```rust
+/// use os_path::{OsPath, PathFilter};
+///
+/// let filter = PathFilter::new()
+///     .include("src/**").unwrap()
+///     .exclude("**/generated/**").unwrap()
+///     .extensions(["rs", "toml"]);
+///
+/// assert!(filter.matches(&OsPath::from("src/lib.rs")));
+/// assert!(!filter.matches(&OsPath::from("src/generated/api.rs")));
+/// assert!(!filter.matches(&OsPath::from("src/.cache.rs")));
+/// assert!(!filter.matches(&OsPath::from("docs/intro.rs")));
+/// assert!(!filter.matches(&OsPath::from("src/notes.md")));
+/// ```
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct PathFilter {
+    includes: Vec<Glob>,
+    excludes: Vec<Glob>,
+    max_depth: Option<usize>,
+    hidden: bool,
+    hidden_policy: HiddenPolicy,
+    extensions: Option<BTreeSet<String>>,
+}
+
+impl PathFilter {
+    /// Creates a filter that matches every non-hidden path.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a glob that paths must match. Paths matching any one include glob pass.
+    pub fn include(mut self, pattern: &str) -> Result<Self, GlobError> {
+        self.includes.push(Glob::new(pattern)?);
+        Ok(self)
+    }
+
+    /// Adds a glob that rejects matching paths. Excludes win over includes.
+    pub fn exclude(mut self, pattern: &str) -> Result<Self, GlobError> {
+        self.excludes.push(Glob::new(pattern)?);
+        Ok(self)
+    }
+
+    /// Rejects paths with more than `depth` components.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Sets whether hidden paths are matched. They are not by default.
+    pub fn hidden(mut self, yes: bool) -> Self {
+        self.hidden = yes;
+        self
+    }
+
+    /// Sets which names count as hidden. The dot-file convention is used by default. A traversal does not descend
+    /// into hidden directories.
+    /// ```rust
+    /// use os_path::{HiddenPolicy, OsPath, PathFilter};
+    ///
+    /// let filter = PathFilter::new().hidden_policy(HiddenPolicy::new().prefix("_"));
+    /// assert!(!filter.matches(&OsPath::from("site/_layouts/")));
+    /// assert!(!filter.matches(&OsPath::from("site/.env")));
+    /// assert!(filter.matches(&OsPath::from("site/index.html")));
+    /// ```
+    pub fn hidden_policy(mut self, policy: HiddenPolicy) -> Self {
+        self.hidden_policy = policy;
+        self
+    }
+
+    /// Only matches files with one of the given extensions, compared without the leading dot and ignoring case.
+    pub fn extensions<I, S>(mut self, extensions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let set = self.extensions.get_or_insert_with(BTreeSet::new);
+        for ext in extensions {
+            set.insert(ext.as_ref().trim_start_matches('.').to_lowercase());
+        }
+        self
+    }
+
+    /// Returns true if the path passes every check. The depth of the path is its number of components.
+    pub fn matches(&self, path: &OsPath) -> bool {
+        self.matches_at(path, path.components.len())
+    }
+
+    /// Returns true if the path, found `depth` levels below a traversal root, passes every check.
+    pub(crate) fn matches_at(&self, path: &OsPath, depth: usize) -> bool {
+        if !self.admits(path, depth) {
+            return false;
+        }
+        if !self.includes.is_empty() && !self.includes.iter().any(|g| g.matches(path)) {
+            return false;
+        }
+        match &self.extensions {
+            Some(set) => {
+                path.is_file() && Self::extension_of(path).is_some_and(|ext| set.contains(&ext))
+            }
+            None => true,
+        }
+    }
+
+    /// Returns true if a traversal should look below the directory, found `depth` levels below its root.
+    pub(crate) fn descends_at(&self, dir: &OsPath, depth: usize) -> bool {
+        self.admits(dir, depth) && self.max_depth.is_none_or(|max| depth < max)
+    }
+
+    /// The checks that prune whole subtrees: hidden entries, excludes, and depth.
+    pub(crate) fn admits(&self, path: &OsPath, depth: usize) -> bool {
+        if !self.hidden && self.hidden_policy.is_hidden(path) {
+            return false;
+        }
+        if self.excludes.iter().any(|g| g.matches(path)) {
+            return false;
+        }
+        self.max_depth.is_none_or(|max| depth <= max)
+    }
+
+    fn extension_of(path: &OsPath) -> Option<String> {
+        path.split_extension().map(|(_, ext)| ext.to_lowercase())
+    }
+}
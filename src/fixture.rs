@@ -0,0 +1,266 @@
+//! Temporary directory trees for tests.
+
+use crate::fs::require_os;
+use crate::{OsPath, PurePath};
+use std::io;
+use std::ops::Deref;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Clone, PartialEq, Debug)]
+enum Entry {
+    File(OsPath, Vec<u8>),
+    Dir(OsPath),
+}
+
+/// Describes a directory tree to create in a fresh temporary directory, for tests that need real files.
+/// ```rust
+/// use os_path::TestTree;
+///
+/// let tree = TestTree::new()
+///     .file("src/main.rs", "fn main() {}")
+///     .file("README.md", "# Demo")
+///     .dir("target/")
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(std::fs::read_to_string(tree.join("src/main.rs")).unwrap(), "fn main() {}");
+/// assert!(tree.join("target/").is_dir());
+///
+/// let root = tree.path().clone();
+/// drop(tree);
+/// assert!(!root.exists());
+/// ```
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct TestTree {
+    entries: Vec<Entry>,
+}
+
+impl TestTree {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a file with the given contents, creating its parent directories. The path is relative to the root.
+    pub fn file<P: AsRef<Path>, C: AsRef<[u8]>>(mut self, path: P, contents: C) -> Self {
+        self.entries.push(Entry::File(
+            OsPath::from(path.as_ref()),
+            contents.as_ref().to_vec(),
+        ));
+        self
+    }
+
+    /// Adds an empty directory, creating its parents. The path is relative to the root.
+    pub fn dir<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.entries.push(Entry::Dir(OsPath::from(path.as_ref())));
+        self
+    }
+
+    /// Creates the tree in a new, uniquely named directory under the system temporary directory.
+    ///
+    /// Paths that are absolute or climb out of the root with `..` are rejected with `io::ErrorKind::InvalidInput`,
+    /// before anything is created.
+    pub fn build(&self) -> io::Result<BuiltTree> {
+        for entry in &self.entries {
+            let (Entry::File(path, _) | Entry::Dir(path)) = entry;
+            if path.absolute || PurePath::climbs_out(&path.components) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("{} is not inside the tree", path),
+                ));
+            }
+        }
+        let root = Self::unique_dir()?;
+        let tree = BuiltTree { root };
+        for entry in &self.entries {
+            match entry {
+                Entry::File(path, contents) => {
+                    let path = tree.root.join(path);
+                    if let Some(parent) = path.parent() {
+                        std::fs::create_dir_all(parent)?;
+                    }
+                    std::fs::write(path, contents)?;
+                }
+                Entry::Dir(path) => std::fs::create_dir_all(tree.root.join(path))?,
+            }
+        }
+        Ok(tree)
+    }
+
+    fn unique_dir() -> io::Result<OsPath> {
+        require_os()?;
+        let base = OsPath::from(std::env::temp_dir());
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.subsec_nanos());
+        loop {
+            let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+            let mut root = base.join(format!(
+                "os_path-test-{}-{}-{}",
+                std::process::id(),
+                nanos,
+                n
+            ));
+            match std::fs::create_dir(&root) {
+                Ok(()) => {
+                    root.force_dir();
+                    return Ok(root);
+                }
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// A directory tree created by `TestTree::build()`. It dereferences to the root directory, and the whole tree is
+/// deleted when this is dropped.
+#[derive(Debug)]
+pub struct BuiltTree {
+    root: OsPath,
+}
+
+impl BuiltTree {
+    /// Returns the root directory of the tree.
+    pub fn path(&self) -> &OsPath {
+        &self.root
+    }
+
+    /// Returns the root directory and keeps the tree on disk, for inspecting it after a failing test.
+    pub fn keep(self) -> OsPath {
+        let root = self.root.clone();
+        std::mem::forget(self);
+        root
+    }
+}
+
+impl Deref for BuiltTree {
+    type Target = OsPath;
+
+    fn deref(&self) -> &OsPath {
+        &self.root
+    }
+}
+
+impl AsRef<Path> for BuiltTree {
+    fn as_ref(&self) -> &Path {
+        self.root.as_ref()
+    }
+}
+
+impl Drop for BuiltTree {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.root);
+    }
+}
+
+/// Asserts that two paths are equal once `.` and `..` are resolved, as `OsPath::resolve()` does. Either side may be
+/// anything an `OsPath` can be made from, and is cloned rather than moved. On failure, the message lists the
+/// components of both paths side by side, marking the ones that differ.
+/// ```rust
+/// use os_path::{assert_paths_eq, OsPath};
+///
+/// assert_paths_eq!(OsPath::from("src/bin/../lib.rs"), "src/./lib.rs");
+/// assert_paths_eq!("docs/", OsPath::from("docs/"), "after {} passes", 2);
+/// ```
+/// ```rust,should_panic
+/// use os_path::assert_paths_eq;
+///
+/// // Panics with:
+/// //    left: site/docs/intro.md
+/// //   right: site/blog/intro.md
+/// //     [0] site
+/// //   - [1] docs
+/// //   + [1] blog
+/// //     [2] intro.md
+/// assert_paths_eq!("site/docs/intro.md", "site/blog/intro.md");
+/// ```
+#[macro_export]
+macro_rules! assert_paths_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        if let Some(diff) = $crate::__path_mismatch(($left).clone(), ($right).clone()) {
+            panic!("assertion failed: paths are not equal\n{}", diff);
+        }
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        if let Some(diff) = $crate::__path_mismatch(($left).clone(), ($right).clone()) {
+            panic!("assertion failed: paths are not equal: {}\n{}", format_args!($($arg)+), diff);
+        }
+    };
+}
+
+/// Asserts that two paths differ once `.` and `..` are resolved. The counterpart of `assert_paths_eq!`.
+/// ```rust
+/// use os_path::assert_paths_ne;
+///
+/// assert_paths_ne!("a/b/../c", "a/b/c");
+/// assert_paths_ne!("logs/", "logs", "a directory is not a file");
+/// ```
+#[macro_export]
+macro_rules! assert_paths_ne {
+    ($left:expr, $right:expr $(,)?) => {
+        if $crate::__path_mismatch(($left).clone(), ($right).clone()).is_none() {
+            panic!("assertion failed: paths are equal\n  both: {}", $crate::OsPath::from(($left).clone()));
+        }
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        if $crate::__path_mismatch(($left).clone(), ($right).clone()).is_none() {
+            panic!(
+                "assertion failed: paths are equal: {}\n  both: {}",
+                format_args!($($arg)+),
+                $crate::OsPath::from(($left).clone())
+            );
+        }
+    };
+}
+
+/// Compares two paths for the assertion macros, returning a description of how they differ, or None if they are
+/// equal once resolved.
+#[doc(hidden)]
+pub fn __path_mismatch<L: Into<OsPath>, R: Into<OsPath>>(left: L, right: R) -> Option<String> {
+    let (left, right) = (normalized(left.into()), normalized(right.into()));
+    if left == right {
+        return None;
+    }
+    let mut diff = format!("   left: {}\n  right: {}\n", left, right);
+    if left.absolute != right.absolute {
+        let kind = |p: &OsPath| if p.absolute { "absolute" } else { "relative" };
+        diff.push_str(&format!(
+            "  left is {}, right is {}\n",
+            kind(&left),
+            kind(&right)
+        ));
+    }
+    let len = left.components.len().max(right.components.len());
+    for i in 0..len {
+        match (left.components.get(i), right.components.get(i)) {
+            (Some(l), Some(r)) if l == r => diff.push_str(&format!("    [{}] {}\n", i, l)),
+            (l, r) => {
+                if let Some(l) = l {
+                    diff.push_str(&format!("  - [{}] {}\n", i, l));
+                }
+                if let Some(r) = r {
+                    diff.push_str(&format!("  + [{}] {}\n", i, r));
+                }
+            }
+        }
+    }
+    if left.directory != right.directory {
+        let kind = |p: &OsPath| if p.directory { "a directory" } else { "a file" };
+        diff.push_str(&format!(
+            "  left is {}, right is {}\n",
+            kind(&left),
+            kind(&right)
+        ));
+    }
+    Some(diff)
+}
+
+fn normalized(mut path: OsPath) -> OsPath {
+    path.components.retain(|c| c != ".");
+    path.resolve();
+    path
+}
@@ -0,0 +1,234 @@
+//! Reading and writing paths in the notation of a platform other than the one the program runs on.
+
+use crate::{OsPath, PurePath};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+const BS: char = '\\';
+const FS: char = '/';
+
+/// A platform's path notation.
+///
+/// `OsPath::from()` always reads the notation of the platform the program was built for. `parse_as()` and
+/// `to_string_as()` read and write either notation anywhere, so a Linux build server can work with Windows paths and
+/// the other way around. Everything between, such as joining, resolving `..` and taking parents, works on the
+/// components and does not depend on the notation.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Flavor {
+    /// `/` separated, absolute when starting with `/`. A `\` is an ordinary character in a name.
+    Posix,
+    /// `\` or `/` separated, absolute when starting with a drive such as `C:` or a UNC prefix such as
+    /// `\\server\share`. Drive letters are upper-cased.
+    Windows,
+}
+
+impl Flavor {
+    /// Returns the notation of the platform the program was built for.
+    pub fn native() -> Self {
+        match cfg!(windows) {
+            true => Flavor::Windows,
+            false => Flavor::Posix,
+        }
+    }
+}
+
+/// The notation a path is displayed in, when it is not the native one. It is not part of the path's value, so paths
+/// that differ only in style are equal and hash alike.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Style(Option<Flavor>);
+
+impl Style {
+    pub(crate) fn of(flavor: Option<Flavor>) -> Self {
+        Self(flavor.filter(|f| *f != Flavor::native()))
+    }
+}
+
+impl PartialEq for Style {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for Style {}
+
+impl Hash for Style {
+    fn hash<H: Hasher>(&self, _: &mut H) {}
+}
+
+/// The separator `OsPath::display_with()` writes between components.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Separator {
+    /// The separator of the platform the program was built for, as `to_string()` writes.
+    #[default]
+    Native,
+    /// `/`, as in `Flavor::Posix`.
+    ForwardSlash,
+    /// `\`, as in `Flavor::Windows`.
+    Backslash,
+}
+
+/// Displays a path with a chosen separator, made by `OsPath::display_with()`.
+#[derive(Clone, Copy, Debug)]
+pub struct PathDisplay<'a> {
+    path: &'a OsPath,
+    separator: Separator,
+}
+
+impl fmt::Display for PathDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.separator {
+            Separator::Native => f.write_str(&self.path.build_string()),
+            Separator::ForwardSlash => f.write_str(&self.path.to_string_as(Flavor::Posix)),
+            Separator::Backslash => f.write_str(&self.path.to_string_as(Flavor::Windows)),
+        }
+    }
+}
+
+/// Flavor Methods
+impl OsPath {
+    /// Reads a path written in the notation of the given platform.
+    /// ```rust
+    /// use os_path::{Flavor, OsPath};
+    ///
+    /// let mut path = OsPath::parse_as(Flavor::Windows, r"c:\Users\me\..\Public\");
+    /// path.resolve();
+    /// assert!(path.is_absolute());
+    /// assert!(path.is_dir());
+    /// assert_eq!(path.to_string_as(Flavor::Windows), r"C:\Users\Public\");
+    ///
+    /// let path = OsPath::parse_as(Flavor::Posix, r"/srv/odd\name.txt");
+    /// assert_eq!(path.name().unwrap(), r"odd\name.txt");
+    /// assert_eq!(path.to_string_as(Flavor::Posix), r"/srv/odd\name.txt");
+    /// ```
+    pub fn parse_as(flavor: Flavor, text: &str) -> Self {
+        match flavor {
+            Flavor::Posix => Self::parse_parts(text, &[FS], false),
+            Flavor::Windows => match PurePath::split_unc(text) {
+                Some((prefix, rest)) => {
+                    let mut unc = Self::parse_parts(rest, &[BS, FS], true);
+                    unc.components.insert(0, prefix);
+                    unc.absolute = true;
+                    unc.directory = unc.directory || unc.components.len() == 1;
+                    unc.path = Self::build_pathbuf(&unc.components, true);
+                    unc
+                }
+                None => Self::parse_parts(text, &[BS, FS], true),
+            },
+        }
+    }
+
+    /// Writes the path in the notation of the given platform. A Windows path written for Posix starts with its drive,
+    /// as in `C:/Users`, and a Posix path written for Windows starts with a bare `\` when it is absolute.
+    /// ```rust
+    /// use os_path::{Flavor, OsPath};
+    ///
+    /// let path = OsPath::parse_as(Flavor::Windows, r"\\build\drops\app\");
+    /// assert_eq!(path.to_string_as(Flavor::Windows), r"\\build\drops\app\");
+    /// assert_eq!(path.to_string_as(Flavor::Posix), "//build/drops/app/");
+    ///
+    /// let path = OsPath::parse_as(Flavor::Posix, "/usr/local/bin");
+    /// assert_eq!(path.to_string_as(Flavor::Windows), r"\usr\local\bin");
+    /// ```
+    pub fn to_string_as(&self, flavor: Flavor) -> String {
+        let (separator, other) = match flavor {
+            Flavor::Posix => (FS, BS),
+            Flavor::Windows => (BS, FS),
+        };
+        let mut text = String::new();
+        let mut names = &self.components[..];
+        if self.absolute {
+            if let Some((first, rest)) = names.split_first().filter(|(f, _)| PurePath::is_prefix(f))
+            {
+                text.push_str(&first.replace(other, &separator.to_string()));
+                names = rest;
+            }
+            text.push(separator);
+        }
+        text.push_str(&names.join(&separator.to_string()));
+        if self.directory && !names.is_empty() {
+            text.push(separator);
+        }
+        PurePath::displayable(text)
+    }
+
+    /// Returns the path displaying itself in the notation of the given platform. Only the text changes, for
+    /// `Display`, `to_string()`, serialization and conversion into `String`: the components, comparisons and the
+    /// native `Path` the path converts into stay the same, and the style is kept through joins and other changes.
+    /// ```rust
+    /// use os_path::{Flavor, OsPath};
+    ///
+    /// let path = OsPath::from("logs/2024/").with_style(Flavor::Windows);
+    /// assert_eq!(path.to_string(), "logs\\2024\\");
+    /// assert_eq!(path.join("app.log").to_string(), "logs\\2024\\app.log");
+    /// assert_eq!(path.style(), Flavor::Windows);
+    /// assert_eq!(path, OsPath::from("logs/2024/"));
+    ///
+    /// let built = OsPath::builder().dir("a").file("b.txt").style(Flavor::Posix).build().unwrap();
+    /// assert_eq!(built.to_string(), "a/b.txt");
+    /// ```
+    pub fn with_style(mut self, flavor: Flavor) -> Self {
+        self.style = Style::of(Some(flavor));
+        self
+    }
+
+    /// Returns the notation the path displays itself in, which is the native one unless set by `with_style()`.
+    pub fn style(&self) -> Flavor {
+        self.style.0.unwrap_or_else(Flavor::native)
+    }
+
+    /// Returns the path with `/` separators whatever the platform, for text read on several platforms, such as
+    /// manifests. Absolute Windows paths start with their drive, as in `C:/Users/me`.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// assert_eq!(OsPath::from("assets\\icons\\app.png").to_portable_string(), "assets/icons/app.png");
+    /// assert_eq!(OsPath::from("build/out/").to_portable_string(), "build/out/");
+    /// ```
+    pub fn to_portable_string(&self) -> String {
+        self.to_string_as(Flavor::Posix)
+    }
+
+    /// Returns something that displays the path with the given separator.
+    /// ```rust
+    /// use os_path::{OsPath, Separator};
+    ///
+    /// let path = OsPath::from("docs/guide/intro.md");
+    /// assert_eq!(format!("{}", path.display_with(Separator::Backslash)), "docs\\guide\\intro.md");
+    /// assert_eq!(path.display_with(Separator::ForwardSlash).to_string(), "docs/guide/intro.md");
+    /// assert_eq!(path.display_with(Separator::Native).to_string(), path.to_string());
+    /// ```
+    pub fn display_with(&self, separator: Separator) -> PathDisplay<'_> {
+        PathDisplay {
+            path: self,
+            separator,
+        }
+    }
+
+    /// Returns the text of the path in its style.
+    pub(crate) fn styled_string(&self) -> String {
+        match self.style.0 {
+            Some(flavor) => self.to_string_as(flavor),
+            None => self.build_string(),
+        }
+    }
+
+    fn parse_parts(text: &str, separators: &[char], drives: bool) -> Self {
+        let directory = text.ends_with(separators) || text.ends_with(crate::UP);
+        let components: Vec<String> = text
+            .split(separators)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect();
+        let (components, absolute) = match drives {
+            true => PurePath::split_drive(components),
+            false => (components, text.starts_with(FS)),
+        };
+        Self {
+            path: Self::build_pathbuf(&components, absolute),
+            components,
+            absolute,
+            directory,
+            style: Style::default(),
+        }
+    }
+}
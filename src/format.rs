@@ -0,0 +1,119 @@
+//! Filling text templates from the parts of a path.
+
+use crate::OsPath;
+use std::fmt;
+
+/// The error returned when a template passed to `OsPath::format()` cannot be filled.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum FormatError {
+    /// The template names a field that does not exist.
+    UnknownField(String),
+    /// A `{` was never closed, or a `}` was never opened.
+    UnbalancedBrace(usize),
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormatError::UnknownField(field) => write!(f, "unknown template field `{}`", field),
+            FormatError::UnbalancedBrace(i) => write!(f, "unbalanced brace at byte {}", i),
+        }
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+/// Template Methods
+impl OsPath {
+    /// Fills a template from the parts of the path. See `format_with()`.
+    /// ```rust
+    /// #[cfg(unix)]
+    /// {
+    /// use os_path::OsPath;
+    ///
+    /// let path = OsPath::from("/photos/2024/beach.jpeg");
+    /// assert_eq!(path.format("{dir}/{stem}_small.{ext}").unwrap(), "/photos/2024/beach_small.jpeg");
+    /// assert_eq!(OsPath::from("/swapfile").format("{dir}/{name}").unwrap(), "/swapfile");
+    /// }
+    /// ```
+    pub fn format(&self, template: &str) -> Result<String, FormatError> {
+        self.format_with(template, &[])
+    }
+
+    /// Fills a template from the parts of the path and the given extra fields.
+    ///
+    /// `{dir}` is the parent directory without a trailing separator, and so empty for an item in the root, which
+    /// keeps `{dir}/{name}` from doubling the separator. `{name}` is the last item, `{stem}` and `{ext}` the last
+    /// item split at its final dot (`ext` excludes the dot and is empty if there is none), and `{drive}` the drive of
+    /// an absolute Windows path, empty elsewhere. Extra fields take precedence over these. Write `{{` and `}}` for
+    /// literal braces.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// let path = OsPath::from("shots/frame.png");
+    /// let renamed = path.format_with("{stem}_{n}.{ext}", &[("n", "0042")]).unwrap();
+    /// assert_eq!(renamed, "frame_0042.png");
+    ///
+    /// assert!(path.format("{size}").is_err());
+    /// ```
+    pub fn format_with(
+        &self,
+        template: &str,
+        fields: &[(&str, &str)],
+    ) -> Result<String, FormatError> {
+        let mut out = String::with_capacity(template.len());
+        let mut chars = template.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '{' if chars.peek().map(|(_, c)| *c) == Some('{') => {
+                    chars.next();
+                    out.push('{');
+                }
+                '}' if chars.peek().map(|(_, c)| *c) == Some('}') => {
+                    chars.next();
+                    out.push('}');
+                }
+                '{' => {
+                    let mut field = String::new();
+                    loop {
+                        match chars.next() {
+                            Some((_, '}')) => break,
+                            Some((_, c)) => field.push(c),
+                            None => return Err(FormatError::UnbalancedBrace(i)),
+                        }
+                    }
+                    out.push_str(&self.template_field(&field, fields)?);
+                }
+                '}' => return Err(FormatError::UnbalancedBrace(i)),
+                c => out.push(c),
+            }
+        }
+        Ok(out)
+    }
+
+    fn template_field(&self, field: &str, fields: &[(&str, &str)]) -> Result<String, FormatError> {
+        if let Some((_, value)) = fields.iter().find(|(name, _)| *name == field) {
+            return Ok(value.to_string());
+        }
+        let value = match field {
+            "dir" => match self.parent() {
+                // The root without its trailing separator is nothing at all.
+                Some(parent) if parent.components.is_empty() => String::new(),
+                Some(mut parent) => {
+                    parent.directory = false;
+                    parent.to_string()
+                }
+                None => String::new(),
+            },
+            "name" => self.name().cloned().unwrap_or_default(),
+            "stem" => self.file_stem().unwrap_or_default(),
+            "ext" => match self.split_extension() {
+                Some((_, ext)) => ext.to_string(),
+                None => String::new(),
+            },
+            "drive" => self.drive().map_or_else(String::new, |d| format!("{}:", d)),
+            _ => return Err(FormatError::UnknownField(field.to_string())),
+        };
+        Ok(value)
+    }
+}
@@ -0,0 +1,76 @@
+//! Reading and writing serde data files.
+
+use crate::error::IoResultExt;
+use crate::OsPath;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::io;
+
+fn invalid_data<E: std::error::Error + Send + Sync + 'static>(path: &OsPath, e: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", path, e))
+}
+
+/// Data File Methods
+impl OsPath {
+    /// Reads the file and parses it as JSON. Parse errors are returned as `io::ErrorKind::InvalidData`, naming the
+    /// file.
+    /// ```rust
+    /// use os_path::OsPath;
+    /// use std::collections::BTreeMap;
+    ///
+    /// let path = OsPath::from(std::env::temp_dir()).join("os_path_json_doc.json");
+    /// let mut config = BTreeMap::new();
+    /// config.insert("theme".to_string(), "dark".to_string());
+    ///
+    /// path.write_json(&config).unwrap();
+    /// let loaded: BTreeMap<String, String> = path.read_json().unwrap();
+    /// assert_eq!(loaded, config);
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    #[cfg(feature = "json")]
+    pub fn read_json<T: DeserializeOwned>(&self) -> io::Result<T> {
+        let text = std::fs::read(self).with_path_context("read", self)?;
+        serde_json::from_slice(&text).map_err(|e| invalid_data(self, e))
+    }
+
+    /// Writes the value as pretty-printed JSON, atomically as `write_atomic()` does.
+    #[cfg(feature = "json")]
+    pub fn write_json<T: Serialize + ?Sized>(&self, value: &T) -> io::Result<()> {
+        let mut text = serde_json::to_vec_pretty(value).map_err(|e| invalid_data(self, e))?;
+        text.push(b'\n');
+        self.write_atomic(text)
+    }
+
+    /// Reads the file and parses it as TOML. Parse errors are returned as `io::ErrorKind::InvalidData`, naming the
+    /// file.
+    /// ```rust
+    /// use os_path::OsPath;
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    /// struct Config {
+    ///     name: String,
+    ///     retries: u32,
+    /// }
+    ///
+    /// let path = OsPath::from(std::env::temp_dir()).join("os_path_toml_doc.toml");
+    /// let config = Config { name: "app".to_string(), retries: 3 };
+    ///
+    /// path.write_toml(&config).unwrap();
+    /// assert_eq!(std::fs::read_to_string(&path).unwrap(), "name = \"app\"\nretries = 3\n");
+    /// assert_eq!(path.read_toml::<Config>().unwrap(), config);
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    #[cfg(feature = "toml")]
+    pub fn read_toml<T: DeserializeOwned>(&self) -> io::Result<T> {
+        let text = std::fs::read_to_string(self).with_path_context("read", self)?;
+        toml::from_str(&text).map_err(|e| invalid_data(self, e))
+    }
+
+    /// Writes the value as TOML, atomically as `write_atomic()` does.
+    #[cfg(feature = "toml")]
+    pub fn write_toml<T: Serialize + ?Sized>(&self, value: &T) -> io::Result<()> {
+        let text = toml::to_string_pretty(value).map_err(|e| invalid_data(self, e))?;
+        self.write_atomic(text)
+    }
+}
@@ -0,0 +1,941 @@
+//! Pluggable filesystem access, so path code can run against the real disk or an in-memory tree.
+
+use crate::error::IoResultExt;
+use crate::filter::PathFilter;
+use crate::subtree::SubtreeSelector;
+use crate::OsPath;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// The kind of entry a `Metadata` describes.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EntryKind {
+    File,
+    Dir,
+    Symlink,
+}
+
+/// A small, backend independent description of a filesystem entry.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Metadata {
+    kind: EntryKind,
+    len: u64,
+    modified: Option<SystemTime>,
+    created: Option<SystemTime>,
+    readonly: bool,
+    mode: Option<u32>,
+}
+
+impl Metadata {
+    /// Returns the kind of entry.
+    pub fn kind(&self) -> EntryKind {
+        self.kind
+    }
+
+    /// Returns true if the entry is a regular file.
+    pub fn is_file(&self) -> bool {
+        self.kind == EntryKind::File
+    }
+
+    /// Returns true if the entry is a directory.
+    pub fn is_dir(&self) -> bool {
+        self.kind == EntryKind::Dir
+    }
+
+    /// Returns true if the entry is a symbolic link.
+    pub fn is_symlink(&self) -> bool {
+        self.kind == EntryKind::Symlink
+    }
+
+    /// Returns the size of the entry in bytes.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns true if the entry has a size of zero bytes.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the last modification time, if the backend records one.
+    pub fn modified(&self) -> Option<SystemTime> {
+        self.modified
+    }
+
+    /// Returns the creation time, if the backend and platform record one.
+    pub fn created(&self) -> Option<SystemTime> {
+        self.created
+    }
+
+    /// Returns true if the entry cannot be written to.
+    pub fn is_readonly(&self) -> bool {
+        self.readonly
+    }
+
+    /// Returns the Unix permission bits, such as `0o644`, or None where there are none.
+    pub fn mode(&self) -> Option<u32> {
+        self.mode
+    }
+}
+
+impl From<std::fs::Metadata> for Metadata {
+    fn from(m: std::fs::Metadata) -> Self {
+        let kind = if m.is_dir() {
+            EntryKind::Dir
+        } else if m.file_type().is_symlink() {
+            EntryKind::Symlink
+        } else {
+            EntryKind::File
+        };
+        #[cfg(unix)]
+        let mode = {
+            use std::os::unix::fs::PermissionsExt;
+            Some(m.permissions().mode() & 0o7777)
+        };
+        #[cfg(not(unix))]
+        let mode = None;
+        Self {
+            kind,
+            len: m.len(),
+            modified: m.modified().ok(),
+            created: m.created().ok(),
+            readonly: m.permissions().readonly(),
+            mode,
+        }
+    }
+}
+
+/// What identifies a file or directory on its filesystem whatever path it is reached by: the device and inode on
+/// Unix, and the canonical path elsewhere. Two paths with the same id name the same entry.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct FileId {
+    #[cfg(unix)]
+    id: (u64, u64),
+    #[cfg(not(unix))]
+    id: std::path::PathBuf,
+}
+
+impl FileId {
+    /// Returns the id of the entry the path names on the real filesystem, following symbolic links.
+    pub fn of<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            let m = std::fs::metadata(path)?;
+            Ok(Self {
+                id: (m.dev(), m.ino()),
+            })
+        }
+        #[cfg(not(unix))]
+        {
+            Ok(Self {
+                id: std::fs::canonicalize(path)?,
+            })
+        }
+    }
+}
+
+/// The error a traversal following symbolic links reports for a link leading back to a directory it is already
+/// inside, in place of that directory's contents.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct LinkCycle {
+    path: OsPath,
+    target: OsPath,
+}
+
+impl LinkCycle {
+    /// Returns the path through the link that closes the cycle.
+    pub fn path(&self) -> &OsPath {
+        &self.path
+    }
+
+    /// Returns the directory the link leads back to.
+    pub fn target(&self) -> &OsPath {
+        &self.target
+    }
+
+    /// Returns the cycle an I/O error reports, or None if it is some other error.
+    pub fn from_io(error: &io::Error) -> Option<&Self> {
+        error.get_ref()?.downcast_ref()
+    }
+
+    pub(crate) fn into_io(path: OsPath, target: OsPath) -> io::Error {
+        io::Error::other(Self { path, target })
+    }
+}
+
+impl fmt::Display for LinkCycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` loops back to `{}`", self.path, self.target)
+    }
+}
+
+impl std::error::Error for LinkCycle {}
+
+/// The filesystem operations OsPath needs in order to inspect a tree.
+///
+/// `read_dir()` returns the entries of a directory sorted by name, with the directory flag of each entry set from
+/// the entry's actual type rather than inferred from a trailing slash.
+pub trait FsBackend {
+    fn metadata(&self, path: &OsPath) -> io::Result<Metadata>;
+
+    fn read_dir(&self, path: &OsPath) -> io::Result<Vec<OsPath>>;
+
+    fn exists(&self, path: &OsPath) -> bool {
+        self.metadata(path).is_ok()
+    }
+
+    /// Returns the id of the entry the path names, for backends where one entry can be reached by several paths.
+    fn file_id(&self, _path: &OsPath) -> Option<FileId> {
+        None
+    }
+}
+
+/// Fails on targets with no operating system underneath, such as WebAssembly in a browser, where `std::fs` calls
+/// always fail and the clock, process id and thread calls made alongside them panic.
+pub(crate) fn require_os() -> io::Result<()> {
+    match cfg!(all(target_family = "wasm", target_os = "unknown")) {
+        true => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "this platform has no filesystem",
+        )),
+        false => Ok(()),
+    }
+}
+
+/// The real filesystem, through `std::fs`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StdFs;
+
+impl FsBackend for StdFs {
+    fn metadata(&self, path: &OsPath) -> io::Result<Metadata> {
+        std::fs::metadata(path)
+            .map(Metadata::from)
+            .with_path_context("read metadata of", path)
+    }
+
+    fn file_id(&self, path: &OsPath) -> Option<FileId> {
+        FileId::of(path).ok()
+    }
+
+    fn read_dir(&self, path: &OsPath) -> io::Result<Vec<OsPath>> {
+        let read = || {
+            let mut entries = Vec::new();
+            for entry in std::fs::read_dir(path)? {
+                let entry = entry?;
+                let mut child = path.join(entry.file_name());
+                if entry.file_type()?.is_dir() {
+                    child.force_dir();
+                }
+                entries.push(child);
+            }
+            entries.sort_by(|a, b| a.name().cmp(&b.name()));
+            Ok(entries)
+        };
+        read().with_path_context("read directory", path)
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Node {
+    File(Vec<u8>, SystemTime),
+    Dir,
+}
+
+/// An in-memory directory tree implementing `FsBackend`, for deterministic tests that never touch the disk.
+///
+/// Paths are resolved before they are stored, and parent directories are created implicitly.
+/// ```rust
+/// use os_path::{MemoryFs, OsPath};
+///
+/// let mut fs = MemoryFs::new();
+/// fs.add_file("/site/index.html", "<h1>hi</h1>").unwrap();
+/// fs.add_dir("/site/assets/").unwrap();
+///
+/// assert!(OsPath::from("/site/").exists_in(&fs));
+/// assert!(!OsPath::from("/site/missing.txt").exists_in(&fs));
+/// assert_eq!(OsPath::from("/site/").read_dir_in(&fs).unwrap().len(), 2);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MemoryFs {
+    nodes: BTreeMap<(bool, Vec<String>), Node>,
+}
+
+impl MemoryFs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a file with the given contents, replacing any existing file at that path. Fails as writing the file on
+    /// disk would: with `NotADirectory` if one of its parents is a file, and with `IsADirectory` if the path is a
+    /// directory.
+    pub fn add_file<P: AsRef<Path>, C: Into<Vec<u8>>>(
+        &mut self,
+        path: P,
+        contents: C,
+    ) -> io::Result<&mut Self> {
+        self.add_file_modified(path, contents, SystemTime::UNIX_EPOCH)
+    }
+
+    /// Adds a file with the given contents and modification time. Fails as `add_file()` does.
+    pub fn add_file_modified<P: AsRef<Path>, C: Into<Vec<u8>>>(
+        &mut self,
+        path: P,
+        contents: C,
+        modified: SystemTime,
+    ) -> io::Result<&mut Self> {
+        let key = Self::key(&OsPath::from(path.as_ref()));
+        if let Some(Node::Dir) = self.nodes.get(&key) {
+            return Err(io::Error::new(
+                io::ErrorKind::IsADirectory,
+                "is a directory",
+            ));
+        }
+        self.add_parents(&key)?;
+        self.nodes
+            .insert(key, Node::File(contents.into(), modified));
+        Ok(self)
+    }
+
+    /// Adds an empty directory, or leaves an existing one as it is. Fails as creating the directory on disk would:
+    /// with `NotADirectory` if one of its parents is a file, and with `AlreadyExists` if the path is a file.
+    pub fn add_dir<P: AsRef<Path>>(&mut self, path: P) -> io::Result<&mut Self> {
+        let key = Self::key(&OsPath::from(path.as_ref()));
+        if let Some(Node::File(..)) = self.nodes.get(&key) {
+            return Err(io::Error::new(io::ErrorKind::AlreadyExists, "file exists"));
+        }
+        self.add_parents(&key)?;
+        self.nodes.insert(key, Node::Dir);
+        Ok(self)
+    }
+
+    /// Returns the contents of a file.
+    pub fn read<P: AsRef<Path>>(&self, path: P) -> io::Result<Vec<u8>> {
+        match self.nodes.get(&Self::key(&OsPath::from(path.as_ref()))) {
+            Some(Node::File(contents, _)) => Ok(contents.clone()),
+            Some(Node::Dir) => Err(io::Error::other("is a directory")),
+            None => Err(Self::not_found()),
+        }
+    }
+
+    /// Creates the missing parents of the key, after checking that none of them is a file, so a failure leaves the
+    /// tree as it was.
+    fn add_parents(&mut self, key: &(bool, Vec<String>)) -> io::Result<()> {
+        let parents = || (0..key.1.len()).map(|i| (key.0, key.1[..i].to_vec()));
+        if parents().any(|parent| matches!(self.nodes.get(&parent), Some(Node::File(..)))) {
+            return Err(io::Error::new(
+                io::ErrorKind::NotADirectory,
+                "not a directory",
+            ));
+        }
+        for parent in parents() {
+            self.nodes.entry(parent).or_insert(Node::Dir);
+        }
+        Ok(())
+    }
+
+    fn key(path: &OsPath) -> (bool, Vec<String>) {
+        let mut path = path.clone();
+        path.resolve();
+        (path.absolute, path.components)
+    }
+
+    fn not_found() -> io::Error {
+        io::Error::new(io::ErrorKind::NotFound, "no such file or directory")
+    }
+}
+
+impl FsBackend for MemoryFs {
+    fn metadata(&self, path: &OsPath) -> io::Result<Metadata> {
+        match self.nodes.get(&Self::key(path)) {
+            Some(Node::File(contents, modified)) => Ok(Metadata {
+                kind: EntryKind::File,
+                len: contents.len() as u64,
+                modified: Some(*modified),
+                created: None,
+                readonly: false,
+                mode: None,
+            }),
+            Some(Node::Dir) => Ok(Metadata {
+                kind: EntryKind::Dir,
+                len: 0,
+                modified: None,
+                created: None,
+                readonly: false,
+                mode: None,
+            }),
+            None => Err(Self::not_found()),
+        }
+    }
+
+    fn read_dir(&self, path: &OsPath) -> io::Result<Vec<OsPath>> {
+        let (absolute, components) = Self::key(path);
+        match self.nodes.get(&(absolute, components.clone())) {
+            Some(Node::Dir) => {}
+            Some(Node::File(..)) => {
+                return Err(io::Error::other("not a directory"));
+            }
+            None => return Err(Self::not_found()),
+        }
+        let entries = self
+            .nodes
+            .iter()
+            .filter(|((a, c), _)| {
+                *a == absolute && c.len() == components.len() + 1 && c.starts_with(&components)
+            })
+            .map(|((_, c), node)| {
+                // The name is already in its escaped form, so it is pushed as is rather than parsed again.
+                let mut child = path.clone();
+                child.components.push(c[c.len() - 1].clone());
+                child.directory = matches!(node, Node::Dir);
+                child.path = OsPath::build_pathbuf(&child.components, child.absolute);
+                child
+            })
+            .collect();
+        Ok(entries)
+    }
+}
+
+/// Hard limits on a traversal, for walking trees that may be hostile or unexpectedly large. A traversal that would
+/// go past a limit stops with an `io::Error` of kind `Other` wrapping a `LimitExceeded`.
+/// ```rust
+/// use os_path::{LimitExceeded, MemoryFs, OsPath, TraversalLimits};
+///
+/// let mut fs = MemoryFs::new();
+/// fs.add_file("/a/b/c/d.txt", "data").unwrap();
+///
+/// let limits = TraversalLimits::new().max_depth(2);
+/// let error = OsPath::from("/a/").walk_in(&fs).limits(limits).find_map(Result::err).unwrap();
+/// assert_eq!(LimitExceeded::from_io(&error), Some(&LimitExceeded::Depth(2)));
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct TraversalLimits {
+    max_depth: Option<usize>,
+    max_entries: Option<u64>,
+    max_bytes: Option<u64>,
+}
+
+impl TraversalLimits {
+    /// Creates limits that allow everything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fails on reaching an entry more than `depth` levels below the starting directory.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Fails on visiting more than `entries` files and directories, including ones a filter leaves out.
+    pub fn max_entries(mut self, entries: u64) -> Self {
+        self.max_entries = Some(entries);
+        self
+    }
+
+    /// Fails once the files visited add up to more than `bytes`.
+    pub fn max_bytes(mut self, bytes: u64) -> Self {
+        self.max_bytes = Some(bytes);
+        self
+    }
+}
+
+/// The limit of a `TraversalLimits` that a traversal went past.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LimitExceeded {
+    /// An entry was deeper than this many levels.
+    Depth(usize),
+    /// More than this many entries were visited.
+    Entries(u64),
+    /// The files visited added up to more than this many bytes.
+    Bytes(u64),
+}
+
+impl LimitExceeded {
+    /// Returns the limit an I/O error reports as exceeded, or None if it is some other error.
+    pub fn from_io(error: &io::Error) -> Option<&Self> {
+        error.get_ref()?.downcast_ref()
+    }
+}
+
+impl fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LimitExceeded::Depth(n) => write!(f, "traversal went deeper than {} levels", n),
+            LimitExceeded::Entries(n) => write!(f, "traversal visited more than {} entries", n),
+            LimitExceeded::Bytes(n) => write!(f, "traversal found more than {} bytes", n),
+        }
+    }
+}
+
+impl std::error::Error for LimitExceeded {}
+
+/// What a traversal has used up of its limits so far.
+#[derive(Debug, Default)]
+pub(crate) struct Budget {
+    limits: TraversalLimits,
+    entries: u64,
+    bytes: u64,
+}
+
+impl Budget {
+    pub(crate) fn new(limits: TraversalLimits) -> Self {
+        Self {
+            limits,
+            ..Self::default()
+        }
+    }
+
+    /// Returns true if the size of each file has to be known.
+    pub(crate) fn counts_bytes(&self) -> bool {
+        self.limits.max_bytes.is_some()
+    }
+
+    /// Records an entry found `depth` levels down holding `bytes`, failing if that goes past a limit.
+    pub(crate) fn charge(&mut self, depth: usize, bytes: u64) -> io::Result<()> {
+        self.entries += 1;
+        self.bytes = self.bytes.saturating_add(bytes);
+        let exceeded = match self.limits {
+            TraversalLimits {
+                max_depth: Some(max),
+                ..
+            } if depth > max => LimitExceeded::Depth(max),
+            TraversalLimits {
+                max_entries: Some(max),
+                ..
+            } if self.entries > max => LimitExceeded::Entries(max),
+            TraversalLimits {
+                max_bytes: Some(max),
+                ..
+            } if self.bytes > max => LimitExceeded::Bytes(max),
+            _ => return Ok(()),
+        };
+        Err(io::Error::other(exceeded))
+    }
+}
+
+/// An iterator over the entries of a directory on the real filesystem, created by `OsPath::read_dir()`.
+///
+/// Entries come in the order the filesystem returns them. Subdirectories, and symbolic links leading to directories,
+/// are yielded as directory paths.
+pub struct ReadDir {
+    dir: OsPath,
+    entries: std::fs::ReadDir,
+}
+
+impl Iterator for ReadDir {
+    type Item = io::Result<OsPath>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let entry = match self.entries.next()? {
+            Ok(entry) => entry,
+            Err(e) => return Some(Err(e).with_path_context("read directory", &self.dir)),
+        };
+        let mut path = self.dir.join(entry.file_name());
+        let is_dir = match entry.file_type() {
+            Ok(kind) if kind.is_symlink() => std::fs::metadata(&path).is_ok_and(|m| m.is_dir()),
+            Ok(kind) => kind.is_dir(),
+            Err(e) => return Some(Err(e).with_path_context("read metadata of", &path)),
+        };
+        if is_dir {
+            path.force_dir();
+        }
+        Some(Ok(path))
+    }
+}
+
+/// A depth-first, pre-order iterator over everything below a directory, created by `OsPath::walk()` or
+/// `OsPath::walk_in()`.
+///
+/// Entries are visited in name order. The starting directory itself is not yielded, and errors reading a
+/// directory are yielded in place of its contents without stopping the walk.
+pub struct Walk<'a> {
+    fs: &'a dyn FsBackend,
+    stack: Vec<io::Result<(OsPath, usize)>>,
+    root_len: usize,
+    max_depth: Option<usize>,
+    filter: Option<PathFilter>,
+    selector: Option<SubtreeSelector>,
+    budget: Budget,
+    root: OsPath,
+    follow_links: bool,
+    ancestors: Vec<(Option<FileId>, OsPath)>,
+    started: bool,
+}
+
+impl<'a> Walk<'a> {
+    fn new(root: &OsPath, fs: &'a dyn FsBackend) -> Self {
+        Self {
+            fs,
+            stack: Vec::new(),
+            root_len: root.components.len(),
+            max_depth: None,
+            filter: None,
+            selector: None,
+            budget: Budget::default(),
+            root: root.clone(),
+            follow_links: false,
+            ancestors: Vec::new(),
+            started: false,
+        }
+    }
+
+    /// Limits how deep the walk descends. A depth of `1` yields only the direct children of the starting directory,
+    /// and a depth of `0` yields nothing.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Only yields entries matching the filter, and skips directories the filter rules out entirely.
+    ///
+    /// The filter sees each entry relative to the starting directory, so its globs are written relative to it too.
+    /// ```rust
+    /// #[cfg(unix)]
+    /// {
+    /// use os_path::{MemoryFs, OsPath, PathFilter};
+    ///
+    /// let mut fs = MemoryFs::new();
+    /// fs.add_file("/p/src/lib.rs", "").unwrap()
+    ///     .add_file("/p/src/notes.md", "").unwrap()
+    ///     .add_file("/p/target/debug/build.rs", "").unwrap()
+    ///     .add_file("/p/.git/config.rs", "").unwrap();
+    ///
+    /// let filter = PathFilter::new().exclude("target").unwrap().extensions(["rs"]);
+    /// let found: Vec<String> = OsPath::from("/p/")
+    ///     .walk_in(&fs)
+    ///     .with_filter(filter)
+    ///     .map(|p| p.unwrap().to_string())
+    ///     .collect();
+    /// assert_eq!(found, vec!["/p/src/lib.rs"]);
+    /// }
+    /// ```
+    pub fn with_filter(mut self, filter: PathFilter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Only yields entries the selector selects, and does not read directories it can select nothing below. The
+    /// selector sees each entry relative to the starting directory. A filter, if also given, must pass as well.
+    /// ```rust
+    /// #[cfg(unix)]
+    /// {
+    /// use os_path::{MemoryFs, OsPath, SubtreeSelector};
+    ///
+    /// let mut fs = MemoryFs::new();
+    /// fs.add_file("/repo/app/main.rs", "").unwrap()
+    ///     .add_file("/repo/app/vendor/dep.rs", "").unwrap()
+    ///     .add_file("/repo/lib/util.rs", "").unwrap();
+    ///
+    /// let selector = SubtreeSelector::new().include("app/").exclude("app/vendor/");
+    /// let found: Vec<String> = OsPath::from("/repo/")
+    ///     .walk_in(&fs)
+    ///     .select(selector)
+    ///     .map(|p| p.unwrap().to_string())
+    ///     .collect();
+    /// assert_eq!(found, vec!["/repo/app/", "/repo/app/main.rs"]);
+    /// }
+    /// ```
+    pub fn select(mut self, selector: SubtreeSelector) -> Self {
+        self.selector = Some(selector);
+        self
+    }
+
+    /// Stops the walk with an error when it goes past one of the limits. Entries a filter leaves out still count.
+    pub fn limits(mut self, limits: TraversalLimits) -> Self {
+        self.budget = Budget::new(limits);
+        self
+    }
+
+    /// Descends into directories reached through symbolic links, which are otherwise yielded but not entered.
+    ///
+    /// A link leading back to a directory the walk is already inside would repeat forever, so in place of its
+    /// contents the walk yields a `LinkCycle` error naming the link and the directory, then carries on.
+    /// ```rust
+    /// #[cfg(unix)]
+    /// {
+    /// use os_path::{LinkCycle, OsPath};
+    ///
+    /// let tmp = OsPath::from(std::env::temp_dir()).join("os_path_follow_doc/");
+    /// std::fs::create_dir_all(tmp.join("a/")).unwrap();
+    /// # let _ = std::fs::remove_file(tmp.join("a/up"));
+    /// std::os::unix::fs::symlink(&tmp, tmp.join("a/up")).unwrap();
+    ///
+    /// let results: Vec<_> = tmp.walk().follow_links(true).collect();
+    /// let cycle = results.iter().find_map(|r| r.as_ref().err().and_then(LinkCycle::from_io)).unwrap();
+    /// assert_eq!(cycle.path(), &tmp.join("a/up/"));
+    /// assert_eq!(cycle.target(), &tmp);
+    /// # std::fs::remove_dir_all(&tmp).unwrap();
+    /// }
+    /// ```
+    pub fn follow_links(mut self, yes: bool) -> Self {
+        self.follow_links = yes;
+        self.ancestors = match yes {
+            true => vec![(self.fs.file_id(&self.root), self.root.clone())],
+            false => Vec::new(),
+        };
+        self
+    }
+
+    fn descend(&mut self, dir: &OsPath, depth: usize) {
+        match self.fs.read_dir(dir) {
+            Ok(entries) => self
+                .stack
+                .extend(entries.into_iter().rev().map(|e| Ok((e, depth + 1)))),
+            Err(e) => self.stack.push(Err(e)),
+        }
+    }
+
+    /// Descends into the directory, unless it is one the walk is already inside when following links.
+    fn enter(&mut self, dir: &OsPath, depth: usize) {
+        if self.follow_links {
+            let id = self.fs.file_id(dir);
+            let cycle = id.as_ref().and_then(|id| {
+                self.ancestors
+                    .iter()
+                    .find(|(seen, _)| seen.as_ref() == Some(id))
+            });
+            if let Some((_, target)) = cycle {
+                let error = LinkCycle::into_io(dir.clone(), target.clone());
+                self.stack.push(Err(error));
+                return;
+            }
+            self.ancestors.push((id, dir.clone()));
+        }
+        self.descend(dir, depth);
+    }
+}
+
+impl Iterator for Walk<'_> {
+    type Item = io::Result<OsPath>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // The starting directory is read on the first call, once the builder methods have had their say.
+        if !self.started {
+            self.started = true;
+            if self.max_depth != Some(0) {
+                let root = self.root.clone();
+                self.descend(&root, 0);
+            }
+        }
+        loop {
+            let (mut path, depth) = match self.stack.pop()? {
+                Ok(entry) => entry,
+                Err(e) => return Some(Err(e)),
+            };
+            if self.follow_links {
+                self.ancestors.truncate(depth);
+                if !path.is_dir() && self.fs.metadata(&path).is_ok_and(|m| m.is_dir()) {
+                    path.force_dir();
+                }
+            }
+            let bytes = match self.budget.counts_bytes() && path.is_file() {
+                true => self.fs.metadata(&path).map_or(0, |m| m.len()),
+                false => 0,
+            };
+            if let Err(e) = self.budget.charge(depth, bytes) {
+                self.stack.clear();
+                return Some(Err(e));
+            }
+            let relative = path.tail_from(self.root_len);
+            let filter = self.filter.as_ref();
+            let selector = self.selector.as_ref();
+            let descend = path.is_dir()
+                && self.max_depth.is_none_or(|max| depth < max)
+                && filter.is_none_or(|f| f.descends_at(&relative, depth))
+                && selector.is_none_or(|s| s.may_select_below(&relative));
+            let yielded = filter.is_none_or(|f| f.matches_at(&relative, depth))
+                && selector.is_none_or(|s| s.selects(&relative));
+            if descend {
+                self.enter(&path, depth);
+            }
+            if yielded {
+                return Some(Ok(path));
+            }
+        }
+    }
+}
+
+/// Filesystem Methods
+impl OsPath {
+    /// Returns true if the path exists in the given backend.
+    /// ```rust
+    /// use os_path::{OsPath, StdFs};
+    ///
+    /// assert!(OsPath::from("src/lib.rs").exists_in(&StdFs));
+    /// ```
+    pub fn exists_in(&self, fs: &dyn FsBackend) -> bool {
+        fs.exists(self)
+    }
+
+    /// Returns the metadata of the path from the given backend.
+    /// ```rust
+    /// use os_path::{MemoryFs, OsPath};
+    ///
+    /// let mut fs = MemoryFs::new();
+    /// fs.add_file("notes.txt", "hello").unwrap();
+    /// assert_eq!(OsPath::from("notes.txt").metadata_in(&fs).unwrap().len(), 5);
+    /// ```
+    pub fn metadata_in(&self, fs: &dyn FsBackend) -> io::Result<Metadata> {
+        fs.metadata(self)
+    }
+
+    /// Returns the metadata of the path on the real filesystem, following symbolic links.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// let metadata = OsPath::from("src/lib.rs").metadata().unwrap();
+    /// assert!(metadata.is_file());
+    /// assert!(metadata.len() > 0);
+    /// ```
+    pub fn metadata(&self) -> io::Result<Metadata> {
+        StdFs.metadata(self)
+    }
+
+    /// Returns the metadata of the path on the real filesystem without following a symbolic link, so that a link
+    /// is described as a link.
+    pub fn symlink_metadata(&self) -> io::Result<Metadata> {
+        std::fs::symlink_metadata(self)
+            .map(Metadata::from)
+            .with_path_context("read metadata of", self)
+    }
+
+    /// Returns the size of the file in bytes, following symbolic links.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// assert_eq!(OsPath::from("Cargo.toml").len().unwrap(), std::fs::read("Cargo.toml").unwrap().len() as u64);
+    /// assert!(OsPath::from("missing.txt").len().is_err());
+    /// ```
+    // An `is_empty()` would read as a question about the path rather than the file, so there is none.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> io::Result<u64> {
+        Ok(self.std_metadata()?.len())
+    }
+
+    /// Returns the last modification time of the path, following symbolic links.
+    pub fn modified(&self) -> io::Result<SystemTime> {
+        self.std_metadata()?
+            .modified()
+            .with_path_context("read modification time of", self)
+    }
+
+    /// Returns the creation time of the path, following symbolic links. Fails where the platform or filesystem does
+    /// not record one.
+    pub fn created(&self) -> io::Result<SystemTime> {
+        self.std_metadata()?
+            .created()
+            .with_path_context("read creation time of", self)
+    }
+
+    /// Returns the permissions of the path, following symbolic links.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// assert!(!OsPath::from("src/lib.rs").permissions().unwrap().readonly());
+    /// ```
+    pub fn permissions(&self) -> io::Result<std::fs::Permissions> {
+        Ok(self.std_metadata()?.permissions())
+    }
+
+    fn std_metadata(&self) -> io::Result<std::fs::Metadata> {
+        std::fs::metadata(self).with_path_context("read metadata of", self)
+    }
+
+    /// Lists the entries of the directory on the real filesystem. Whether each entry is a directory is read from the
+    /// filesystem, so subdirectories come back as directory paths whatever they are named.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// let entries: Vec<OsPath> = OsPath::from("src").read_dir().unwrap().map(Result::unwrap).collect();
+    /// let lib = entries.iter().find(|p| p.name().unwrap() == "lib.rs").unwrap();
+    /// assert!(lib.is_file());
+    /// assert!(entries.iter().all(|p| p.is_dir() == p.to_path().is_dir()));
+    /// ```
+    pub fn read_dir(&self) -> io::Result<ReadDir> {
+        Ok(ReadDir {
+            dir: self.clone(),
+            entries: std::fs::read_dir(self).with_path_context("read directory", self)?,
+        })
+    }
+
+    /// Lists the entries of the directory in the given backend, sorted by name.
+    /// ```rust
+    /// use os_path::{MemoryFs, OsPath};
+    ///
+    /// let mut fs = MemoryFs::new();
+    /// fs.add_file("/a/b.txt", "").unwrap().add_dir("/a/c/").unwrap();
+    ///
+    /// let entries = OsPath::from("/a/").read_dir_in(&fs).unwrap();
+    /// assert!(entries[0].is_file());
+    /// assert!(entries[1].is_dir());
+    /// ```
+    pub fn read_dir_in(&self, fs: &dyn FsBackend) -> io::Result<Vec<OsPath>> {
+        fs.read_dir(self)
+    }
+
+    /// Recursively walks the directory on the real filesystem.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// let found = OsPath::from("src/").walk().filter_map(Result::ok).any(|p| p.name().unwrap() == "lib.rs");
+    /// assert!(found);
+    /// ```
+    pub fn walk(&self) -> Walk<'static> {
+        Walk::new(self, &StdFs)
+    }
+
+    /// Recursively walks the directory in the given backend.
+    /// ```rust
+    /// #[cfg(unix)]
+    /// {
+    /// use os_path::{MemoryFs, OsPath};
+    ///
+    /// let mut fs = MemoryFs::new();
+    /// fs.add_file("/a/b/c.txt", "").unwrap().add_file("/a/d.txt", "").unwrap();
+    ///
+    /// let paths: Vec<String> = OsPath::from("/a/").walk_in(&fs).map(|p| p.unwrap().to_string()).collect();
+    /// assert_eq!(paths, vec!["/a/b/", "/a/b/c.txt", "/a/d.txt"]);
+    /// }
+    /// ```
+    pub fn walk_in<'a>(&self, fs: &'a dyn FsBackend) -> Walk<'a> {
+        Walk::new(self, fs)
+    }
+
+    /// Totals the size in bytes of the files below this directory on disk, failing if the walk goes past a limit.
+    /// ```rust
+    /// use os_path::{OsPath, TraversalLimits};
+    ///
+    /// let size = OsPath::from("src/").dir_size(TraversalLimits::new()).unwrap();
+    /// assert!(size > 0);
+    /// assert!(OsPath::from("src/").dir_size(TraversalLimits::new().max_bytes(10)).is_err());
+    /// ```
+    pub fn dir_size(&self, limits: TraversalLimits) -> io::Result<u64> {
+        self.dir_size_in(limits, &StdFs)
+    }
+
+    /// Totals the size in bytes of the files below this directory in the given backend, failing if the walk goes
+    /// past a limit.
+    pub fn dir_size_in(&self, limits: TraversalLimits, fs: &dyn FsBackend) -> io::Result<u64> {
+        // The byte limit is checked here against the sizes read for the total, rather than by the walk, which would
+        // read the metadata of every file a second time.
+        let walk_limits = TraversalLimits {
+            max_bytes: None,
+            ..limits
+        };
+        let mut total: u64 = 0;
+        for path in self.walk_in(fs).limits(walk_limits) {
+            let path = path?;
+            if path.is_file() {
+                total = total.saturating_add(fs.metadata(&path)?.len());
+                if let Some(max) = limits.max_bytes.filter(|max| total > *max) {
+                    return Err(io::Error::other(LimitExceeded::Bytes(max)));
+                }
+            }
+        }
+        Ok(total)
+    }
+}
@@ -0,0 +1,485 @@
+//! Shell style wildcard patterns matched against paths.
+
+use crate::fs::{FsBackend, StdFs, Walk};
+use crate::OsPath;
+use regex::RegexSet;
+use std::fmt;
+use std::io;
+
+/// The error returned when a glob pattern cannot be compiled.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct GlobError {
+    pub(crate) pattern: String,
+    pub(crate) reason: &'static str,
+    pub(crate) component: Option<String>,
+}
+
+impl GlobError {
+    pub(crate) fn new(pattern: &str, reason: &'static str) -> Self {
+        Self {
+            pattern: pattern.to_string(),
+            reason,
+            component: None,
+        }
+    }
+
+    /// Names the component of the pattern at fault.
+    pub(crate) fn at(mut self, component: &str) -> Self {
+        self.component = Some(component.to_string());
+        self
+    }
+}
+
+impl fmt::Display for GlobError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid glob `{}`: {}", self.pattern, self.reason)
+    }
+}
+
+impl std::error::Error for GlobError {}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum Token {
+    Char(char),
+    AnyChar,
+    AnyChars,
+    Class(bool, Vec<(char, char)>),
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum Segment {
+    AnyComponents,
+    Component(Vec<Token>),
+}
+
+/// A compiled wildcard pattern.
+///
+/// Patterns are written with `/` separators on every platform and support `?` (one character), `*` (any run of
+/// characters within a component), `**` (any number of whole components), and `[abc]`, `[a-z]`, `[!abc]`
+/// character classes. A pattern without a `/` is matched against the name of the path only, so `*.txt` matches
+/// `notes.txt` in any directory. Any other pattern is matched against the whole path, and must start with `/` to
+/// match absolute paths (or with a drive such as `C:/` on Windows), unless it starts with `**`.
+/// ```rust
+/// use os_path::{Glob, OsPath};
+///
+/// let glob = Glob::new("src/**/*.rs").unwrap();
+/// assert!(glob.matches(&OsPath::from("src/lib.rs")));
+/// assert!(glob.matches(&OsPath::from("src/a/b/c.rs")));
+/// assert!(!glob.matches(&OsPath::from("tests/tests.rs")));
+///
+/// assert!(Glob::new("*.[ch]").unwrap().matches(&OsPath::from("/usr/include/stdio.h")));
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Glob {
+    pattern: String,
+    absolute: bool,
+    name_only: bool,
+    segments: Vec<Segment>,
+}
+
+impl Glob {
+    /// Compiles a pattern, failing on an unclosed or empty character class.
+    pub fn new(pattern: &str) -> Result<Self, GlobError> {
+        let bytes = pattern.as_bytes();
+        let absolute = pattern.starts_with('/')
+            || (bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':');
+        let name_only = !pattern.trim_end_matches('/').contains('/');
+        let mut segments = Vec::new();
+        for part in pattern.split('/').filter(|p| !p.is_empty()) {
+            if part == "**" {
+                if segments.last() != Some(&Segment::AnyComponents) {
+                    segments.push(Segment::AnyComponents);
+                }
+                continue;
+            }
+            segments.push(Segment::Component(Self::compile(pattern, part)?));
+        }
+        Ok(Self {
+            pattern: pattern.to_string(),
+            absolute,
+            name_only,
+            segments,
+        })
+    }
+
+    /// Returns the pattern as it was written.
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Returns true if the path matches the pattern. This never touches the filesystem.
+    pub fn matches(&self, path: &OsPath) -> bool {
+        if self.name_only {
+            return match path.components.last() {
+                Some(name) => Self::match_segments(&self.segments, std::slice::from_ref(name)),
+                None => self.segments.is_empty(),
+            };
+        }
+        let floating = !self.absolute && self.segments.first() == Some(&Segment::AnyComponents);
+        if self.absolute != path.absolute && !floating {
+            return false;
+        }
+        Self::match_segments(&self.segments, &path.components)
+    }
+
+    fn compile(pattern: &str, part: &str) -> Result<Vec<Token>, GlobError> {
+        let mut tokens = Vec::new();
+        let mut chars = part.chars().peekable();
+        while let Some(c) = chars.next() {
+            let token = match c {
+                '?' => Token::AnyChar,
+                '*' => {
+                    while chars.peek() == Some(&'*') {
+                        chars.next();
+                    }
+                    Token::AnyChars
+                }
+                '[' => {
+                    let negated = matches!(chars.peek(), Some('!') | Some('^'));
+                    if negated {
+                        chars.next();
+                    }
+                    let mut ranges = Vec::new();
+                    let mut closed = false;
+                    while let Some(c) = chars.next() {
+                        if c == ']' && !ranges.is_empty() {
+                            closed = true;
+                            break;
+                        }
+                        if chars.peek() == Some(&'-') {
+                            let mut lookahead = chars.clone();
+                            lookahead.next();
+                            match lookahead.peek() {
+                                Some(&end) if end != ']' => {
+                                    chars.next();
+                                    chars.next();
+                                    ranges.push((c, end));
+                                    continue;
+                                }
+                                _ => {}
+                            }
+                        }
+                        ranges.push((c, c));
+                    }
+                    if !closed {
+                        return Err(GlobError::new(pattern, "unclosed character class").at(part));
+                    }
+                    Token::Class(negated, ranges)
+                }
+                c => Token::Char(c),
+            };
+            tokens.push(token);
+        }
+        Ok(tokens)
+    }
+
+    fn match_segments(segments: &[Segment], components: &[String]) -> bool {
+        match_stars(
+            segments,
+            components,
+            |segment| *segment == Segment::AnyComponents,
+            |segment, component| match segment {
+                Segment::Component(tokens) => {
+                    let chars: Vec<char> = component.chars().collect();
+                    Self::match_tokens(tokens, &chars)
+                }
+                Segment::AnyComponents => true,
+            },
+        )
+    }
+
+    fn match_tokens(tokens: &[Token], chars: &[char]) -> bool {
+        match_stars(
+            tokens,
+            chars,
+            |token| *token == Token::AnyChars,
+            |token, c| Self::match_char(token, *c),
+        )
+    }
+
+    fn match_char(token: &Token, c: char) -> bool {
+        match token {
+            Token::Char(t) => *t == c,
+            Token::AnyChar => true,
+            Token::AnyChars => true,
+            Token::Class(negated, ranges) => {
+                ranges.iter().any(|(lo, hi)| (*lo..=*hi).contains(&c)) != *negated
+            }
+        }
+    }
+}
+
+impl Glob {
+    /// Translates the pattern into a regular expression over `GlobSet::subject()`, matching exactly what `matches()`
+    /// matches.
+    fn to_regex(&self) -> String {
+        let mut regex = String::from("(?s)^");
+        if self.name_only {
+            regex.push_str("/?(?:[^/]*/)*");
+            match self.segments.first() {
+                Some(Segment::Component(tokens)) => Self::push_component(&mut regex, tokens),
+                Some(Segment::AnyComponents) => regex.push_str("[^/]+/"),
+                None => regex.truncate(regex.len() - "(?:[^/]*/)*".len()),
+            }
+        } else {
+            let floating = !self.absolute && self.segments.first() == Some(&Segment::AnyComponents);
+            if floating {
+                regex.push_str("/?");
+            } else if self.absolute {
+                regex.push('/');
+            }
+            for segment in &self.segments {
+                match segment {
+                    Segment::Component(tokens) => Self::push_component(&mut regex, tokens),
+                    Segment::AnyComponents => regex.push_str("(?:[^/]*/)*"),
+                }
+            }
+        }
+        regex.push('$');
+        regex
+    }
+
+    fn push_component(regex: &mut String, tokens: &[Token]) {
+        for token in tokens {
+            match token {
+                Token::Char(c) => regex.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+                Token::AnyChar => regex.push_str("[^/]"),
+                Token::AnyChars => regex.push_str("[^/]*"),
+                Token::Class(negated, ranges) => {
+                    // A backwards range such as `z-a` holds nothing, as it does for `matches()`.
+                    let ranges: Vec<_> = ranges.iter().filter(|(lo, hi)| lo <= hi).collect();
+                    if ranges.is_empty() {
+                        regex.push_str(if *negated { "[^/]" } else { r"[^\s\S]" });
+                        continue;
+                    }
+                    regex.push_str(if *negated { "[^/" } else { "[" });
+                    for (lo, hi) in ranges {
+                        regex.push_str(&regex::escape(lo.encode_utf8(&mut [0; 4])));
+                        if lo != hi {
+                            regex.push('-');
+                            regex.push_str(&regex::escape(hi.encode_utf8(&mut [0; 4])));
+                        }
+                    }
+                    regex.push(']');
+                }
+            }
+        }
+        regex.push('/');
+    }
+}
+
+impl fmt::Display for Glob {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.pattern)
+    }
+}
+
+/// Many wildcard patterns compiled into a single matcher, so that a path is checked against all of them in one pass
+/// rather than one pattern at a time. Each pattern matches exactly the paths its `Glob` would.
+/// ```rust
+/// use os_path::{GlobSet, OsPath};
+///
+/// let set = GlobSet::new(["*.tmp", "target/**", "src/**/*.rs"]).unwrap();
+/// assert!(set.matches_any(&OsPath::from("target/debug/build.log")));
+/// assert_eq!(set.matching_indices(&OsPath::from("src/bin/cache.tmp")), vec![0]);
+/// assert_eq!(set.matching_indices(&OsPath::from("src/main.rs")), vec![2]);
+/// assert!(!set.matches_any(&OsPath::from("README.md")));
+/// ```
+#[derive(Clone, Debug)]
+pub struct GlobSet {
+    globs: Vec<Glob>,
+    set: RegexSet,
+}
+
+impl GlobSet {
+    /// Compiles the patterns, failing on the first one that is not a valid glob.
+    pub fn new<I, S>(patterns: I) -> Result<Self, GlobError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let globs = patterns
+            .into_iter()
+            .map(|p| Glob::new(p.as_ref()))
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::from_globs(globs)
+    }
+
+    /// Combines patterns that were already compiled.
+    pub fn from_globs(globs: Vec<Glob>) -> Result<Self, GlobError> {
+        let set = RegexSet::new(globs.iter().map(Glob::to_regex)).map_err(|_| {
+            let patterns: Vec<&str> = globs.iter().map(Glob::as_str).collect();
+            GlobError::new(&patterns.join(" "), "too many patterns to compile together")
+        })?;
+        Ok(Self { globs, set })
+    }
+
+    /// Returns the patterns in the set, in the order they were given.
+    pub fn globs(&self) -> &[Glob] {
+        &self.globs
+    }
+
+    /// Returns the number of patterns in the set.
+    pub fn len(&self) -> usize {
+        self.globs.len()
+    }
+
+    /// Returns true if the set has no patterns.
+    pub fn is_empty(&self) -> bool {
+        self.globs.is_empty()
+    }
+
+    /// Returns true if the path matches any of the patterns. This never touches the filesystem.
+    pub fn matches_any(&self, path: &OsPath) -> bool {
+        self.set.is_match(&Self::subject(path))
+    }
+
+    /// Returns the positions of the patterns that match the path, in ascending order.
+    pub fn matching_indices(&self, path: &OsPath) -> Vec<usize> {
+        self.set.matches(&Self::subject(path)).into_iter().collect()
+    }
+
+    /// Writes the path as the text the compiled patterns run over: each component followed by `/`, after a leading
+    /// `/` if the path is absolute.
+    fn subject(path: &OsPath) -> String {
+        let mut subject = String::new();
+        if path.absolute {
+            subject.push('/');
+        }
+        for c in &path.components {
+            subject.push_str(c);
+            subject.push('/');
+        }
+        subject
+    }
+}
+
+/// An iterator over the entries below a directory that match a glob, returned by `OsPath::glob()`.
+pub struct GlobWalk<'a> {
+    walk: Walk<'a>,
+    glob: Glob,
+    root_len: usize,
+}
+
+impl Iterator for GlobWalk<'_> {
+    type Item = io::Result<OsPath>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let path = match self.walk.next()? {
+                Ok(path) => path,
+                Err(e) => return Some(Err(e)),
+            };
+            let matched = if self.glob.absolute {
+                self.glob.matches(&path)
+            } else {
+                self.glob.matches(&path.tail_from(self.root_len))
+            };
+            if matched {
+                return Some(Ok(path));
+            }
+        }
+    }
+}
+
+/// Glob Methods
+impl OsPath {
+    /// Returns true if the path matches the wildcard pattern, as `Glob::matches()` does. This never touches the
+    /// filesystem.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// let path = OsPath::from("docs/guide/intro.txt");
+    /// assert!(path.matches_glob("*.txt").unwrap());
+    /// assert!(path.matches_glob("docs/**/intro.*").unwrap());
+    /// assert!(!path.matches_glob("src/**").unwrap());
+    /// assert!(path.matches_glob("[z-a").is_err());
+    /// ```
+    pub fn matches_glob(&self, pattern: &str) -> Result<bool, GlobError> {
+        Ok(Glob::new(pattern)?.matches(self))
+    }
+
+    /// Walks the directory and yields every entry below it that matches the pattern. Relative patterns are matched
+    /// against each entry's path relative to the directory, and patterns without a `/` against its name at any depth,
+    /// as `Glob` describes. Patterns without `**` stop the walk at the depth they can reach.
+    /// ```rust
+    /// use os_path::{OsPath, TestTree};
+    ///
+    /// let logs = TestTree::new()
+    ///     .file("app.log", "")
+    ///     .file("2024/01/db.log", "")
+    ///     .file("2024/01/db.txt", "")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let mut found: Vec<String> = logs
+    ///     .glob("**/*.log")
+    ///     .unwrap()
+    ///     .map(|p| p.unwrap().name().unwrap().clone())
+    ///     .collect();
+    /// found.sort();
+    /// assert_eq!(found, ["app.log", "db.log"]);
+    /// assert_eq!(logs.glob("*/*/db.*").unwrap().count(), 2);
+    /// ```
+    pub fn glob(&self, pattern: &str) -> Result<GlobWalk<'static>, GlobError> {
+        self.glob_in(pattern, &StdFs)
+    }
+
+    /// Walks the directory in the given backend and yields every entry below it that matches the pattern. See
+    /// `glob()`.
+    pub fn glob_in<'a>(
+        &self,
+        pattern: &str,
+        fs: &'a dyn FsBackend,
+    ) -> Result<GlobWalk<'a>, GlobError> {
+        let glob = Glob::new(pattern)?;
+        let mut walk = self.walk_in(fs);
+        let unbounded =
+            glob.name_only || glob.absolute || glob.segments.contains(&Segment::AnyComponents);
+        if !unbounded {
+            walk = walk.max_depth(glob.segments.len());
+        }
+        Ok(GlobWalk {
+            walk,
+            glob,
+            root_len: self.components.len(),
+        })
+    }
+}
+
+/// Matches a pattern in which `is_star` items stand for any run of subject items and every other item for one.
+///
+/// Only the most recent star is ever retried: once a later star matches, whatever an earlier star could give up
+/// the later one could take as well. That keeps matching to O(pattern × subject) where trying every split at
+/// every star is exponential.
+pub(crate) fn match_stars<P, S>(
+    pattern: &[P],
+    subject: &[S],
+    is_star: impl Fn(&P) -> bool,
+    matches: impl Fn(&P, &S) -> bool,
+) -> bool {
+    let (mut p, mut s) = (0, 0);
+    // The star to retry, and the subject position it currently stops at.
+    let mut retry: Option<(usize, usize)> = None;
+    while s < subject.len() {
+        match pattern.get(p) {
+            Some(item) if is_star(item) => {
+                retry = Some((p, s));
+                p += 1;
+            }
+            Some(item) if matches(item, &subject[s]) => {
+                p += 1;
+                s += 1;
+            }
+            _ => match retry {
+                Some((star, stop)) => {
+                    retry = Some((star, stop + 1));
+                    p = star + 1;
+                    s = stop + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(is_star)
+}
@@ -0,0 +1,339 @@
+//! Stable hashing of paths.
+
+use crate::error::IoResultExt;
+use crate::fs::{EntryKind, FsBackend, StdFs};
+use crate::OsPath;
+use std::io::{self, Read};
+use std::time::{SystemTime, UNIX_EPOCH};
+use xxhash_rust::xxh3::{xxh3_128, Xxh3};
+
+/// Hashing Methods
+impl OsPath {
+    /// Returns a 128-bit hash of the path that is identical on every platform, endianness, and crate version.
+    ///
+    /// The hash is XXH3-128 over the UTF-8 bytes of the path rendered with `/` separators, a leading `/` for Unix
+    /// absolute paths, and a trailing `/` for directories. A Windows UNC prefix is written with `/` as well, as
+    /// `to_string_as(Flavor::Posix)` writes it. Anything that is not valid Unicode is hashed in the escaped form the
+    /// path holds it in rather than as U+FFFD, so such paths only hash equal when they compare equal.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// let a = OsPath::from("assets/textures/grass.png");
+    /// let b = OsPath::from("assets\\textures\\grass.png");
+    /// assert_eq!(a.portable_hash(), b.portable_hash());
+    /// assert_eq!(a.portable_hash(), 0x42ae2ae9835f8dc8318bfaa4814b36d3);
+    /// ```
+    pub fn portable_hash(&self) -> u128 {
+        xxh3_128(self.build_portable_key().as_bytes())
+    }
+}
+
+/// Content Addressing Methods
+impl OsPath {
+    /// Returns the fan-out location of `id` below this directory, using `levels` subdirectories of `width`
+    /// characters each taken from the start of `id`, as used by object stores and caches.
+    ///
+    /// Returns None if `id` is shorter than `levels * width` characters, contains a separator, is `.` or `..`, or
+    /// `width` is zero.
+    /// ```rust
+    /// #[cfg(unix)]
+    /// {
+    /// use os_path::OsPath;
+    ///
+    /// let objects = OsPath::from("objects/");
+    /// let path = objects.sharded("abcdef.bin", 2, 2).unwrap();
+    /// assert_eq!(path.to_string(), "objects/ab/cd/abcdef.bin");
+    /// assert_eq!(objects.unsharded(&path, 2, 2).unwrap(), "abcdef.bin");
+    /// }
+    /// ```
+    pub fn sharded(&self, id: &str, levels: usize, width: usize) -> Option<OsPath> {
+        if id.is_empty() || id.contains(['/', '\\']) || id == "." || id == ".." {
+            return None;
+        }
+        let chars: Vec<char> = id.chars().collect();
+        if chars.len() < levels * width || (width == 0 && levels > 0) {
+            return None;
+        }
+        let mut path = self.clone();
+        path.force_dir();
+        for level in chars.chunks(width.max(1)).take(levels) {
+            path.components.push(level.iter().collect());
+        }
+        path.components.push(id.to_string());
+        path.directory = false;
+        path.path = Self::build_pathbuf(&path.components, path.absolute);
+        Some(path)
+    }
+
+    /// Parses a path produced by `sharded()` back into its id, checking that it sits below this directory and that
+    /// its shard directories agree with the id.
+    pub fn unsharded(&self, path: &OsPath, levels: usize, width: usize) -> Option<String> {
+        if path.absolute != self.absolute || !path.components.starts_with(&self.components) {
+            return None;
+        }
+        let tail = &path.components[self.components.len()..];
+        let (id, shards) = tail.split_last()?;
+        if shards.len() != levels {
+            return None;
+        }
+        match self.sharded(id, levels, width) {
+            Some(expected) if expected.components == path.components => Some(id.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// Selects which details `tree_snapshot()` records for each file, beyond its relative path and kind.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct SnapshotOptions {
+    sizes: bool,
+    mtimes: bool,
+}
+
+impl SnapshotOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the size of each file.
+    pub fn sizes(mut self, yes: bool) -> Self {
+        self.sizes = yes;
+        self
+    }
+
+    /// Records the modification time of each file.
+    pub fn mtimes(mut self, yes: bool) -> Self {
+        self.mtimes = yes;
+        self
+    }
+}
+
+/// One entry of a `TreeSnapshot`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SnapshotEntry {
+    /// The entry relative to the snapshot root, with `/` separators and a trailing `/` for directories. Each byte or
+    /// code unit that is not valid Unicode is kept as a NUL followed by its value in hex, so distinct names stay
+    /// distinct.
+    pub path: String,
+    pub kind: EntryKind,
+    pub len: Option<u64>,
+    pub modified: Option<SystemTime>,
+}
+
+/// A deterministic listing of a directory tree, and a digest of it.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TreeSnapshot {
+    entries: Vec<SnapshotEntry>,
+    digest: u128,
+}
+
+impl TreeSnapshot {
+    /// Returns the entries, sorted by their relative path.
+    pub fn entries(&self) -> &[SnapshotEntry] {
+        &self.entries
+    }
+
+    /// Returns the XXH3-128 digest of `listing()`.
+    pub fn digest(&self) -> u128 {
+        self.digest
+    }
+
+    /// Returns the canonical text form of the snapshot that the digest is computed over: one line per entry, holding
+    /// the relative path followed by the size and the modification time (as `seconds.nanoseconds` since the Unix
+    /// epoch) when they were recorded.
+    pub fn listing(&self) -> String {
+        let mut listing = String::new();
+        for entry in &self.entries {
+            listing.push_str(&entry.path);
+            if let Some(len) = entry.len {
+                listing.push_str(&format!(" {}", len));
+            }
+            if let Some(modified) = entry.modified {
+                let since = modified.duration_since(UNIX_EPOCH).unwrap_or_default();
+                listing.push_str(&format!(" {}.{:09}", since.as_secs(), since.subsec_nanos()));
+            }
+            listing.push('\n');
+        }
+        listing
+    }
+}
+
+/// Snapshot Methods
+impl OsPath {
+    /// Takes a snapshot of the directory tree on disk. See `tree_snapshot_in()`.
+    pub fn tree_snapshot(&self, options: SnapshotOptions) -> io::Result<TreeSnapshot> {
+        self.tree_snapshot_in(options, &StdFs)
+    }
+
+    /// Takes a snapshot of everything below the directory in the given backend. The listing is sorted and uses
+    /// portable relative paths, so two equal trees produce the same digest on every platform.
+    /// ```rust
+    /// use os_path::{MemoryFs, OsPath, SnapshotOptions};
+    ///
+    /// let mut fs = MemoryFs::new();
+    /// fs.add_file("/out/b.txt", "bb").unwrap().add_file("/out/a/c.txt", "c").unwrap();
+    ///
+    /// let snapshot = OsPath::from("/out/").tree_snapshot_in(SnapshotOptions::new().sizes(true), &fs).unwrap();
+    /// assert_eq!(snapshot.listing(), "a/\na/c.txt 1\nb.txt 2\n");
+    ///
+    /// fs.add_file("/out/b.txt", "changed").unwrap();
+    /// let changed = OsPath::from("/out/").tree_snapshot_in(SnapshotOptions::new().sizes(true), &fs).unwrap();
+    /// assert_ne!(snapshot.digest(), changed.digest());
+    /// ```
+    pub fn tree_snapshot_in(
+        &self,
+        options: SnapshotOptions,
+        fs: &dyn FsBackend,
+    ) -> io::Result<TreeSnapshot> {
+        let root_len = self.components.len();
+        let mut entries = Vec::new();
+        for path in self.walk_in(fs) {
+            let path = path?;
+            let relative = path.tail_from(root_len);
+            let (kind, len, modified) = if path.is_dir() {
+                (EntryKind::Dir, None, None)
+            } else if options.sizes || options.mtimes {
+                let metadata = fs.metadata(&path)?;
+                (
+                    metadata.kind(),
+                    Some(metadata.len()).filter(|_| options.sizes),
+                    metadata.modified().filter(|_| options.mtimes),
+                )
+            } else {
+                (EntryKind::File, None, None)
+            };
+            entries.push(SnapshotEntry {
+                path: relative.build_portable_key(),
+                kind,
+                len,
+                modified,
+            });
+        }
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let mut snapshot = TreeSnapshot { entries, digest: 0 };
+        snapshot.digest = xxh3_128(snapshot.listing().as_bytes());
+        Ok(snapshot)
+    }
+}
+
+/// A hash function for `hash_file()`. The cryptographic ones are behind the features of the same name.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Algorithm {
+    /// XXH3-128, fast but not cryptographic. Suitable for change detection, not for verifying untrusted data.
+    Xxh3,
+    #[cfg(feature = "sha256")]
+    Sha256,
+    #[cfg(feature = "blake3")]
+    Blake3,
+}
+
+/// The result of hashing a file's contents.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Digest {
+    algorithm: Algorithm,
+    bytes: Vec<u8>,
+}
+
+impl Digest {
+    /// Returns the algorithm that produced the digest.
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    /// Returns the raw digest bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Returns the digest as lowercase hexadecimal, as written in checksum manifests.
+    pub fn to_hex(&self) -> String {
+        self.bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// Returns true if the digest equals the given hexadecimal string, ignoring case.
+    pub fn matches_hex(&self, hex: &str) -> bool {
+        self.to_hex().eq_ignore_ascii_case(hex.trim())
+    }
+}
+
+impl std::fmt::Display for Digest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_hex())
+    }
+}
+
+/// File Hashing Methods
+impl OsPath {
+    /// Hashes the contents of the file, reading it in chunks.
+    /// ```rust
+    /// use os_path::{Algorithm, OsPath};
+    ///
+    /// let path = OsPath::from(std::env::temp_dir()).join("os_path_hash_doc.txt");
+    /// std::fs::write(&path, "hello").unwrap();
+    ///
+    /// let digest = path.hash_file(Algorithm::Xxh3).unwrap();
+    /// assert_eq!(digest.as_bytes().len(), 16);
+    /// assert!(digest.matches_hex(&digest.to_hex().to_uppercase()));
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn hash_file(&self, algorithm: Algorithm) -> io::Result<Digest> {
+        let mut file = std::fs::File::open(self).with_path_context("open", self)?;
+        let mut hasher = Hasher::new(algorithm);
+        let mut buf = vec![0; 64 * 1024];
+        loop {
+            match file.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => hasher.update(&buf[..n]),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e).with_path_context("read", self),
+            }
+        }
+        Ok(Digest {
+            algorithm,
+            bytes: hasher.finish(),
+        })
+    }
+}
+
+enum Hasher {
+    Xxh3(Box<Xxh3>),
+    #[cfg(feature = "sha256")]
+    Sha256(sha2::Sha256),
+    #[cfg(feature = "blake3")]
+    Blake3(Box<blake3::Hasher>),
+}
+
+impl Hasher {
+    fn new(algorithm: Algorithm) -> Self {
+        match algorithm {
+            Algorithm::Xxh3 => Hasher::Xxh3(Box::default()),
+            #[cfg(feature = "sha256")]
+            Algorithm::Sha256 => Hasher::Sha256(sha2::Digest::new()),
+            #[cfg(feature = "blake3")]
+            Algorithm::Blake3 => Hasher::Blake3(Box::default()),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Xxh3(h) => h.update(data),
+            #[cfg(feature = "sha256")]
+            Hasher::Sha256(h) => sha2::Digest::update(h, data),
+            #[cfg(feature = "blake3")]
+            Hasher::Blake3(h) => {
+                h.update(data);
+            }
+        }
+    }
+
+    fn finish(self) -> Vec<u8> {
+        match self {
+            Hasher::Xxh3(h) => h.digest128().to_be_bytes().to_vec(),
+            #[cfg(feature = "sha256")]
+            Hasher::Sha256(h) => sha2::Digest::finalize(h).to_vec(),
+            #[cfg(feature = "blake3")]
+            Hasher::Blake3(h) => h.finalize().as_bytes().to_vec(),
+        }
+    }
+}
@@ -0,0 +1,145 @@
+//! Remembering what a directory tree held, so a later scan reports only what changed.
+
+use crate::fs::{FsBackend, StdFs};
+use crate::watch::WatchEvent;
+use crate::OsPath;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io;
+use std::time::SystemTime;
+
+/// What a `DirIndex` records about one entry. Directories are recorded with a size of zero and no modification
+/// time, so only their appearance and disappearance are reported.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct IndexEntry {
+    pub len: u64,
+    pub modified: Option<SystemTime>,
+}
+
+/// The size and modification time of everything below a directory, as of the last scan.
+///
+/// Entries are keyed by their portable path relative to the scanned directory, so an index can be saved with serde,
+/// for example with `write_json()`, and loaded again on another run or platform to pick up where it left off. Each
+/// byte or code unit of a name that is not valid Unicode is kept in the key as a NUL followed by its value in hex, so
+/// such names never share a key.
+/// ```rust
+/// #[cfg(unix)]
+/// {
+/// use os_path::{DirIndex, MemoryFs, OsPath, WatchEvent};
+///
+/// let mut fs = MemoryFs::new();
+/// fs.add_file("/sync/a.txt", "a").unwrap().add_file("/sync/b.txt", "b").unwrap();
+///
+/// let root = OsPath::from("/sync/");
+/// let mut index = DirIndex::scan_in(&root, &fs).unwrap();
+/// assert_eq!(index.len(), 2);
+///
+/// fs.add_file("/sync/b.txt", "changed").unwrap().add_file("/sync/c.txt", "c").unwrap();
+/// let changes = index.rescan_in(&root, &fs).unwrap();
+/// assert_eq!(
+///     changes,
+///     vec![
+///         WatchEvent::Modified(OsPath::from("/sync/b.txt")),
+///         WatchEvent::Created(OsPath::from("/sync/c.txt")),
+///     ]
+/// );
+/// assert!(index.rescan_in(&root, &fs).unwrap().is_empty());
+/// }
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub struct DirIndex {
+    entries: BTreeMap<String, IndexEntry>,
+}
+
+impl DirIndex {
+    /// Creates an empty index, against which a first `rescan()` reports everything as created.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Indexes everything below the directory on disk.
+    pub fn scan(root: &OsPath) -> io::Result<Self> {
+        Self::scan_in(root, &StdFs)
+    }
+
+    /// Indexes everything below the directory in the given backend.
+    pub fn scan_in(root: &OsPath, fs: &dyn FsBackend) -> io::Result<Self> {
+        let root_len = root.components.len();
+        let mut entries = BTreeMap::new();
+        for path in root.walk_in(fs) {
+            let path = path?;
+            let entry = match path.is_dir() {
+                true => IndexEntry {
+                    len: 0,
+                    modified: None,
+                },
+                false => {
+                    let metadata = fs.metadata(&path)?;
+                    IndexEntry {
+                        len: metadata.len(),
+                        modified: metadata.modified(),
+                    }
+                }
+            };
+            entries.insert(path.tail_from(root_len).build_portable_key(), entry);
+        }
+        Ok(Self { entries })
+    }
+
+    /// Scans the directory on disk again, returning what was created, modified, or removed since the last scan, and
+    /// updates the index to match. See `rescan_in()`.
+    pub fn rescan(&mut self, root: &OsPath) -> io::Result<Vec<WatchEvent>> {
+        self.rescan_in(root, &StdFs)
+    }
+
+    /// Scans the directory in the given backend again, returning what was created, modified, or removed since the
+    /// last scan, ordered by path, and updates the index to match. A file counts as modified when its size or
+    /// modification time differs. If the scan fails, the index is left as it was.
+    pub fn rescan_in(&mut self, root: &OsPath, fs: &dyn FsBackend) -> io::Result<Vec<WatchEvent>> {
+        let current = Self::scan_in(root, fs)?;
+        let mut changes = Vec::new();
+        for (key, entry) in &current.entries {
+            match self.entries.get(key) {
+                None => changes.push((key, WatchEvent::Created(root.join_os(&from_key(key))))),
+                Some(old) if old != entry => {
+                    changes.push((key, WatchEvent::Modified(root.join_os(&from_key(key)))))
+                }
+                Some(_) => {}
+            }
+        }
+        for key in self.entries.keys() {
+            if !current.entries.contains_key(key) {
+                changes.push((key, WatchEvent::Removed(root.join_os(&from_key(key)))));
+            }
+        }
+        changes.sort_by(|a, b| a.0.cmp(b.0));
+        let changes = changes.into_iter().map(|(_, change)| change).collect();
+        *self = current;
+        Ok(changes)
+    }
+
+    /// Returns what was recorded for the path, given relative to the scanned directory.
+    pub fn get(&self, path: &OsPath) -> Option<&IndexEntry> {
+        self.entries.get(&path.build_portable_key())
+    }
+
+    /// Returns the recorded paths, relative to the scanned directory and sorted by their portable form.
+    pub fn paths(&self) -> Vec<OsPath> {
+        self.entries.keys().map(|key| from_key(key)).collect()
+    }
+
+    /// Returns the number of entries recorded.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if nothing is recorded.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Parses a key back into the path it was made from, escapes and all.
+fn from_key(key: &str) -> OsPath {
+    OsPath::from(std::path::PathBuf::from(crate::raw::encode(key)))
+}
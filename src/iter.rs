@@ -0,0 +1,185 @@
+//! Adapters for iterators of paths, such as the results of `walk()` and `read_dir()`, that work one path at a time.
+
+use crate::{OsPath, PurePath};
+use std::collections::HashSet;
+use std::iter::FusedIterator;
+
+/// Adapters for any iterator of `OsPath`. The walking iterators yield `io::Result<OsPath>`, so errors are dealt with
+/// first, with `filter_map(Result::ok)` or `map_while(Result::ok)`.
+/// ```rust
+/// #[cfg(unix)]
+/// {
+/// use os_path::{MemoryFs, OsPath, PathIterExt};
+///
+/// let mut fs = MemoryFs::new();
+/// fs.add_file("/site/img/b.png", "").unwrap().add_file("/site/img/a.png", "").unwrap().add_file("/site/index.html", "").unwrap();
+///
+/// let images: Vec<String> = OsPath::from("/site/")
+///     .walk_in(&fs)
+///     .filter_map(Result::ok)
+///     .filter_ext("png")
+///     .relative_to("/site/")
+///     .map(|p| p.to_string())
+///     .collect();
+/// assert_eq!(images, vec!["img/a.png", "img/b.png"]);
+/// }
+/// ```
+pub trait PathIterExt: Iterator<Item = OsPath> + Sized {
+    /// Keeps the files whose extension, as `extension()` returns it without the dot, is `ext`. Directories are
+    /// dropped.
+    fn filter_ext(self, ext: &str) -> FilterExt<Self> {
+        FilterExt {
+            iter: self,
+            ext: ext.to_string(),
+        }
+    }
+
+    /// Keeps the paths that are `prefix` or below it, compared component by component as `strip_prefix()` does.
+    fn under<P: Into<OsPath>>(self, prefix: P) -> Under<Self> {
+        Under {
+            iter: self,
+            prefix: prefix.into(),
+        }
+    }
+
+    /// Turns each path into the relative path leading to it from the directory `base`, as `OsPath::relative_to()`
+    /// does, and drops the paths that have none.
+    fn relative_to<P: Into<OsPath>>(self, base: P) -> RelativeTo<Self> {
+        RelativeTo {
+            iter: self,
+            base: base.into(),
+        }
+    }
+
+    /// Collects the paths and returns them in `cmp_natural()` order, so `page2` comes before `page10`.
+    fn sorted_natural(self) -> std::vec::IntoIter<OsPath> {
+        let mut paths: Vec<OsPath> = self.collect();
+        paths.sort_by(OsPath::cmp_natural);
+        paths.into_iter()
+    }
+
+    /// Drops each path that is, or is below, a path already yielded. A walk lists a directory before its contents,
+    /// so on a walk this keeps only the topmost of the paths that pass the earlier adapters. For paths in any order,
+    /// use the `dedup_ancestors()` function.
+    fn dedup_ancestors(self) -> DedupAncestors<Self> {
+        DedupAncestors {
+            iter: self,
+            relative: HashSet::new(),
+            absolute: HashSet::new(),
+        }
+    }
+}
+
+impl<I: Iterator<Item = OsPath>> PathIterExt for I {}
+
+/// Reduces a list of paths to the topmost ones, dropping every path that is, or is below, another path in the list,
+/// as when turning a selection of files and folders into the shortest list of trees to copy or back up. The paths
+/// come back sorted.
+///
+/// Paths are compared component by component as given, as `strip_prefix()` compares them, so a path is only dropped
+/// if an ancestor is spelled the same way; `resolve()` or `canonicalize()` them first to catch other spellings.
+/// ```rust
+/// #[cfg(unix)]
+/// {
+/// use os_path::{dedup_ancestors, OsPath};
+///
+/// let roots = dedup_ancestors(["/a/b/c", "/x/y", "/a", "/a/b", "/x/y/"]);
+/// assert_eq!(roots, vec![OsPath::from("/a"), OsPath::from("/x/y")]);
+/// }
+/// ```
+pub fn dedup_ancestors<I>(paths: I) -> Vec<OsPath>
+where
+    I: IntoIterator,
+    I::Item: Into<OsPath>,
+{
+    let mut paths: Vec<OsPath> = paths.into_iter().map(Into::into).collect();
+    // An ancestor's components are a prefix of its descendants', so sorting puts it first.
+    paths.sort();
+    paths.into_iter().dedup_ancestors().collect()
+}
+
+/// The iterator returned by `PathIterExt::filter_ext()`.
+#[derive(Clone, Debug)]
+pub struct FilterExt<I> {
+    iter: I,
+    ext: String,
+}
+
+impl<I: Iterator<Item = OsPath>> Iterator for FilterExt<I> {
+    type Item = OsPath;
+
+    fn next(&mut self) -> Option<OsPath> {
+        let ext = self.ext.as_str();
+        self.iter.find(|p| p.extension().is_some_and(|e| e == ext))
+    }
+}
+
+impl<I: FusedIterator<Item = OsPath>> FusedIterator for FilterExt<I> {}
+
+/// The iterator returned by `PathIterExt::under()`.
+#[derive(Clone, Debug)]
+pub struct Under<I> {
+    iter: I,
+    prefix: OsPath,
+}
+
+impl<I: Iterator<Item = OsPath>> Iterator for Under<I> {
+    type Item = OsPath;
+
+    fn next(&mut self) -> Option<OsPath> {
+        let prefix = &self.prefix;
+        self.iter.find(|p| p.is_within(prefix))
+    }
+}
+
+impl<I: FusedIterator<Item = OsPath>> FusedIterator for Under<I> {}
+
+/// The iterator returned by `PathIterExt::relative_to()`.
+#[derive(Clone, Debug)]
+pub struct RelativeTo<I> {
+    iter: I,
+    base: OsPath,
+}
+
+impl<I: Iterator<Item = OsPath>> Iterator for RelativeTo<I> {
+    type Item = OsPath;
+
+    fn next(&mut self) -> Option<OsPath> {
+        let base = &self.base;
+        self.iter.find_map(|p| p.relative_to(base))
+    }
+}
+
+impl<I: FusedIterator<Item = OsPath>> FusedIterator for RelativeTo<I> {}
+
+/// The iterator returned by `PathIterExt::dedup_ancestors()`.
+#[derive(Clone, Debug)]
+pub struct DedupAncestors<I> {
+    iter: I,
+    relative: HashSet<Vec<String>>,
+    absolute: HashSet<Vec<String>>,
+}
+
+impl<I: Iterator<Item = OsPath>> Iterator for DedupAncestors<I> {
+    type Item = OsPath;
+
+    fn next(&mut self) -> Option<OsPath> {
+        loop {
+            let path = self.iter.next()?;
+            let roots = match path.absolute {
+                true => &mut self.absolute,
+                false => &mut self.relative,
+            };
+            let covered = (0..=path.components.len()).any(|n| {
+                roots.contains(&path.components[..n])
+                    && !PurePath::climbs_out(&path.components[n..])
+            });
+            if !covered {
+                roots.insert(path.components.clone());
+                return Some(path);
+            }
+        }
+    }
+}
+
+impl<I: FusedIterator<Item = OsPath>> FusedIterator for DedupAncestors<I> {}
@@ -6,7 +6,7 @@
 //! PathBuf's issue of returning to you the exact string you passed to it, even if it's incorrect for the current
 //! platform.
 //! ```rust
-//! #[cfg(unix)]
+//! #[cfg(all(unix, feature = "std"))]
 //! {
 //! // Standard Library
 //! use std::path::PathBuf;
@@ -27,7 +27,7 @@
 //!
 //!
 //! ```rust
-//! #[cfg(unix)]
+//! #[cfg(all(unix, feature = "std"))]
 //! {
 //! // Standard Library
 //! use std::path::PathBuf;
@@ -66,7 +66,7 @@
 //! If you `join()` or `push()` a path that starts with `..`, OsPath will traverse the path, and build the correct path.
 //!
 //! ```rust
-//! #[cfg(unix)]
+//! #[cfg(all(unix, feature = "std"))]
 //! {
 //! // Standard Library
 //! use std::path::PathBuf;
@@ -89,7 +89,7 @@
 //! OsPath can handle multiple `..` in a row, and will traverse the path correctly.
 //!
 //! ```rust
-//! #[cfg(unix)]
+//! #[cfg(all(unix, feature = "std"))]
 //! {
 //! use os_path::OsPath;
 //!
@@ -104,7 +104,7 @@
 //! path, and build the correct path, skipping over the file.
 //!
 //! ```rust
-//! #[cfg(unix)]
+//! #[cfg(all(unix, feature = "std"))]
 //! {
 //! use os_path::OsPath;
 //!
@@ -115,20 +115,274 @@
 //! }
 //! ```
 //!
+//! A `..` that would climb above the start of a relative path is kept rather than lost, while an absolute path stops
+//! at its root, the way the operating system treats `/..`.
+//!
+//! ```rust
+//! #[cfg(all(unix, feature = "std"))]
+//! {
+//! use os_path::OsPath;
+//!
+//! assert_eq!(OsPath::from("a/").join("../../x").to_string(), "../x");
+//! assert_eq!(OsPath::from("/a/").join("../../x").to_string(), "/x");
+//! }
+//! ```
+//!
+//! # Names That Are Not Unicode
+//!
+//! A path read from the operating system may hold names that are not valid Unicode. OsPath keeps them exactly, so
+//! `as_os_str()` and `to_pathbuf()` give back the original bytes, even after the path has been joined or resolved. The
+//! text of such a name shows each invalid byte (or on Windows, unpaired surrogate) as a NUL followed by hex digits,
+//! which can never appear in a real name, while `Display` shows U+FFFD in its place.
+//!
 //! # File And Directory Handling
 //!
 //! If the path ends in a `/` or `\\` OsPath assumes this is a directory, otherwise it's a file.
 //!
+//! # Without the Standard Library
+//!
+//! The `std` feature, on by default, brings in `OsPath` and everything that reads or writes the filesystem. Without
+//! it the crate is `no_std` and only needs `alloc`, offering `PurePath`, which parses, joins, resolves and displays
+//! paths the same way `OsPath` does.
 
-#[cfg(windows)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(all(windows, feature = "std"))]
 use regex::Regex;
+#[cfg(feature = "std")]
 use serde::de::{self, Visitor};
+#[cfg(feature = "std")]
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use std::ffi::OsStr;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+#[cfg(feature = "std")]
+use std::ffi::{OsStr, OsString};
+#[cfg(feature = "std")]
 use std::fmt;
+#[cfg(feature = "std")]
 use std::path::{Path, PathBuf};
 
-#[cfg(unix)]
+#[cfg(feature = "std")]
+mod ancestor;
+#[cfg(feature = "async")]
+mod async_fs;
+#[cfg(feature = "std")]
+mod base;
+#[cfg(feature = "std")]
+mod builder;
+#[cfg(feature = "std")]
+mod case;
+#[cfg(feature = "std")]
+mod compare;
+mod components;
+#[cfg(feature = "std")]
+mod copy;
+#[cfg(feature = "std")]
+mod decode;
+#[cfg(feature = "miette")]
+mod diagnostic;
+#[cfg(feature = "std")]
+mod editorconfig;
+#[cfg(feature = "std")]
+mod env;
+#[cfg(feature = "std")]
+mod error;
+#[cfg(feature = "std")]
+mod expand;
+#[cfg(feature = "std")]
+mod file_url;
+#[cfg(feature = "std")]
+mod filter;
+#[cfg(feature = "std")]
+mod fixture;
+#[cfg(feature = "std")]
+mod flavor;
+#[cfg(feature = "std")]
+mod format;
+#[cfg(any(feature = "json", feature = "toml"))]
+mod formats;
+#[cfg(feature = "std")]
+mod fs;
+#[cfg(feature = "std")]
+mod glob;
+#[cfg(feature = "std")]
+mod hash;
+#[cfg(feature = "std")]
+mod index;
+#[cfg(feature = "std")]
+mod iter;
+#[cfg(feature = "std")]
+mod link;
+#[cfg(feature = "std")]
+mod lock;
+#[cfg(feature = "std")]
+mod name;
+#[cfg(feature = "std")]
+mod normalize;
+#[cfg(feature = "std")]
+mod parallel;
+#[cfg(feature = "std")]
+mod pathspec;
+#[cfg(feature = "std")]
+mod policy;
+#[cfg(feature = "std")]
+mod preserve;
+#[cfg(feature = "std")]
+mod process;
+#[cfg(feature = "std")]
+mod prune;
+mod pure;
+mod raw;
+#[cfg(feature = "std")]
+mod rel_id;
+#[cfg(feature = "std")]
+mod rename;
+#[cfg(feature = "std")]
+mod rotate;
+#[cfg(feature = "std")]
+mod router;
+#[cfg(feature = "std")]
+mod sanitize;
+#[cfg(feature = "std")]
+mod secure;
+#[cfg(feature = "std")]
+mod sequence;
+#[cfg(feature = "std")]
+mod serde_forms;
+#[cfg(feature = "std")]
+mod set;
+#[cfg(feature = "std")]
+mod short_name;
+#[cfg(feature = "std")]
+mod subtree;
+#[cfg(feature = "synthetic")]
+mod synthetic;
+#[cfg(feature = "std")]
+mod transaction;
+#[cfg(feature = "std")]
+mod tree;
+#[cfg(feature = "std")]
+mod usage;
+#[cfg(feature = "std")]
+mod validate;
+#[cfg(feature = "std")]
+mod vfs;
+#[cfg(feature = "std")]
+mod watch;
+#[cfg(feature = "std")]
+mod web;
+#[cfg(feature = "std")]
+mod write;
+
+#[cfg(feature = "std")]
+pub use ancestor::common_ancestor;
+#[cfg(feature = "std")]
+pub use base::BasePath;
+#[cfg(feature = "std")]
+pub use builder::{BuildError, OsPathBuilder};
+#[cfg(feature = "std")]
+pub use case::CaseStyle;
+#[cfg(feature = "std")]
+pub use compare::CaseSensitivity;
+pub use components::Components;
+#[cfg(feature = "std")]
+pub use copy::{CopyOptions, Overwrite, Symlinks};
+#[cfg(feature = "std")]
+pub use decode::{DecodeOptions, InvalidBytes};
+#[cfg(feature = "std")]
+pub use editorconfig::EditorConfigGlob;
+#[cfg(feature = "std")]
+pub use env::{EnvPaths, Environment, ProcessEnv};
+#[cfg(feature = "std")]
+pub use error::{IoResultExt, PathError};
+#[cfg(feature = "std")]
+pub use expand::{expand_to_files, expand_to_files_in};
+#[cfg(feature = "std")]
+pub use file_url::FileUrlError;
+#[cfg(feature = "std")]
+pub use filter::{HiddenPolicy, PathFilter};
+#[cfg(feature = "std")]
+#[doc(hidden)]
+pub use fixture::__path_mismatch;
+#[cfg(feature = "std")]
+pub use fixture::{BuiltTree, TestTree};
+#[cfg(feature = "std")]
+use flavor::Style;
+#[cfg(feature = "std")]
+pub use flavor::{Flavor, PathDisplay, Separator};
+#[cfg(feature = "std")]
+pub use format::FormatError;
+#[cfg(feature = "std")]
+pub use fs::{
+    EntryKind, FileId, FsBackend, LimitExceeded, LinkCycle, MemoryFs, Metadata, ReadDir, StdFs,
+    TraversalLimits, Walk,
+};
+#[cfg(feature = "std")]
+pub use glob::{Glob, GlobError, GlobSet, GlobWalk};
+#[cfg(feature = "std")]
+pub use hash::{Algorithm, Digest, SnapshotEntry, SnapshotOptions, TreeSnapshot};
+#[cfg(feature = "std")]
+pub use index::{DirIndex, IndexEntry};
+#[cfg(feature = "std")]
+pub use iter::{dedup_ancestors, DedupAncestors, FilterExt, PathIterExt, RelativeTo, Under};
+#[cfg(feature = "std")]
+pub use link::{link_tree, relink_tree, unlink_tree, LinkError, LinkOptions};
+#[cfg(feature = "std")]
+pub use lock::{LockError, LockInfo, LockNaming, Lockfile};
+#[cfg(feature = "std")]
+pub use normalize::Normalization;
+#[cfg(feature = "std")]
+pub use parallel::{ParallelWalk, WalkOrder};
+#[cfg(feature = "std")]
+pub use pathspec::{Pathspec, PathspecError, PathspecSet};
+#[cfg(feature = "std")]
+pub use policy::{Decision, Effect, Policy, Rule, RuleMatcher};
+#[cfg(feature = "std")]
+pub use preserve::PreservedPath;
+#[cfg(feature = "std")]
+pub use process::CurrentDirGuard;
+pub use pure::PurePath;
+#[cfg(feature = "std")]
+pub use rel_id::{RelId, RelIdError};
+#[cfg(feature = "std")]
+pub use rename::{RenameError, RenameOp, RenamePlan};
+#[cfg(feature = "std")]
+pub use router::PrefixRouter;
+#[cfg(feature = "std")]
+pub use secure::PathEscape;
+#[cfg(feature = "std")]
+pub use sequence::{detect_sequence, Sequence};
+#[cfg(feature = "std")]
+pub use serde_forms::{AsComponents, Lenient};
+#[cfg(feature = "std")]
+pub use set::{diff_sets, diff_sets_with, DiffOptions, OsPathSet, SetDiff};
+#[cfg(feature = "std")]
+pub use short_name::ShortName;
+#[cfg(feature = "std")]
+pub use subtree::SubtreeSelector;
+#[cfg(feature = "synthetic")]
+pub use synthetic::SyntheticPaths;
+#[cfg(feature = "std")]
+pub use transaction::{FsTransaction, JournalEntry};
+#[cfg(feature = "std")]
+pub use tree::{render_tree, render_tree_in, TreeOptions};
+#[cfg(feature = "std")]
+pub use validate::OsPathError;
+#[cfg(feature = "std")]
+pub use vfs::{MountTable, Overlay};
+#[cfg(feature = "watch")]
+pub use watch::DebouncedWatcher;
+#[cfg(feature = "std")]
+pub use watch::{Debouncer, WatchEvent};
+#[cfg(feature = "std")]
+pub use web::{resolve_request_path, resolve_request_path_with_index, ResolveError, WebRules};
+#[cfg(feature = "std")]
+pub use write::BackupPolicy;
+
+// Unix, and targets with no notation of their own such as WebAssembly, use forward slashes and a single root.
+#[cfg(not(windows))]
 mod localization {
     pub const ROOT: &str = "/";
     pub const SLASH: char = '/';
@@ -137,37 +391,98 @@ mod localization {
 
 #[cfg(windows)]
 mod localization {
-    // pub const ROOT: &str = "C:\\";
+    // Absolute paths start with a drive component such as `D:` rather than a fixed root.
     pub const SLASH: char = '\\';
     pub const SLASH_STR: &str = "\\";
 }
 
-#[cfg(unix)]
+#[cfg(not(windows))]
 use localization::{ROOT, SLASH, SLASH_STR};
 
 #[cfg(windows)]
 use localization::{SLASH, SLASH_STR};
 
-const RC: char = char::REPLACEMENT_CHARACTER; // '�'
 const BS: char = '\\';
 const FS: char = '/';
 const UP: &str = "..";
 
+#[cfg(feature = "std")]
 /// An intelligent path type that can be used in place of `std::path::PathBuf`.
-#[derive(Clone, PartialEq, Debug, Default)]
+///
+/// Paths are equal when they have the same components, are both absolute or both relative, and both name a file or
+/// both a directory. Comparison is case-sensitive on every platform, even though Windows and macOS filesystems
+/// usually are not; only the letter of a Windows drive is normalized, so `c:\` and `C:\` are the same path. Use
+/// `windows_eq()` to compare by the rules of NTFS.
+///
+/// Paths are ordered component by component, comparing the text of each component by Unicode code point, so a path
+/// sorts directly before the paths below it. Relative paths come before absolute ones, and a file before a directory
+/// with the same components.
+/// ```rust
+/// use os_path::OsPath;
+/// use std::collections::BTreeSet;
+///
+/// let paths: BTreeSet<OsPath> = ["b.txt", "a/", "a/z.txt", "a-b/", "A/"].into_iter().map(OsPath::from).collect();
+/// let sorted: Vec<String> = paths.iter().map(|p| p.to_string().replace('\\', "/")).collect();
+/// assert_eq!(sorted, ["A/", "a/", "a/z.txt", "a-b/", "b.txt"]);
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
 pub struct OsPath {
     components: Vec<String>,
     absolute: bool,
     directory: bool,
     path: PathBuf,
+    style: Style,
 }
 
+#[cfg(feature = "std")]
 /// Public Methods
 impl OsPath {
     pub fn new() -> Self {
         Self::default()
     }
 
+    /// Parses a string into a path, rejecting input that cannot name a file on any platform. Never panics.
+    ///
+    /// Every other input has a defined result: a lone `..` is a relative directory holding one `..` component, a
+    /// string of only separators is the root on Unix, and repeated separators collapse. There is no limit on the
+    /// number or length of components.
+    /// ```rust
+    /// use os_path::{OsPath, ParseError};
+    ///
+    /// assert_eq!(OsPath::try_parse("").unwrap_err(), ParseError::Empty);
+    /// assert_eq!(OsPath::try_parse("a\0b").unwrap_err(), ParseError::NulByte(1));
+    ///
+    /// let up = OsPath::try_parse("..").unwrap();
+    /// assert!(up.is_dir() && !up.is_absolute());
+    ///
+    /// let deep = "x/".repeat(10_000);
+    /// assert!(OsPath::try_parse(&deep).is_ok());
+    /// ```
+    pub fn try_parse(input: &str) -> Result<Self, ParseError> {
+        if input.is_empty() {
+            return Err(ParseError::Empty);
+        }
+        if let Some(i) = input.find('\0') {
+            return Err(ParseError::NulByte(i));
+        }
+        Ok(Self::build_self(input))
+    }
+
+    /// Parses raw bytes into a path as `try_parse()` does, rejecting bytes that are not UTF-8. Suitable as a fuzzing
+    /// entry point.
+    /// ```rust
+    /// use os_path::{OsPath, ParseError};
+    ///
+    /// assert_eq!(OsPath::try_parse_bytes(b"ok/\xff").unwrap_err(), ParseError::InvalidUtf8(3));
+    /// assert!(OsPath::try_parse_bytes(b"ok/fine").is_ok());
+    /// ```
+    pub fn try_parse_bytes(bytes: &[u8]) -> Result<Self, ParseError> {
+        match std::str::from_utf8(bytes) {
+            Ok(input) => Self::try_parse(input),
+            Err(e) => Err(ParseError::InvalidUtf8(e.valid_up_to())),
+        }
+    }
+
     /// Creates a new OsPath from the existing one, and joins the path to it.
     /// ```rust
     /// #[cfg(unix)]
@@ -182,8 +497,8 @@ impl OsPath {
     pub fn join<P: AsRef<Path>>(&self, path: P) -> Self {
         let mut new_self = self.clone();
         let path = Self::build_self(path);
-        Self::merge_paths(&mut new_self, path);
-        new_self.path = Self::build_pathbuf(&new_self.components, new_self.absolute);
+        let appended = Self::merge_paths(&mut new_self, path);
+        new_self.update_pathbuf(appended);
         new_self
     }
 
@@ -200,12 +515,74 @@ impl OsPath {
     /// ```
     pub fn push<P: AsRef<Path>>(&mut self, path: P) {
         let path = Self::build_self(path);
-        Self::merge_paths(self, path);
-        self.path = Self::build_pathbuf(&self.components, self.absolute);
+        let appended = Self::merge_paths(self, path);
+        self.update_pathbuf(appended);
+    }
+
+    /// Joins another OsPath as `join()` does, but merges its components directly instead of turning it back into a
+    /// string and parsing it again. Unlike `join()` through `Path`, this keeps the joined path's directory marker.
+    /// ```rust
+    /// #[cfg(unix)]
+    /// {
+    /// use os_path::OsPath;
+    ///
+    /// let base = OsPath::from("/srv/app/");
+    /// let rel = OsPath::from("static/css/site.css");
+    /// assert_eq!(base.join_os(&rel), base.join(&rel));
+    /// assert_eq!(base.join_os(&rel).to_string(), "/srv/app/static/css/site.css");
+    /// }
+    /// ```
+    pub fn join_os(&self, path: &OsPath) -> Self {
+        let mut new_self = self.clone();
+        new_self.push_os(path);
+        new_self
+    }
+
+    /// Appends another OsPath as `push()` does, without parsing it again. See `join_os()`.
+    pub fn push_os(&mut self, path: &OsPath) {
+        let appended = Self::merge_paths(self, path.clone());
+        self.update_pathbuf(appended);
+    }
+
+    /// Returns the path with the text appended to its last component, without a separator, as for adding a download
+    /// or temporary suffix. A directory stays a directory. If the text holds a separator, the part after it is joined
+    /// as by `join()`.
+    /// ```rust
+    /// #[cfg(unix)]
+    /// {
+    /// use os_path::OsPath;
+    ///
+    /// let file = OsPath::from("/downloads/file");
+    /// assert_eq!(file.concat(".partial").to_string(), "/downloads/file.partial");
+    /// assert_eq!(OsPath::from("assets/img").concat("@2x.png").to_string(), "assets/img@2x.png");
+    /// assert_eq!(OsPath::from("build/").concat(".old").to_string(), "build.old/");
+    /// }
+    /// ```
+    pub fn concat(&self, suffix: &str) -> Self {
+        let (head, rest) = match suffix.find([BS, FS]) {
+            Some(i) => (&suffix[..i], Some(&suffix[i..])),
+            None => (suffix, None),
+        };
+        let head = raw::escape_nul(head);
+        let mut new_self = self.clone();
+        match new_self.components.last_mut() {
+            Some(last) => last.push_str(&head),
+            None if !head.is_empty() => new_self.components.push(head.into_owned()),
+            None => {}
+        }
+        new_self.path = Self::build_pathbuf(&new_self.components, new_self.absolute);
+        if let Some(rest) = rest {
+            new_self.directory = true;
+            new_self.push(rest);
+        }
+        new_self
     }
 
     /// Traverses the components of the path and and resolves any `..` components.
     /// This cannot be done automatically because ".." may be desireable in some cases.
+    ///
+    /// A relative path keeps any `..` that climbs above its start, so `a/../../b` resolves to `../b`. An absolute
+    /// path cannot climb above its root, so `/a/../../b` resolves to `/b`.
     /// ```rust
     /// #[cfg(unix)]
     /// {
@@ -219,15 +596,7 @@ impl OsPath {
     /// }
     /// ```
     pub fn resolve(&mut self) {
-        let mut new_vec: Vec<String> = Vec::new();
-        for c in &self.components {
-            if c != UP {
-                new_vec.push(c.clone());
-            } else {
-                new_vec.pop();
-            }
-        }
-        self.components = new_vec;
+        self.components = PurePath::resolved(&self.components, self.absolute);
         self.path = Self::build_pathbuf(&self.components, self.absolute);
     }
 
@@ -248,6 +617,162 @@ impl OsPath {
         self.absolute
     }
 
+    /// Returns the drive letter of an absolute Windows path, in upper case. Unix paths have no drive.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// #[cfg(windows)]
+    /// {
+    /// assert_eq!(OsPath::from("d:\\data\\file.txt").drive(), Some('D'));
+    /// assert_eq!(OsPath::from("data\\file.txt").drive(), None);
+    /// }
+    /// #[cfg(unix)]
+    /// assert_eq!(OsPath::from("/data/file.txt").drive(), None);
+    /// ```
+    pub fn drive(&self) -> Option<char> {
+        let first = self.components.first().filter(|_| self.absolute)?;
+        let drive = first.strip_prefix(r"\\?\").unwrap_or(first);
+        if PurePath::is_drive(drive) {
+            Some(drive.as_bytes()[0].to_ascii_uppercase() as char)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the path on the given drive, replacing its current drive, or making a relative path absolute on that
+    /// drive. Everything after the drive is kept as it is. Unix paths and UNC paths have no drive and are returned
+    /// unchanged, and so is any path if the letter is not an ASCII letter.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// #[cfg(windows)]
+    /// {
+    /// let moved = OsPath::from("C:\\data\\file.txt").with_drive('d');
+    /// assert_eq!(moved.to_string(), "D:\\data\\file.txt");
+    /// assert_eq!(OsPath::from("data\\").with_drive('E').to_string(), "E:\\data\\");
+    /// }
+    /// #[cfg(unix)]
+    /// assert_eq!(OsPath::from("/data/file.txt").with_drive('D').to_string(), "/data/file.txt");
+    /// ```
+    pub fn with_drive(&self, letter: char) -> Self {
+        let mut new_self = self.clone();
+        if !cfg!(windows) || !letter.is_ascii_alphabetic() || self.unc_prefix().is_some() {
+            return new_self;
+        }
+        let drive = format!("{}:", letter.to_ascii_uppercase());
+        if let Some(first) = self.components.first().filter(|_| self.drive().is_some()) {
+            let verbatim = if first.starts_with(r"\\?\") {
+                r"\\?\"
+            } else {
+                ""
+            };
+            new_self.components[0] = format!("{}{}", verbatim, drive);
+        } else {
+            new_self.components.insert(0, drive);
+            new_self.absolute = true;
+        }
+        new_self.path = Self::build_pathbuf(&new_self.components, new_self.absolute);
+        new_self
+    }
+
+    /// Returns the path moved to the drive its current drive maps to, for remapping stored paths after a disk
+    /// migration. Letters are matched without regard to case. Paths without a drive, and paths whose drive is not in
+    /// the map, are returned unchanged.
+    /// ```rust
+    /// use os_path::OsPath;
+    /// use std::collections::HashMap;
+    ///
+    /// let map = HashMap::from([('C', 'D'), ('e', 'f')]);
+    ///
+    /// #[cfg(windows)]
+    /// {
+    /// assert_eq!(OsPath::from("c:\\data\\file.txt").map_drive(&map).to_string(), "D:\\data\\file.txt");
+    /// assert_eq!(OsPath::from("E:\\logs\\").map_drive(&map).to_string(), "F:\\logs\\");
+    /// assert_eq!(OsPath::from("G:\\music\\").map_drive(&map).to_string(), "G:\\music\\");
+    /// assert_eq!(OsPath::from("data\\file.txt").map_drive(&map).to_string(), "data\\file.txt");
+    /// }
+    /// #[cfg(unix)]
+    /// assert_eq!(OsPath::from("/data/file.txt").map_drive(&map).to_string(), "/data/file.txt");
+    /// ```
+    pub fn map_drive(&self, map: &HashMap<char, char>) -> Self {
+        let Some(drive) = self.drive() else {
+            return self.clone();
+        };
+        let mapped = map
+            .iter()
+            .find(|(from, _)| from.eq_ignore_ascii_case(&drive))
+            .map(|(_, to)| *to);
+        match mapped {
+            Some(letter) => self.with_drive(letter),
+            None => self.clone(),
+        }
+    }
+
+    /// Returns true if the path starts with a Windows UNC prefix naming a network share, as in
+    /// `\\server\share\file.txt` or the verbatim `\\?\UNC\server\share\file.txt`. Unix paths are never UNC paths.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// #[cfg(windows)]
+    /// {
+    /// let path = OsPath::from(r"\\fileserver\share\reports\q1.xlsx");
+    /// assert!(path.is_unc());
+    /// assert_eq!(path.server(), Some("fileserver"));
+    /// assert_eq!(path.share(), Some("share"));
+    /// assert_eq!(path.parent().unwrap().to_string(), r"\\fileserver\share\reports\");
+    /// assert_eq!(path.join(r"..\..\..\q2.xlsx").to_string(), r"\\fileserver\share\q2.xlsx");
+    ///
+    /// let verbatim = OsPath::from(r"\\?\UNC\fileserver\share\q1.xlsx");
+    /// assert!(verbatim.is_unc());
+    /// assert_eq!(verbatim.server(), Some("fileserver"));
+    /// assert!(!OsPath::from(r"\\?\C:\data").is_unc());
+    /// }
+    /// #[cfg(unix)]
+    /// assert!(!OsPath::from("//fileserver/share/q1.xlsx").is_unc());
+    /// ```
+    pub fn is_unc(&self) -> bool {
+        self.unc_prefix().is_some()
+    }
+
+    /// Returns the server named by a UNC path, such as `fileserver` in `\\fileserver\share\q1.xlsx`.
+    pub fn server(&self) -> Option<&str> {
+        self.unc_prefix().map(|(server, _)| server)
+    }
+
+    /// Returns the share named by a UNC path, such as `share` in `\\fileserver\share\q1.xlsx`.
+    pub fn share(&self) -> Option<&str> {
+        self.unc_prefix().map(|(_, share)| share)
+    }
+
+    /// Returns the number of `..` components at the start of the path, which is how far a path that has been
+    /// resolved climbs above wherever it is joined.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// assert_eq!(OsPath::from("../../lib/util.rs").leading_parent_count(), 2);
+    /// assert_eq!(OsPath::from("src/../lib.rs").leading_parent_count(), 0);
+    /// ```
+    pub fn leading_parent_count(&self) -> usize {
+        self.components.iter().take_while(|c| *c == UP).count()
+    }
+
+    /// Returns how many levels above its starting point the path reaches at any moment while walking its components
+    /// in order, rather than where it ends up. A path is confined to a directory exactly when this is zero, which
+    /// makes it the check for archive entries and include paths. Absolute paths are measured from their root.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// // Ends up inside its starting point, but passes through its parent on the way.
+    /// let path = OsPath::from("../sandbox/file.txt");
+    /// assert_eq!(path.max_escape_depth(), 1);
+    ///
+    /// assert_eq!(OsPath::from("a/b/../../../../c/d/e").max_escape_depth(), 2);
+    /// assert_eq!(OsPath::from("a/../b").max_escape_depth(), 0);
+    /// ```
+    pub fn max_escape_depth(&self) -> usize {
+        PurePath::escape_depth(&self.components)
+    }
+
     /// Returns true if the path exists.
     /// ```rust
     /// use os_path::OsPath;
@@ -259,6 +784,40 @@ impl OsPath {
         self.path.exists()
     }
 
+    /// Returns the absolute path with every symlink and `..` resolved by the filesystem, as
+    /// `std::fs::canonicalize()` does. The result is a directory if it names one. On Windows a verbatim `\\?\C:`
+    /// prefix is given back as the plain drive.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// let src = OsPath::from("src/../src").canonicalize().unwrap();
+    /// assert!(src.is_absolute() && src.is_dir());
+    /// assert_eq!(src.name().unwrap(), "src");
+    /// assert!(OsPath::from("no/such/file").canonicalize().is_err());
+    /// ```
+    pub fn canonicalize(&self) -> std::io::Result<Self> {
+        let real = std::fs::canonicalize(&self.path).with_path_context("canonicalize", self)?;
+        Ok(Self::from_canonical(&real, real.is_dir()))
+    }
+
+    /// Builds the OsPath for a path returned by `std::fs::canonicalize()`, dropping the verbatim prefix Windows adds
+    /// to drive paths.
+    pub(crate) fn from_canonical(real: &Path, is_dir: bool) -> Self {
+        let mut new_self = Self::build_self(real);
+        #[cfg(windows)]
+        if let Some(first) = new_self.components.first_mut() {
+            if let Some(drive) = first
+                .strip_prefix(r"\\?\")
+                .filter(|d| PurePath::is_drive(d))
+            {
+                *first = drive.to_string();
+                new_self.path = Self::build_pathbuf(&new_self.components, new_self.absolute);
+            }
+        }
+        new_self.directory = is_dir;
+        new_self
+    }
+
     /// Returns true if the last item is a file.
     /// ```rust
     /// use os_path::OsPath;
@@ -307,11 +866,52 @@ impl OsPath {
     /// ```
     pub fn extension(&self) -> Option<String> {
         if self.is_file() {
-            return Some(self.name()?.split('.').last()?.to_string());
+            return Some(self.name()?.split('.').next_back()?.to_string());
         }
         None
     }
 
+    /// Returns the name of the file without its final extension. Dot-files such as `.bashrc` and names without a dot
+    /// are returned whole.
+    /// ```rust
+    /// #[cfg(unix)]
+    /// {
+    /// use os_path::OsPath;
+    ///
+    /// assert_eq!(OsPath::from("/foo/bar/archive.tar.gz").file_stem().unwrap(), "archive.tar");
+    /// assert_eq!(OsPath::from("/home/user/.bashrc").file_stem().unwrap(), ".bashrc");
+    /// assert_eq!(OsPath::from("/").file_stem(), None);
+    /// }
+    /// ```
+    pub fn file_stem(&self) -> Option<String> {
+        match self.split_extension() {
+            Some((stem, _)) => Some(stem.to_string()),
+            None => self.name().cloned(),
+        }
+    }
+
+    /// Returns every dot-separated segment of the name, in order. A leading dot stays with the first segment.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// assert_eq!(OsPath::from("archive.tar.gz").stems(), vec!["archive", "tar", "gz"]);
+    /// assert_eq!(OsPath::from(".config.json").stems(), vec![".config", "json"]);
+    /// assert!(OsPath::new().stems().is_empty());
+    /// ```
+    pub fn stems(&self) -> Vec<String> {
+        let name = match self.name() {
+            Some(name) => name,
+            None => return Vec::new(),
+        };
+        let (prefix, rest) = match name.strip_prefix('.') {
+            Some(rest) => (".", rest),
+            None => ("", name.as_str()),
+        };
+        let mut stems: Vec<String> = rest.split('.').map(String::from).collect();
+        stems[0].insert_str(0, prefix);
+        stems
+    }
+
     /// Returns the path of the parent directory, if it has one.
     /// ```rust
     /// #[cfg(unix)]
@@ -326,6 +926,12 @@ impl OsPath {
         if self.components.len() < 2 && !self.absolute {
             return None;
         }
+        // The root itself, or a bare drive or share on Windows, has no parent.
+        if self.components.is_empty()
+            || (self.components.len() == 1 && PurePath::is_prefix(&self.components[0]))
+        {
+            return None;
+        }
         let i = self.components.len() - 1;
         let mut new_self = self.clone();
         new_self.components.truncate(i);
@@ -334,6 +940,21 @@ impl OsPath {
         Some(new_self)
     }
 
+    /// Returns true if the last item is hidden by the Unix dot-file convention.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// assert!(OsPath::from("/home/user/.bashrc").is_hidden());
+    /// assert!(!OsPath::from("/home/user/notes.txt").is_hidden());
+    /// assert!(!OsPath::from("../").is_hidden());
+    /// ```
+    pub fn is_hidden(&self) -> bool {
+        match self.name() {
+            Some(name) => name.starts_with('.') && name != "." && name != UP,
+            None => false,
+        }
+    }
+
     /// Returns the root element of the path, if it has one.
     /// ```rust
     /// {
@@ -364,6 +985,59 @@ impl OsPath {
         self.directory = true;
     }
 
+    /// Makes the path relative by dropping its root, and on Windows its drive or share, keeping every other component.
+    /// A relative path is left as it is.
+    /// ```rust
+    /// #[cfg(unix)]
+    /// {
+    /// use os_path::OsPath;
+    ///
+    /// let mut path = OsPath::from("/etc/nginx/nginx.conf");
+    /// path.make_relative();
+    /// assert_eq!(path.to_string(), "etc/nginx/nginx.conf");
+    /// }
+    /// ```
+    pub fn make_relative(&mut self) {
+        if !self.absolute {
+            return;
+        }
+        if self
+            .components
+            .first()
+            .is_some_and(|c| PurePath::is_prefix(c))
+        {
+            self.components.remove(0);
+        }
+        self.absolute = false;
+        self.path = Self::build_pathbuf(&self.components, false);
+    }
+
+    /// Makes a relative path absolute by placing it under `base`, which is always treated as a directory. A `..` at
+    /// the start of the path climbs out of `base`. An absolute path is left as it is.
+    /// ```rust
+    /// #[cfg(unix)]
+    /// {
+    /// use os_path::OsPath;
+    ///
+    /// let mut path = OsPath::from("../shared/config.toml");
+    /// path.make_absolute("/srv/app");
+    /// assert_eq!(path.to_string(), "/srv/shared/config.toml");
+    ///
+    /// let mut path = OsPath::from("/etc/hosts");
+    /// path.make_absolute("/srv/app");
+    /// assert_eq!(path.to_string(), "/etc/hosts");
+    /// }
+    /// ```
+    pub fn make_absolute<P: AsRef<Path>>(&mut self, base: P) {
+        if self.absolute {
+            return;
+        }
+        let mut based = Self::build_self(base);
+        based.force_dir();
+        based.push_os(self);
+        *self = based;
+    }
+
     /// Returns the path as a PathBuf.
     /// ```rust
     /// #[cfg(unix)]
@@ -391,71 +1065,340 @@ impl OsPath {
     pub fn to_path(&self) -> &Path {
         self.path.as_path()
     }
+
+    /// Returns the path as the platform string passed to the operating system. Names that are not valid Unicode are
+    /// kept exactly as they were read.
+    /// ```rust
+    /// #[cfg(unix)]
+    /// {
+    /// use os_path::OsPath;
+    /// use std::ffi::OsStr;
+    /// use std::os::unix::ffi::OsStrExt;
+    ///
+    /// let raw = OsStr::from_bytes(b"/data/caf\xe9.txt");
+    /// let path = OsPath::from(std::path::Path::new(raw));
+    /// assert_eq!(path.as_os_str(), raw);
+    /// assert_eq!(path.parent().unwrap().join("menu.txt").to_string(), "/data/menu.txt");
+    /// assert_eq!(path.to_string(), "/data/caf\u{FFFD}.txt");
+    /// }
+    /// ```
+    pub fn as_os_str(&self) -> &OsStr {
+        self.path.as_os_str()
+    }
+
+    /// Returns the path as an owned platform string. See `as_os_str()`.
+    pub fn to_os_string(&self) -> OsString {
+        self.path.clone().into_os_string()
+    }
+
+    /// Returns the path as a PathBuf that keeps the trailing separator of a directory, for tools such as rsync and
+    /// robocopy that treat `src/` and `src` differently. `to_pathbuf()` drops it, as `PathBuf` does.
+    /// ```rust
+    /// #[cfg(unix)]
+    /// {
+    /// use os_path::OsPath;
+    ///
+    /// let dir = OsPath::from("/foo/bar/");
+    /// assert_eq!(dir.to_pathbuf().as_os_str(), "/foo/bar");
+    /// assert_eq!(dir.to_pathbuf_dir_marked().as_os_str(), "/foo/bar/");
+    /// assert_eq!(OsPath::from("/foo/bar").to_pathbuf_dir_marked().as_os_str(), "/foo/bar");
+    /// }
+    /// ```
+    pub fn to_pathbuf_dir_marked(&self) -> PathBuf {
+        let mut path = self.path.clone();
+        if self.directory && !self.components.is_empty() {
+            path.push("");
+        }
+        path
+    }
+
+    /// Returns the path as an owned platform string that keeps the trailing separator of a directory. See
+    /// `to_pathbuf_dir_marked()`.
+    pub fn to_os_string_dir_marked(&self) -> OsString {
+        self.to_pathbuf_dir_marked().into_os_string()
+    }
+
+    /// Re-bases the path from `from_root` onto `to_root`, keeping everything below `from_root` exactly as it is.
+    /// Fails if the path is not below `from_root`, including when a `..` in the remainder would climb out of it.
+    /// ```rust
+    /// #[cfg(unix)]
+    /// {
+    /// use os_path::OsPath;
+    ///
+    /// let host = OsPath::from("/var/lib/containers/app/etc/hosts");
+    /// let guest = host.translate("/var/lib/containers/app/", "/").unwrap();
+    /// assert_eq!(guest.to_string(), "/etc/hosts");
+    ///
+    /// assert!(OsPath::from("/etc/passwd").translate("/var/lib/", "/").is_err());
+    /// }
+    /// ```
+    pub fn translate<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        from_root: P,
+        to_root: Q,
+    ) -> Result<Self, PrefixError> {
+        let tail = self.strip_prefix(&Self::build_self(from_root))?.components;
+        let mut new_self = Self::build_self(to_root);
+        new_self.components.extend_from_slice(&tail);
+        new_self.directory = if tail.is_empty() {
+            new_self.directory || self.directory
+        } else {
+            self.directory
+        };
+        new_self.path = Self::build_pathbuf(&new_self.components, new_self.absolute);
+        Ok(new_self)
+    }
+
+    /// Returns the part of the path below `base`, as a relative path. Fails unless `base` is a leading part of the
+    /// path, compared component by component, including when a `..` in the remainder would climb back out of it.
+    /// ```rust
+    /// #[cfg(unix)]
+    /// {
+    /// use os_path::OsPath;
+    ///
+    /// let path = OsPath::from("/srv/www/img/logo.png");
+    /// assert_eq!(path.strip_prefix(&OsPath::from("/srv/www/")).unwrap().to_string(), "img/logo.png");
+    /// assert!(path.strip_prefix(&OsPath::from("/srv/w")).is_err());
+    /// assert!(path.strip_prefix(&OsPath::from("srv/www/")).is_err());
+    /// }
+    /// ```
+    pub fn strip_prefix(&self, base: &OsPath) -> Result<Self, PrefixError> {
+        if !self.is_within(base) {
+            return Err(PrefixError);
+        }
+        Ok(self.tail_from(base.components.len()))
+    }
+
+    /// Returns true if `base` is a leading part of the path, compared component by component, and no `..` in the
+    /// remainder climbs back out of it.
+    fn is_within(&self, base: &OsPath) -> bool {
+        self.absolute == base.absolute
+            && self.components.starts_with(&base.components)
+            && !PurePath::climbs_out(&self.components[base.components.len()..])
+    }
+
+    /// Returns the relative path that leads from the directory `base` to this path, climbing out of `base` with `..`
+    /// as far as needed, as for the target of a relative link or the name of an archive entry. Both paths are resolved
+    /// first. A path relative to itself is `./`.
+    ///
+    /// Returns `None` when there is no such path: when one path is absolute and the other is not, when they are on
+    /// different Windows drives or shares, or when `base` climbs with `..` into directories whose names are unknown.
+    /// ```rust
+    /// #[cfg(unix)]
+    /// {
+    /// use os_path::OsPath;
+    ///
+    /// let page = OsPath::from("/srv/www/blog/2024/post.html");
+    /// let css = OsPath::from("/srv/www/static/site.css");
+    /// assert_eq!(css.relative_to(&page.parent().unwrap()).unwrap().to_string(), "../../static/site.css");
+    /// assert_eq!(page.relative_to(&OsPath::from("/srv/www")).unwrap().to_string(), "blog/2024/post.html");
+    /// assert_eq!(page.relative_to(&OsPath::from("www/")), None);
+    /// }
+    /// ```
+    pub fn relative_to(&self, base: &OsPath) -> Option<Self> {
+        if self.absolute != base.absolute {
+            return None;
+        }
+        let (mut target, mut base) = (self.clone(), base.clone());
+        target.resolve();
+        base.resolve();
+        let common = target
+            .components
+            .iter()
+            .zip(&base.components)
+            .take_while(|(a, b)| a == b)
+            .count();
+        let rest = &base.components[common..];
+        if rest.iter().any(|c| c == UP) {
+            return None;
+        }
+        if self.absolute
+            && common == 0
+            && base
+                .components
+                .first()
+                .is_some_and(|c| PurePath::is_prefix(c))
+        {
+            return None;
+        }
+        let mut components: Vec<String> = rest.iter().map(|_| UP.to_string()).collect();
+        components.extend_from_slice(&target.components[common..]);
+        let directory = if components.is_empty() {
+            components.push(".".to_string());
+            true
+        } else {
+            target.directory
+        };
+        let path = Self::build_pathbuf(&components, false);
+        Some(Self {
+            components,
+            absolute: false,
+            directory,
+            path,
+            style: Style::default(),
+        })
+    }
 }
 
+#[cfg(feature = "std")]
+/// The error returned when a path is not below the root it was expected to be under.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PrefixError;
+
+#[cfg(feature = "std")]
+impl fmt::Display for PrefixError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("path is not below the given root")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PrefixError {}
+
+#[cfg(feature = "std")]
+/// The error returned by `OsPath::try_parse()` for input that cannot be a path.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ParseError {
+    /// The input was empty.
+    Empty,
+    /// The input contained a NUL byte at this offset.
+    NulByte(usize),
+    /// The input was not valid UTF-8 from this offset.
+    InvalidUtf8(usize),
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Empty => f.write_str("path is empty"),
+            ParseError::NulByte(i) => write!(f, "path contains a NUL byte at offset {}", i),
+            ParseError::InvalidUtf8(i) => write!(f, "path is not valid UTF-8 at offset {}", i),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
+#[cfg(feature = "std")]
 /// Private Methods
 impl OsPath {
     fn build_self<P: AsRef<Path>>(path: P) -> Self {
-        let path = path.as_ref().to_string_lossy().to_string();
+        Self::build_from_str(&raw::decode(path.as_ref().as_os_str()))
+    }
 
-        #[cfg(unix)]
-        let absolute = path.starts_with(ROOT) || path.starts_with(BS) || path.starts_with(FS);
+    fn build_from_str(path: &str) -> Self {
+        Self::from_pure(PurePath::parse(path))
+    }
 
-        #[cfg(windows)]
-        let absolute = match Regex::new(r"^[a-zA-Z]:") {
-            Ok(re) => re.is_match(&path),
-            Err(_) => false,
+    /// Builds the path from its components, with a `PathBuf` to match.
+    fn from_pure(pure: PurePath) -> Self {
+        let path = Self::build_pathbuf(&pure.components, pure.absolute);
+        Self {
+            components: pure.components,
+            absolute: pure.absolute,
+            directory: pure.directory,
+            path,
+            style: Style::default(),
+        }
+    }
+
+    /// Returns the components of the path without its `PathBuf`.
+    fn to_pure(&self) -> PurePath {
+        PurePath {
+            components: self.components.clone(),
+            absolute: self.absolute,
+            directory: self.directory,
+        }
+    }
+
+    /// Returns the server and share of a UNC prefix.
+    fn unc_prefix(&self) -> Option<(&str, &str)> {
+        let first = self.components.first().filter(|_| self.absolute)?;
+        let text = match first.strip_prefix(r"\\?\") {
+            Some(verbatim) => verbatim.strip_prefix(r"UNC\")?,
+            None => first.strip_prefix(r"\\")?,
         };
+        let (server, share, _) = PurePath::split_server_share(text)?;
+        Some((server, share))
+    }
 
-        let directory = path.ends_with(SLASH) || path.ends_with(UP);
-        let clean: String = path
-            .chars()
-            .map(|c| if c == BS || c == FS { RC } else { c })
-            .collect();
-        let components: Vec<String> = clean
-            .split(RC)
-            .filter_map(|s| {
-                if s.is_empty() {
-                    None
-                } else {
-                    Some(s.to_string())
-                }
-            })
-            .collect();
-        let path = Self::build_pathbuf(&components, absolute);
+    /// Splits the name of a file into its stem and final extension. Dot-files such as `.bashrc` and names without a
+    /// dot have no extension.
+    fn split_extension(&self) -> Option<(&str, &str)> {
+        if self.directory {
+            return None;
+        }
+        let (stem, ext) = self.name()?.rsplit_once('.')?;
+        if stem.is_empty() {
+            return None;
+        }
+        Some((stem, ext))
+    }
+
+    /// Returns the path with its last component replaced, as a file.
+    fn with_name<S: Into<String>>(&self, name: S) -> Self {
+        let mut sibling = self.clone();
+        match sibling.components.last_mut() {
+            Some(last) => *last = name.into(),
+            None => sibling.components.push(name.into()),
+        }
+        sibling.directory = false;
+        sibling.path = OsPath::build_pathbuf(&sibling.components, sibling.absolute);
+        sibling
+    }
+
+    /// Returns the relative path made of the components after the first `n`.
+    fn tail_from(&self, n: usize) -> Self {
+        let components = self.components[n.min(self.components.len())..].to_vec();
+        let directory = self.directory && !components.is_empty();
+        let path = Self::build_pathbuf(&components, false);
         Self {
             components,
-            absolute,
+            absolute: false,
             directory,
             path,
+            style: Style::default(),
         }
     }
 
     fn build_string(&self) -> String {
-        match (self.absolute, self.directory) {
-            #[cfg(unix)]
-            (true, true) => ROOT.to_string() + &self.components.join(SLASH_STR) + SLASH_STR,
-            #[cfg(unix)]
-            (true, false) => ROOT.to_string() + &self.components.join(SLASH_STR),
-
-            #[cfg(windows)]
-            (true, true) => self.components.join(SLASH_STR) + SLASH_STR,
-            #[cfg(windows)]
-            (true, false) => self.components.join(SLASH_STR),
+        PurePath::render(&self.components, self.absolute, self.directory)
+    }
 
-            (false, false) => self.components.join(SLASH_STR),
-            (false, true) => self.components.join(SLASH_STR) + SLASH_STR,
+    /// Renders the path with `/` separators on every platform. Unix roots keep their leading `/`, Windows roots
+    /// start with their drive, or with their UNC prefix written with `/` as `to_string_as(Flavor::Posix)` writes it.
+    /// Anything that is not valid Unicode stays escaped, so distinct paths never render the same, which makes this
+    /// the form to key and hash paths by.
+    fn build_portable_key(&self) -> String {
+        let mut s = String::new();
+        #[cfg(not(windows))]
+        if self.absolute {
+            s.push(FS);
         }
+        let mut names = self.components.clone();
+        if let Some(first) = names
+            .first_mut()
+            .filter(|c| self.absolute && PurePath::is_unc_prefix(c))
+        {
+            *first = first.replace(BS, "/");
+        }
+        s.push_str(&names.join("/"));
+        if self.directory && !self.components.is_empty() {
+            s.push(FS);
+        }
+        s
     }
 
     fn build_pathbuf(components: &Vec<String>, absolute: bool) -> PathBuf {
         let mut path = PathBuf::new();
         if absolute {
-            #[cfg(unix)]
+            #[cfg(not(windows))]
             path.push(ROOT);
             #[cfg(windows)]
             if components.len() == 1 {
-                path.push(format!("{}{}", &components[0], SLASH_STR));
+                path.push(raw::encode(&format!("{}{}", &components[0], SLASH_STR)));
                 return path; // !!! EARLY RETURN !!!
             }
         }
@@ -467,63 +1410,104 @@ impl OsPath {
                     path.push(format!("{}{}", &c, SLASH_STR));
                     continue;
                 }
-                path.push(c);
+                path.push(raw::encode(c));
             }
         } else {
             for c in components {
-                path.push(c);
+                path.push(raw::encode(c));
             }
         }
 
-        #[cfg(unix)]
+        #[cfg(not(windows))]
         for c in components {
-            path.push(c);
+            path.push(raw::encode(c));
         }
 
         path
     }
 
-    fn merge_paths(first: &mut Self, mut second: Self) {
-        if second.components.is_empty() {
-            return;
-        }
-        if first.components.is_empty() && !first.absolute {
+    /// Appends the second path to the first as `PurePath` does. An empty relative path is replaced by the second
+    /// path, including how it is displayed.
+    /// Returns how many components were appended if that is all that happened, with the ones before them left as they
+    /// were, or None if earlier components were removed or replaced.
+    fn merge_paths(first: &mut Self, second: Self) -> Option<usize> {
+        if first.components.is_empty() && !first.absolute && !second.components.is_empty() {
             *first = second;
-            return;
-        }
-        if !first.directory && second.components.first().unwrap() == UP {
-            first.components.pop();
-            first.components.pop();
-            second.components.remove(0);
+            return None;
         }
-        for c in second.components {
-            if c == UP {
-                first.components.pop();
-                continue;
+        let mut merged = PurePath {
+            components: std::mem::take(&mut first.components),
+            absolute: first.absolute,
+            directory: first.directory,
+        };
+        let appended = merged.merge(PurePath {
+            components: second.components,
+            absolute: second.absolute,
+            directory: second.directory,
+        });
+        first.components = merged.components;
+        first.directory = merged.directory;
+        appended
+    }
+
+    /// Brings the `PathBuf` up to date after a merge. When components were only appended, the existing buffer is
+    /// extended with them rather than built again from every component, which keeps joining many names onto the
+    /// same directory cheap.
+    fn update_pathbuf(&mut self, appended: Option<usize>) {
+        let start = self.components.len() - appended.unwrap_or(0);
+        match appended {
+            Some(_)
+                if !self.components[start..]
+                    .iter()
+                    .any(|c| PurePath::is_prefix(c)) =>
+            {
+                for c in &self.components[start..] {
+                    self.path.push(raw::encode(c));
+                }
             }
-            first.components.push(c);
+            _ => self.path = Self::build_pathbuf(&self.components, self.absolute),
         }
-        first.directory = second.directory;
     }
 }
 
+#[cfg(feature = "std")]
+impl Ord for OsPath {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.absolute
+            .cmp(&other.absolute)
+            .then_with(|| self.components.cmp(&other.components))
+            .then_with(|| self.directory.cmp(&other.directory))
+    }
+}
+
+#[cfg(feature = "std")]
+impl PartialOrd for OsPath {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(feature = "std")]
 impl fmt::Display for OsPath {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.build_string())
+        write!(f, "{}", self.styled_string())
     }
 }
 
+#[cfg(feature = "std")]
 impl Serialize for OsPath {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        serializer.serialize_str(&self.build_string())
+        serializer.serialize_str(&self.styled_string())
     }
 }
 
+#[cfg(feature = "std")]
 struct OsPathVisitor;
 
+#[cfg(feature = "std")]
 impl<'de> Visitor<'de> for OsPathVisitor {
     type Value = OsPath;
 
@@ -539,6 +1523,7 @@ impl<'de> Visitor<'de> for OsPathVisitor {
     }
 }
 
+#[cfg(feature = "std")]
 impl<'de> Deserialize<'de> for OsPath {
     fn deserialize<D>(deserializer: D) -> Result<OsPath, D::Error>
     where
@@ -548,66 +1533,77 @@ impl<'de> Deserialize<'de> for OsPath {
     }
 }
 
+#[cfg(feature = "std")]
 impl From<&OsPath> for OsPath {
     fn from(p: &OsPath) -> Self {
         p.clone()
     }
 }
 
+#[cfg(feature = "std")]
 impl From<&str> for OsPath {
     fn from(s: &str) -> Self {
         Self::build_self(s)
     }
 }
 
+#[cfg(feature = "std")]
 impl From<String> for OsPath {
     fn from(s: String) -> Self {
         Self::build_self(s)
     }
 }
 
+#[cfg(feature = "std")]
 impl From<OsPath> for String {
     fn from(p: OsPath) -> Self {
-        p.build_string()
+        p.styled_string()
     }
 }
 
+#[cfg(feature = "std")]
 impl From<&OsPath> for String {
     fn from(p: &OsPath) -> Self {
-        p.build_string()
+        p.styled_string()
     }
 }
 
+#[cfg(feature = "std")]
 impl From<&String> for OsPath {
     fn from(s: &String) -> Self {
         Self::build_self(s)
     }
 }
 
+#[cfg(feature = "std")]
 impl From<PathBuf> for OsPath {
     fn from(p: PathBuf) -> Self {
         Self::build_self(p)
     }
 }
 
+#[cfg(feature = "std")]
 impl From<OsPath> for PathBuf {
     fn from(p: OsPath) -> Self {
         p.path
     }
 }
 
+#[cfg(feature = "std")]
 impl From<&PathBuf> for OsPath {
     fn from(p: &PathBuf) -> Self {
         Self::build_self(p)
     }
 }
 
+#[cfg(feature = "std")]
 impl From<&Path> for OsPath {
     fn from(p: &Path) -> Self {
         Self::build_self(p)
     }
 }
 
+#[cfg(feature = "std")]
 impl FromIterator<OsPath> for OsPath {
     fn from_iter<I: IntoIterator<Item = OsPath>>(iter: I) -> Self {
         let mut path = Self::new();
@@ -618,35 +1614,83 @@ impl FromIterator<OsPath> for OsPath {
     }
 }
 
-impl FromIterator<String> for OsPath {
-    fn from_iter<I: IntoIterator<Item = String>>(iter: I) -> Self {
+#[cfg(feature = "std")]
+impl<S: AsRef<str>> FromIterator<S> for OsPath {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
         let mut path = Self::new();
+        path.extend(iter);
+        path
+    }
+}
+
+#[cfg(feature = "std")]
+impl Extend<OsPath> for OsPath {
+    fn extend<I: IntoIterator<Item = OsPath>>(&mut self, iter: I) {
         for i in iter {
-            path.push(i);
+            self.push(i);
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<S: AsRef<str>> Extend<S> for OsPath {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, iter: I) {
+        for i in iter {
+            self.push(i.as_ref());
         }
-        path
     }
 }
 
+#[cfg(feature = "std")]
 impl AsRef<OsPath> for OsPath {
     fn as_ref(&self) -> &OsPath {
         self
     }
 }
 
+#[cfg(feature = "std")]
 impl AsRef<Path> for OsPath {
     fn as_ref(&self) -> &Path {
         &self.path
     }
 }
 
+#[cfg(feature = "std")]
 impl AsRef<OsStr> for OsPath {
     fn as_ref(&self) -> &OsStr {
         self.path.as_os_str()
     }
 }
 
-#[cfg(test)]
+#[cfg(feature = "std")]
+/// Gives an OsPath every `&self` method of `Path`, and lets `&OsPath` be passed where a `&Path` is expected. Where
+/// OsPath has a method of the same name, such as `join()`, `parent()` or `exists()`, its own method is called; use
+/// `to_path()` to reach the `Path` one.
+/// ```rust
+/// use os_path::OsPath;
+/// use std::path::Path;
+///
+/// fn stem(path: &Path) -> Option<&str> {
+///     path.file_stem()?.to_str()
+/// }
+///
+/// let path = OsPath::from("src/lib.rs");
+/// assert_eq!(stem(&path), Some("lib"));
+/// assert_eq!(path.file_name().unwrap(), "lib.rs");
+/// assert!(path.ends_with("lib.rs"));
+/// assert_eq!(path.display().to_string(), path.to_string());
+/// ```
+impl std::ops::Deref for OsPath {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.path
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+// `test_new` and `test_build_self` spell out the expected value of each flag with `assert_eq!`.
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
 
@@ -724,4 +1768,66 @@ mod tests {
             assert_eq!(path.root().unwrap(), "O:".to_string());
         }
     }
+
+    #[test]
+    fn test_pathological_inputs_never_panic() {
+        let deep = "a/".repeat(10_000);
+        let ups = "../".repeat(10_000);
+        let inputs = [
+            "/",
+            "//",
+            "\\\\",
+            "..",
+            "../..",
+            "/..",
+            "/../..",
+            ".",
+            "./.",
+            "C:",
+            "C:\\",
+            "\u{FFFD}",
+            "a\u{FFFD}b",
+            "~",
+            " ",
+            &deep,
+            &ups,
+        ];
+        for input in inputs {
+            let path = OsPath::try_parse(input).unwrap();
+            let _ = path.parent();
+            let _ = path.name();
+            let _ = path.extension();
+            let _ = path.root();
+            let _ = path.to_string();
+            let mut resolved = path.clone();
+            resolved.resolve();
+            for other in inputs {
+                let _ = path.join(other);
+            }
+        }
+        assert_eq!(OsPath::try_parse(&deep).unwrap().components.len(), 10_000);
+
+        #[cfg(unix)]
+        {
+            let root = OsPath::try_parse("///").unwrap();
+            assert!(root.absolute && root.components.is_empty());
+            assert_eq!(root.parent(), None);
+        }
+    }
+
+    #[test]
+    fn test_join_os_matches_join() {
+        let bases = ["", "/", "a", "a/", "/a/b.txt", "../x/", "C:/d/"];
+        let others = ["", "b", "/b/c/", "../c", "../../../c.txt", "..", "./d"];
+        for base in bases {
+            for other in others {
+                let (base, other) = (OsPath::from(base), OsPath::from(other));
+                // Joining the string form keeps a trailing separator, which going through `Path` would lose.
+                let expected = base.join(other.to_string());
+                let joined = base.join_os(&other);
+                assert_eq!(joined, expected);
+                assert_eq!(joined.path, expected.path);
+            }
+        }
+    }
 }
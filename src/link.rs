@@ -0,0 +1,292 @@
+//! Mirroring directory trees with symbolic links, in the style of GNU Stow.
+
+use crate::copy::make_symlink;
+use crate::error::IoResultExt;
+use crate::filter::PathFilter;
+use crate::fs::{FsBackend, StdFs};
+use crate::{OsPath, PurePath};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Controls the behavior of `link_tree()`, `unlink_tree()`, and `relink_tree()`.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct LinkOptions {
+    filter: Option<PathFilter>,
+    relative: bool,
+}
+
+impl LinkOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only links files matching the filter, relative to the source directory.
+    pub fn filter(mut self, filter: PathFilter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Makes links point to their source by a relative path, so the two trees can be moved together. A link on another
+    /// Windows drive than its source points to it by its absolute path.
+    pub fn relative(mut self, yes: bool) -> Self {
+        self.relative = yes;
+        self
+    }
+}
+
+/// The reasons linking a tree can fail.
+#[derive(Debug)]
+pub enum LinkError {
+    /// These destinations already exist and are not links into the source tree. Nothing was changed.
+    Conflicts(Vec<OsPath>),
+    Io(io::Error),
+}
+
+impl fmt::Display for LinkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LinkError::Conflicts(paths) => {
+                write!(f, "{} conflicting destination", paths.len())?;
+                if paths.len() != 1 {
+                    write!(f, "s")?;
+                }
+                for (i, path) in paths.iter().enumerate() {
+                    write!(f, "{}{}", if i == 0 { ": " } else { ", " }, path)?;
+                }
+                Ok(())
+            }
+            LinkError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for LinkError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LinkError::Io(e) => Some(e),
+            LinkError::Conflicts(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for LinkError {
+    fn from(e: io::Error) -> Self {
+        LinkError::Io(e)
+    }
+}
+
+/// Mirrors the files below `src` into `dest` as symbolic links, creating real directories as needed, and returns
+/// the links created. Links that already point at their source are left alone.
+///
+/// Every destination is checked before anything is created, so a conflict leaves `dest` untouched.
+/// ```rust
+/// #[cfg(unix)]
+/// {
+/// use os_path::{link_tree, unlink_tree, LinkOptions, OsPath};
+///
+/// let tmp = OsPath::from(std::env::temp_dir()).join("os_path_link_doc/");
+/// std::fs::create_dir_all(tmp.join("dotfiles/vim/")).unwrap();
+/// std::fs::create_dir_all(tmp.join("home/")).unwrap();
+/// std::fs::write(tmp.join("dotfiles/vim/.vimrc"), "set nu").unwrap();
+///
+/// let src = tmp.join("dotfiles/vim/");
+/// let home = tmp.join("home/");
+/// let options = LinkOptions::new().relative(true);
+/// let links = link_tree(&src, &home, &options).unwrap();
+///
+/// assert_eq!(links, vec![home.join(".vimrc")]);
+/// assert_eq!(std::fs::read_link(home.join(".vimrc")).unwrap(), OsPath::from("../dotfiles/vim/.vimrc").to_pathbuf());
+/// assert_eq!(unlink_tree(&src, &home, &options).unwrap(), links);
+/// # std::fs::remove_dir_all(&tmp).unwrap();
+/// }
+/// ```
+pub fn link_tree<P: AsRef<Path>, Q: AsRef<Path>>(
+    src: P,
+    dest: Q,
+    options: &LinkOptions,
+) -> Result<Vec<OsPath>, LinkError> {
+    let (src, dest) = (absolute(src.as_ref())?, absolute(dest.as_ref())?);
+    let pairs = plan(&src, &dest, options)?;
+
+    let mut conflicts = BTreeSet::new();
+    let mut todo = Vec::new();
+    for (file, link) in pairs {
+        let mut dir = link.parent();
+        while let Some(d) = dir {
+            if d.components.len() <= dest.components.len() {
+                break;
+            }
+            if std::fs::symlink_metadata(&d).is_ok_and(|m| !m.is_dir()) {
+                conflicts.insert(d.clone());
+            }
+            dir = d.parent();
+        }
+        if std::fs::symlink_metadata(&link).is_ok() {
+            if !links_to(&link, &file) {
+                conflicts.insert(link.clone());
+            }
+        } else {
+            todo.push((file, link));
+        }
+    }
+    if !conflicts.is_empty() {
+        return Err(LinkError::Conflicts(conflicts.into_iter().collect()));
+    }
+
+    let mut created = Vec::new();
+    for (file, link) in todo {
+        if let Some(parent) = link.parent() {
+            std::fs::create_dir_all(&parent).with_path_context("create directory", &parent)?;
+        }
+        let target = match options.relative {
+            true => relative_target(&link, &file).unwrap_or_else(|| file.to_pathbuf()),
+            false => file.to_pathbuf(),
+        };
+        make_symlink(&target, &link)?;
+        created.push(link);
+    }
+    Ok(created)
+}
+
+/// Removes the links in `dest` that point at files below `src`, then any directories below `dest` left empty, and
+/// returns the links removed. Anything else in `dest` is left alone.
+pub fn unlink_tree<P: AsRef<Path>, Q: AsRef<Path>>(
+    src: P,
+    dest: Q,
+    options: &LinkOptions,
+) -> Result<Vec<OsPath>, LinkError> {
+    let (src, dest) = (absolute(src.as_ref())?, absolute(dest.as_ref())?);
+    let mut removed = Vec::new();
+    let mut dirs = BTreeSet::new();
+    for (file, link) in plan(&src, &dest, options)? {
+        if links_to(&link, &file) {
+            std::fs::remove_file(&link)?;
+            let mut dir = link.parent();
+            while let Some(d) = dir {
+                if d.components.len() <= dest.components.len() {
+                    break;
+                }
+                dir = d.parent();
+                dirs.insert(d.components);
+            }
+            removed.push(link);
+        }
+    }
+    // Deepest directories sort after their parents, so walking backwards empties children first.
+    for components in dirs.into_iter().rev() {
+        let mut dir = dest.clone();
+        dir.components = components;
+        dir.path = OsPath::build_pathbuf(&dir.components, dir.absolute);
+        if std::fs::read_dir(&dir).is_ok_and(|mut entries| entries.next().is_none()) {
+            std::fs::remove_dir(&dir)?;
+        }
+    }
+    Ok(removed)
+}
+
+/// Unlinks and then links the tree again, picking up files added to or removed from `src` since it was linked.
+/// Returns the links created.
+pub fn relink_tree<P: AsRef<Path>, Q: AsRef<Path>>(
+    src: P,
+    dest: Q,
+    options: &LinkOptions,
+) -> Result<Vec<OsPath>, LinkError> {
+    let (src, dest) = (absolute(src.as_ref())?, absolute(dest.as_ref())?);
+    // Links to files since removed from `src` can only be in the directories that mirror its directories.
+    let mut dirs = vec![src.clone()];
+    for entry in src.walk() {
+        let entry = entry?;
+        if entry.is_dir() {
+            dirs.push(entry);
+        }
+    }
+    for dir in dirs {
+        let mirror = dest.join(dir.tail_from(src.components.len()));
+        for entry in StdFs.read_dir(&mirror).unwrap_or_default() {
+            if dangling_into(&entry, &src) {
+                std::fs::remove_file(&entry)?;
+            }
+        }
+    }
+    unlink_tree(&src, &dest, options)?;
+    link_tree(&src, &dest, options)
+}
+
+/// Pairs each file to be linked with the link that would mirror it.
+fn plan(src: &OsPath, dest: &OsPath, options: &LinkOptions) -> io::Result<Vec<(OsPath, OsPath)>> {
+    let mut walk = src.walk();
+    if let Some(filter) = &options.filter {
+        walk = walk.with_filter(filter.clone());
+    }
+    let mut pairs = Vec::new();
+    for entry in walk {
+        let file = entry?;
+        if file.is_dir() {
+            continue;
+        }
+        let mut link = dest.clone();
+        link.components
+            .extend_from_slice(&file.components[src.components.len()..]);
+        link.directory = false;
+        link.path = OsPath::build_pathbuf(&link.components, link.absolute);
+        pairs.push((file, link));
+    }
+    Ok(pairs)
+}
+
+fn absolute(path: &Path) -> io::Result<OsPath> {
+    let mut path = OsPath::from(path);
+    if !path.absolute {
+        path = OsPath::from(std::env::current_dir()?).join(&path);
+    }
+    path.resolve();
+    Ok(path)
+}
+
+/// Returns where the link points, resolved against its directory, if it is a link.
+fn link_target(link: &OsPath) -> Option<OsPath> {
+    let target = std::fs::read_link(link).ok()?;
+    let mut target = match link.parent() {
+        Some(parent) if !OsPath::from(&target).absolute => parent.join(&target),
+        _ => OsPath::from(target),
+    };
+    target.resolve();
+    Some(target)
+}
+
+fn links_to(link: &OsPath, file: &OsPath) -> bool {
+    link_target(link)
+        .is_some_and(|t| t.components == file.components && t.absolute == file.absolute)
+}
+
+/// Returns true if the entry is a link into `src` whose target no longer exists.
+fn dangling_into(entry: &OsPath, src: &OsPath) -> bool {
+    link_target(entry).is_some_and(|t| {
+        t.components.starts_with(&src.components) && std::fs::symlink_metadata(&t).is_err()
+    })
+}
+
+/// Returns the path from the link's directory to the file, or None when no relative path joins them, as when they are
+/// on different Windows drives.
+fn relative_target(link: &OsPath, file: &OsPath) -> Option<PathBuf> {
+    let dir = &link.components[..link.components.len().saturating_sub(1)];
+    let common = dir
+        .iter()
+        .zip(&file.components)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let prefixed = |c: &[String]| c.first().is_some_and(|c| PurePath::is_prefix(c));
+    if common == 0 && (prefixed(dir) || prefixed(&file.components)) {
+        return None;
+    }
+    let mut target = PathBuf::new();
+    for _ in common..dir.len() {
+        target.push(crate::UP);
+    }
+    for component in &file.components[common..] {
+        target.push(crate::raw::encode(component));
+    }
+    Some(target)
+}
@@ -0,0 +1,275 @@
+//! Advisory lockfiles kept next to the paths they protect.
+
+use crate::fs::require_os;
+use crate::OsPath;
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// How `lock_path_with()` names the lockfile for a path.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum LockNaming {
+    /// `foo.txt` is locked by `foo.txt.lock`.
+    #[default]
+    Append,
+    /// `foo.txt` is locked by `foo.lock`.
+    ReplaceExtension,
+    /// `foo.txt` is locked by `.foo.txt.lock`.
+    Hidden,
+}
+
+/// The process recorded in a lockfile.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct LockInfo {
+    pub pid: u32,
+    pub host: String,
+}
+
+impl LockInfo {
+    fn current() -> Self {
+        Self {
+            pid: std::process::id(),
+            host: hostname(),
+        }
+    }
+
+    fn parse(text: &str) -> Option<Self> {
+        let mut lines = text.lines();
+        let pid = lines.next()?.trim().parse().ok()?;
+        let host = lines.next()?.trim().to_string();
+        Some(Self { pid, host })
+    }
+
+    /// Returns true if the holder is known to have exited: it ran on this host and its process is gone.
+    fn is_stale(&self) -> bool {
+        self.host == hostname() && !process_alive(self.pid)
+    }
+}
+
+impl fmt::Display for LockInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "process {} on {}", self.pid, self.host)
+    }
+}
+
+/// The reasons a lockfile cannot be acquired.
+#[derive(Debug)]
+pub enum LockError {
+    /// Another live process holds the lock. The holder is unknown if the lockfile could not be read.
+    Held(Option<LockInfo>),
+    Io(io::Error),
+}
+
+impl fmt::Display for LockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LockError::Held(Some(info)) => write!(f, "lock is held by {}", info),
+            LockError::Held(None) => write!(f, "lock is held by an unknown process"),
+            LockError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for LockError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LockError::Io(e) => Some(e),
+            LockError::Held(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for LockError {
+    fn from(e: io::Error) -> Self {
+        LockError::Io(e)
+    }
+}
+
+/// A held lockfile. The file is removed when this is dropped.
+#[derive(Debug)]
+pub struct Lockfile {
+    path: OsPath,
+}
+
+impl Lockfile {
+    /// Returns the path of the lockfile.
+    pub fn path(&self) -> &OsPath {
+        &self.path
+    }
+
+    /// Removes the lockfile, reporting any error that dropping would ignore.
+    pub fn release(self) -> io::Result<()> {
+        let result = std::fs::remove_file(&self.path);
+        std::mem::forget(self);
+        result
+    }
+}
+
+impl Drop for Lockfile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Lock Methods
+impl OsPath {
+    /// Returns the lockfile for this path, named by appending `.lock`. See `lock_path_with()`.
+    /// ```rust
+    /// #[cfg(unix)]
+    /// {
+    /// use os_path::OsPath;
+    ///
+    /// assert_eq!(OsPath::from("/srv/db.sqlite").lock_path(), OsPath::from("/srv/db.sqlite.lock"));
+    /// }
+    /// ```
+    pub fn lock_path(&self) -> Self {
+        self.lock_path_with(LockNaming::Append)
+    }
+
+    /// Returns the lockfile for this path, named as `naming` describes. Directories are locked by a file next to
+    /// them rather than inside them.
+    /// ```rust
+    /// use os_path::{LockNaming, OsPath};
+    ///
+    /// let path = OsPath::from("data/foo.txt");
+    /// assert_eq!(path.lock_path_with(LockNaming::ReplaceExtension), OsPath::from("data/foo.lock"));
+    /// assert_eq!(path.lock_path_with(LockNaming::Hidden), OsPath::from("data/.foo.txt.lock"));
+    /// ```
+    pub fn lock_path_with(&self, naming: LockNaming) -> Self {
+        let name = self.name().cloned().unwrap_or_default();
+        let lock_name = match naming {
+            LockNaming::Append => format!("{}.lock", name),
+            LockNaming::ReplaceExtension => match self.split_extension() {
+                Some((stem, _)) => format!("{}.lock", stem),
+                None => format!("{}.lock", name),
+            },
+            LockNaming::Hidden => format!(".{}.lock", name.trim_start_matches('.')),
+        };
+        let mut lock = self.clone();
+        match lock.components.last_mut() {
+            Some(last) => *last = lock_name,
+            None => lock.components.push(lock_name),
+        }
+        lock.directory = false;
+        lock.path = OsPath::build_pathbuf(&lock.components, lock.absolute);
+        lock
+    }
+
+    /// Atomically creates the lockfile from `lock_path()`, recording this process's ID and host name in it.
+    ///
+    /// A lockfile left by a process on this host that has since exited is removed and the lock taken over. The stale
+    /// file is first renamed aside and checked again, so a fresh lock taken by another process in the meantime is
+    /// put back rather than deleted. Whether a process is alive can only be checked on Unix; elsewhere, and for other
+    /// hosts, existing locks are respected.
+    /// ```rust
+    /// use os_path::{LockError, OsPath};
+    ///
+    /// let path = OsPath::from(std::env::temp_dir()).join("os_path_lock_doc.db");
+    /// let lock = path.try_acquire_lockfile().unwrap();
+    /// assert!(matches!(path.try_acquire_lockfile(), Err(LockError::Held(Some(_)))));
+    ///
+    /// lock.release().unwrap();
+    /// assert!(path.try_acquire_lockfile().is_ok());
+    /// ```
+    pub fn try_acquire_lockfile(&self) -> Result<Lockfile, LockError> {
+        require_os()?;
+        let path = self.lock_path();
+        let info = LockInfo::current();
+        // One retry after clearing a stale lock; if another process wins that race, the lock is theirs.
+        for _ in 0..2 {
+            match std::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+            {
+                Ok(mut file) => {
+                    let lock = Lockfile { path };
+                    writeln!(file, "{}\n{}", info.pid, info.host)?;
+                    file.sync_all()?;
+                    return Ok(lock);
+                }
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                    let holder = read_holder(&path);
+                    match holder {
+                        Some(holder) if holder.is_stale() => clear_stale(&path)?,
+                        holder => return Err(LockError::Held(holder)),
+                    }
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Err(LockError::Held(read_holder(&path)))
+    }
+}
+
+fn read_holder(path: &OsPath) -> Option<LockInfo> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|text| LockInfo::parse(&text))
+}
+
+/// Moves a lockfile judged stale out of the way. Between reading it and removing it another process may have cleared
+/// it and taken the lock, so it is renamed to a name only this call uses and read again there. A lock that turns out
+/// to be live is linked back, which fails harmlessly if yet another lock has appeared meanwhile.
+fn clear_stale(path: &OsPath) -> Result<(), LockError> {
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let aside = path.concat(&format!(".stale{}-{}", std::process::id(), n));
+    match std::fs::rename(path, &aside) {
+        Ok(()) => {}
+        // Someone else cleared it first; trying again settles who gets the lock.
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e.into()),
+    }
+    let holder = read_holder(&aside);
+    if holder.as_ref().is_some_and(LockInfo::is_stale) {
+        std::fs::remove_file(&aside)?;
+        return Ok(());
+    }
+    let _ = std::fs::hard_link(&aside, path);
+    let _ = std::fs::remove_file(&aside);
+    Err(LockError::Held(holder))
+}
+
+fn hostname() -> String {
+    for var in ["HOSTNAME", "COMPUTERNAME"] {
+        if let Ok(name) = std::env::var(var) {
+            if !name.is_empty() {
+                return name;
+            }
+        }
+    }
+    #[cfg(unix)]
+    if let Ok(name) = std::fs::read_to_string("/etc/hostname") {
+        let name = name.trim();
+        if !name.is_empty() {
+            return name.to_string();
+        }
+    }
+    String::from("localhost")
+}
+
+#[cfg(unix)]
+fn process_alive(pid: u32) -> bool {
+    extern "C" {
+        fn kill(pid: i32, sig: i32) -> i32;
+    }
+    const ESRCH: i32 = 3;
+    // Zero and negative IDs name process groups, not a process.
+    let pid = match i32::try_from(pid) {
+        Ok(pid) if pid > 0 => pid,
+        _ => return false,
+    };
+    // Signal 0 checks that the process exists without sending anything. EPERM means it exists but is not ours.
+    // SAFETY: `kill` takes two integers and touches no memory of ours.
+    match unsafe { kill(pid, 0) } {
+        0 => true,
+        _ => io::Error::last_os_error().raw_os_error() != Some(ESRCH),
+    }
+}
+
+#[cfg(not(unix))]
+fn process_alive(_pid: u32) -> bool {
+    true
+}
@@ -0,0 +1,151 @@
+//! Shortening names and turning free text into names without splitting characters.
+//!
+//! With the `unicode` feature, cuts fall between grapheme clusters, so an emoji built from several code points or a
+//! letter followed by combining accents is kept whole or dropped whole. Without it, cuts fall between code points.
+
+use crate::OsPath;
+
+/// Name Methods
+impl OsPath {
+    /// Returns the path with its last component shortened to at most `max_bytes` bytes of UTF-8, the limit most
+    /// filesystems put on a name. The extension is kept and the stem is cut, unless the extension alone does not fit,
+    /// in which case the whole name is cut. A name that already fits is left alone.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// let path = OsPath::from("notes/a very long title.txt");
+    /// assert_eq!(path.truncate_name(10).name().unwrap(), "a very.txt");
+    /// assert_eq!(path.truncate_name(100), path);
+    ///
+    /// #[cfg(feature = "unicode")]
+    /// {
+    ///     // The family emoji is seven code points; it is dropped whole rather than split.
+    ///     let path = OsPath::from("photos/family👨‍👩‍👧.jpg");
+    ///     assert_eq!(path.truncate_name(15).name().unwrap(), "family.jpg");
+    ///     // The `e` and its combining acute accent stay together.
+    ///     let path = OsPath::from("cafe\u{301}.txt");
+    ///     assert_eq!(path.truncate_name(8).name().unwrap(), "caf.txt");
+    /// }
+    /// ```
+    pub fn truncate_name(&self, max_bytes: usize) -> Self {
+        let name = match self.name() {
+            Some(name) if name.len() > max_bytes => name,
+            _ => return self.clone(),
+        };
+        let truncated = match self.split_extension() {
+            Some((stem, ext)) if ext.len() + 1 < max_bytes => {
+                format!("{}.{}", truncate_str(stem, max_bytes - ext.len() - 1), ext)
+            }
+            _ => truncate_str(name, max_bytes).to_string(),
+        };
+        let mut path = self.with_name(truncated);
+        path.directory = self.directory;
+        path
+    }
+
+    /// Replaces the final extension of a file name, or adds one if it has none, so `archive.tar.gz` becomes
+    /// `archive.tar.zst` and `.bashrc` becomes `.bashrc.bak`. A leading `.` on the extension is ignored, and an empty
+    /// extension removes the final one. Directories are left as they are.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// let mut path = OsPath::from("docs/report.txt");
+    /// path.set_extension("md");
+    /// assert_eq!(path, OsPath::from("docs/report.md"));
+    ///
+    /// path.set_extension("");
+    /// assert_eq!(path, OsPath::from("docs/report"));
+    /// ```
+    pub fn set_extension(&mut self, extension: &str) {
+        if self.directory || self.components.is_empty() {
+            return;
+        }
+        let stem = match self.split_extension() {
+            Some((stem, _)) => stem.to_string(),
+            None => self.components[self.components.len() - 1].clone(),
+        };
+        let extension = crate::raw::escape_nul(extension.strip_prefix('.').unwrap_or(extension));
+        let name = if extension.is_empty() {
+            stem
+        } else {
+            format!("{}.{}", stem, extension)
+        };
+        *self = self.with_name(name);
+    }
+
+    /// Returns the path with its final extension replaced or added. See `set_extension()`.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// assert_eq!(OsPath::from("logs/app.tar.gz").with_extension(".zst"), OsPath::from("logs/app.tar.zst"));
+    /// assert_eq!(OsPath::from("Makefile").with_extension("bak"), OsPath::from("Makefile.bak"));
+    /// ```
+    pub fn with_extension(&self, extension: &str) -> Self {
+        let mut new_self = self.clone();
+        new_self.set_extension(extension);
+        new_self
+    }
+
+    /// Turns free text such as a document title into a lowercase name of at most `max_bytes` bytes: runs of
+    /// whitespace, punctuation, and characters that are not allowed in file names become a single `-`, and dashes
+    /// are trimmed from both ends. Letters in any script and emoji are kept.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// assert_eq!(OsPath::slugify("Q3 Report: Draft / v2!", 64), "q3-report-draft-v2");
+    /// assert_eq!(OsPath::slugify("Q3 Report: Draft / v2!", 9), "q3-report");
+    ///
+    /// #[cfg(feature = "unicode")]
+    /// assert_eq!(OsPath::slugify("Trip to Zürich 🇨🇭🇨🇭", 24), "trip-to-zürich-🇨🇭");
+    /// ```
+    pub fn slugify(text: &str, max_bytes: usize) -> String {
+        let mut slug = String::new();
+        let mut dash = false;
+        for unit in units(text) {
+            let keep = unit
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_alphanumeric() || (!c.is_ascii() && !c.is_whitespace()));
+            if !keep {
+                dash = true;
+                continue;
+            }
+            let lower = unit.to_lowercase();
+            let needed = lower.len() + usize::from(dash && !slug.is_empty());
+            if slug.len() + needed > max_bytes {
+                break;
+            }
+            if dash && !slug.is_empty() {
+                slug.push('-');
+            }
+            dash = false;
+            slug.push_str(&lower);
+        }
+        slug
+    }
+}
+
+/// Splits text into the pieces that must not be divided: grapheme clusters, or code points without the `unicode`
+/// feature.
+#[cfg(feature = "unicode")]
+fn units(text: &str) -> impl Iterator<Item = &str> {
+    unicode_segmentation::UnicodeSegmentation::graphemes(text, true)
+}
+
+#[cfg(not(feature = "unicode"))]
+fn units(text: &str) -> impl Iterator<Item = &str> {
+    text.char_indices()
+        .map(move |(i, c)| &text[i..i + c.len_utf8()])
+}
+
+/// Returns the longest prefix of the text of at most `max_bytes` bytes that ends between two units.
+pub(crate) fn truncate_str(text: &str, max_bytes: usize) -> &str {
+    let mut end = 0;
+    for unit in units(text) {
+        if end + unit.len() > max_bytes {
+            break;
+        }
+        end += unit.len();
+    }
+    &text[..end]
+}
@@ -0,0 +1,129 @@
+//! The three levels of normalizing a path: by its text, by its names, and by what the filesystem says.
+//!
+//! Each level does what the one before it does and more. `normalize_lexical()` drops `.` components, which cannot
+//! change what a path names. `resolve()` also cancels a name against a following `..`, which can, when that name is
+//! a symbolic link. `normalize_physical()` asks the filesystem, so links are followed and the result is always right.
+
+use crate::error::IoResultExt;
+use crate::{OsPath, PurePath, UP};
+use std::io;
+use std::path::Path;
+
+/// How much `OsPath::from_normalized()` normalizes a path as it parses it.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Normalization {
+    /// Nothing beyond what `OsPath::from()` does: repeated separators collapse, and `.` and `..` are kept.
+    #[default]
+    Verbatim,
+    /// Drop `.` components as `normalize_lexical()` does.
+    Lexical,
+    /// Drop `.` components and cancel names against `..` as `resolve()` does.
+    Logical,
+}
+
+/// Normalization Methods
+impl OsPath {
+    /// Parses a path as `From` does, then normalizes it to the level given. `From` itself only collapses repeated
+    /// separators, which the path's list of names cannot hold anyway, so code that wants `.` and `..` left alone gets
+    /// that by default, and code that wants them gone says so here.
+    /// ```rust
+    /// #[cfg(unix)]
+    /// {
+    /// use os_path::{Normalization, OsPath};
+    ///
+    /// let text = "./docs//../src/./lib.rs";
+    /// assert_eq!(OsPath::from_normalized(text, Normalization::Verbatim).to_string(), "./docs/../src/./lib.rs");
+    /// assert_eq!(OsPath::from_normalized(text, Normalization::Lexical).to_string(), "docs/../src/lib.rs");
+    /// assert_eq!(OsPath::from_normalized(text, Normalization::Logical).to_string(), "src/lib.rs");
+    /// }
+    /// ```
+    pub fn from_normalized<P: AsRef<Path>>(path: P, level: Normalization) -> Self {
+        let path = Self::build_self(path);
+        match level {
+            Normalization::Verbatim => path,
+            Normalization::Lexical => path.normalize_lexical(),
+            Normalization::Logical => {
+                let mut path = path.normalize_lexical();
+                path.resolve();
+                path
+            }
+        }
+    }
+
+    /// Returns the path with its `.` components dropped, which never changes what it names. `..` is kept, since
+    /// cancelling it against the name before it is only right when that name is not a symbolic link; `resolve()` does
+    /// that. A relative path made only of `.` becomes a single `.`.
+    /// ```rust
+    /// #[cfg(unix)]
+    /// {
+    /// use os_path::OsPath;
+    ///
+    /// assert_eq!(OsPath::from("/srv/./www/../img/.").normalize_lexical().to_string(), "/srv/www/../img/");
+    /// assert_eq!(OsPath::from("./notes.txt").normalize_lexical().to_string(), "notes.txt");
+    /// assert_eq!(OsPath::from("././").normalize_lexical().to_string(), "./");
+    /// }
+    /// ```
+    pub fn normalize_lexical(&self) -> Self {
+        let mut new_self = self.clone();
+        if !new_self.components.iter().any(|c| c == ".") {
+            return new_self;
+        }
+        if self.components.last().is_some_and(|c| c == ".") {
+            new_self.directory = true;
+        }
+        new_self.components.retain(|c| c != ".");
+        if new_self.components.is_empty() && !new_self.absolute {
+            new_self.components.push(".".to_string());
+        }
+        new_self.path = Self::build_pathbuf(&new_self.components, new_self.absolute);
+        new_self
+    }
+
+    /// Returns the absolute path with every symbolic link, `.` and `..` resolved by the filesystem, as
+    /// `canonicalize()` does, except that the path does not have to exist. The longest leading part that exists is
+    /// canonicalized and the rest is appended with `.` and `..` resolved by name, which is safe because a name that
+    /// does not exist cannot be a link. A relative path is taken from the current directory.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// let real = OsPath::from("src/../src/lib.rs").normalize_physical().unwrap();
+    /// assert_eq!(real, OsPath::from("src/lib.rs").canonicalize().unwrap());
+    ///
+    /// let planned = OsPath::from("src/new/./../new/mod.rs").normalize_physical().unwrap();
+    /// assert_eq!(planned, OsPath::from("src/").canonicalize().unwrap().join("new/mod.rs"));
+    /// ```
+    pub fn normalize_physical(&self) -> io::Result<Self> {
+        let mut head = self.normalize_lexical();
+        let mut rest: Vec<String> = Vec::new();
+        loop {
+            let target = match head.components.is_empty() && !head.absolute {
+                true => Path::new("."),
+                false => head.path.as_path(),
+            };
+            match std::fs::canonicalize(target) {
+                Ok(real) => {
+                    let mut new_self = Self::from_canonical(&real, real.is_dir());
+                    if rest.is_empty() {
+                        new_self.directory |= self.directory;
+                        return Ok(new_self);
+                    }
+                    for name in rest.into_iter().rev() {
+                        match name == UP {
+                            true => PurePath::pop_component(&mut new_self.components, true),
+                            false if name == "." => {}
+                            false => new_self.components.push(name),
+                        }
+                    }
+                    new_self.directory = self.directory;
+                    new_self.path = Self::build_pathbuf(&new_self.components, true);
+                    return Ok(new_self);
+                }
+                Err(e) if e.kind() == io::ErrorKind::NotFound && !head.components.is_empty() => {
+                    rest.extend(head.components.pop());
+                    head.path = Self::build_pathbuf(&head.components, head.absolute);
+                }
+                Err(e) => return Err(e).with_path_context("canonicalize", self),
+            }
+        }
+    }
+}
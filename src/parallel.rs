@@ -0,0 +1,219 @@
+//! Walking a directory tree with several threads reading directories at once.
+
+use crate::filter::PathFilter;
+use crate::fs::{require_os, FsBackend, StdFs};
+use crate::OsPath;
+use std::io;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+
+/// The order a `ParallelWalk` yields its entries in.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum WalkOrder {
+    /// As soon as they are read, in no particular order. Nothing is held back.
+    #[default]
+    Unordered,
+    /// In the same order as `walk()`, which means the whole tree is read before the first entry is yielded.
+    Sorted,
+}
+
+/// An iterator over everything below a directory on the real filesystem, read by a pool of threads, created by
+/// `OsPath::par_walk()`.
+///
+/// The threads start when the first entry is asked for. As with `walk()`, the starting directory itself is not
+/// yielded, and errors reading a directory are yielded in place of its contents. Dropping the iterator stops the
+/// threads once they finish the directory they are reading.
+pub struct ParallelWalk {
+    root: OsPath,
+    threads: usize,
+    order: WalkOrder,
+    max_depth: Option<usize>,
+    filter: Option<PathFilter>,
+    results: Option<Results>,
+}
+
+enum Results {
+    Streaming(Receiver<Found>),
+    Sorted(std::vec::IntoIter<Found>),
+}
+
+/// An entry or error, with the path it sorts by. An error sorts right after the directory it was reading.
+type Found = (OsPath, io::Result<OsPath>);
+
+#[derive(Default)]
+struct Queue {
+    dirs: Vec<(OsPath, usize)>,
+    busy: usize,
+    stopped: bool,
+}
+
+struct Shared {
+    queue: Mutex<Queue>,
+    ready: Condvar,
+    root_len: usize,
+    max_depth: Option<usize>,
+    filter: Option<PathFilter>,
+}
+
+impl ParallelWalk {
+    fn new(root: &OsPath) -> Self {
+        Self {
+            root: root.clone(),
+            threads: thread::available_parallelism().map_or(4, |n| n.get()),
+            order: WalkOrder::default(),
+            max_depth: None,
+            filter: None,
+            results: None,
+        }
+    }
+
+    /// Sets how many threads read directories. The default is the number of CPUs; for network shares, where most
+    /// of the time is spent waiting, more can help.
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Sets the order entries are yielded in.
+    pub fn order(mut self, order: WalkOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Limits how deep the walk descends, as `Walk::max_depth()` does.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Only yields entries matching the filter, and skips directories it rules out, as `Walk::with_filter()` does.
+    pub fn with_filter(mut self, filter: PathFilter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    fn start(&self) -> Results {
+        if let Err(e) = require_os() {
+            return Results::Sorted(vec![(self.root.clone(), Err(e))].into_iter());
+        }
+        let shared = Arc::new(Shared {
+            queue: Mutex::new(Queue {
+                // A depth of zero leaves nothing below the starting directory to yield.
+                dirs: match self.max_depth {
+                    Some(0) => Vec::new(),
+                    _ => vec![(self.root.clone(), 0)],
+                },
+                ..Queue::default()
+            }),
+            ready: Condvar::new(),
+            root_len: self.root.components.len(),
+            max_depth: self.max_depth,
+            filter: self.filter.clone(),
+        });
+        let (sender, receiver) = mpsc::channel();
+        for _ in 0..self.threads {
+            let shared = Arc::clone(&shared);
+            let sender = sender.clone();
+            thread::spawn(move || shared.work(&sender));
+        }
+        drop(sender);
+        match self.order {
+            WalkOrder::Unordered => Results::Streaming(receiver),
+            WalkOrder::Sorted => {
+                let mut found: Vec<Found> = receiver.into_iter().collect();
+                found.sort_by(|(a, ra), (b, rb)| a.cmp(b).then(ra.is_err().cmp(&rb.is_err())));
+                Results::Sorted(found.into_iter())
+            }
+        }
+    }
+}
+
+impl Shared {
+    fn work(&self, sender: &Sender<Found>) {
+        while let Some((dir, depth)) = self.take() {
+            let mut subdirs = Vec::new();
+            let mut sent = true;
+            match StdFs.read_dir(&dir) {
+                Ok(entries) => {
+                    for path in entries {
+                        let depth = depth + 1;
+                        let relative = path.tail_from(self.root_len);
+                        let filter = self.filter.as_ref();
+                        if path.is_dir()
+                            && self.max_depth.is_none_or(|max| depth < max)
+                            && filter.is_none_or(|f| f.descends_at(&relative, depth))
+                        {
+                            subdirs.push((path.clone(), depth));
+                        }
+                        if filter.is_none_or(|f| f.matches_at(&relative, depth)) {
+                            sent &= sender.send((path.clone(), Ok(path))).is_ok();
+                        }
+                    }
+                }
+                Err(e) => sent = sender.send((dir, Err(e))).is_ok(),
+            }
+            self.finish(subdirs, !sent);
+        }
+    }
+
+    /// Waits for a directory to read, or returns None once there are none left and none being read.
+    fn take(&self) -> Option<(OsPath, usize)> {
+        let mut queue = self.queue.lock().unwrap_or_else(|e| e.into_inner());
+        loop {
+            if queue.stopped {
+                return None;
+            }
+            if let Some(dir) = queue.dirs.pop() {
+                queue.busy += 1;
+                return Some(dir);
+            }
+            if queue.busy == 0 {
+                return None;
+            }
+            queue = self.ready.wait(queue).unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    fn finish(&self, subdirs: Vec<(OsPath, usize)>, stop: bool) {
+        let mut queue = self.queue.lock().unwrap_or_else(|e| e.into_inner());
+        queue.dirs.extend(subdirs);
+        queue.busy -= 1;
+        queue.stopped |= stop;
+        self.ready.notify_all();
+    }
+}
+
+impl Iterator for ParallelWalk {
+    type Item = io::Result<OsPath>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.results.is_none() {
+            self.results = Some(self.start());
+        }
+        let found = match self.results.as_mut()? {
+            Results::Streaming(receiver) => receiver.recv().ok(),
+            Results::Sorted(found) => found.next(),
+        };
+        found.map(|(_, result)| result)
+    }
+}
+
+/// Parallel Walk Methods
+impl OsPath {
+    /// Recursively walks the directory on the real filesystem with several threads, which is faster than `walk()`
+    /// when reading directories is slow, as on network shares or very large trees.
+    /// ```rust
+    /// use os_path::{OsPath, WalkOrder};
+    ///
+    /// let found: Vec<OsPath> = OsPath::from("src/").par_walk().threads(4).filter_map(Result::ok).collect();
+    /// assert!(found.iter().any(|p| p.name().unwrap() == "lib.rs"));
+    ///
+    /// let sorted = OsPath::from("src/").par_walk().order(WalkOrder::Sorted).filter_map(Result::ok);
+    /// let serial = OsPath::from("src/").walk().filter_map(Result::ok);
+    /// assert!(sorted.eq(serial));
+    /// ```
+    pub fn par_walk(&self) -> ParallelWalk {
+        ParallelWalk::new(self)
+    }
+}
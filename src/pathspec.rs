@@ -0,0 +1,381 @@
+//! Git pathspecs, matched against paths relative to the top of a repository the way git itself matches them.
+
+use crate::OsPath;
+use std::borrow::Cow;
+use std::fmt;
+
+/// The error returned when a pathspec cannot be parsed.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum PathspecError {
+    /// The magic word or symbol is not one git knows, or is one this crate does not support, such as `attr`.
+    UnknownMagic(String),
+    /// A long form `:(...)` was not closed.
+    Unclosed,
+    /// The `literal` and `glob` magic were both given.
+    Incompatible,
+    /// The pathspec climbs above the top of the repository.
+    OutsideRepository,
+}
+
+impl fmt::Display for PathspecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathspecError::UnknownMagic(magic) => {
+                write!(f, "unimplemented pathspec magic `{}`", magic)
+            }
+            PathspecError::Unclosed => f.write_str("missing `)` at the end of pathspec magic"),
+            PathspecError::Incompatible => {
+                f.write_str("`literal` and `glob` pathspec magic are incompatible")
+            }
+            PathspecError::OutsideRepository => f.write_str("pathspec is outside the repository"),
+        }
+    }
+}
+
+impl std::error::Error for PathspecError {}
+
+/// A single git pathspec, such as `src/*.c`, `:(icase)readme*`, or `:!vendor/`.
+///
+/// Both the long form `:(top,icase,exclude,literal,glob)` and the short form `:/`, `:!` and `:^` are understood. A
+/// pathspec without wildcards matches the path it names and everything below it. One with wildcards is matched
+/// against the whole path, and as in git its `*` and `?` also match `/` unless the `glob` magic is given, which makes
+/// them stop at `/` and gives `**` its usual meaning. A trailing `/` matches directories only, and `\` escapes the
+/// character after it.
+///
+/// Paths are relative to the top of the repository. The pathspec is relative to the prefix, the directory the
+/// command runs in, unless it has the `top` magic. The prefix is always matched exactly, even with `icase`.
+/// ```rust
+/// use os_path::{OsPath, Pathspec};
+///
+/// let spec = Pathspec::new("src/*.c").unwrap();
+/// assert!(spec.matches(&OsPath::from("src/main.c")));
+/// assert!(spec.matches(&OsPath::from("src/net/http.c")));
+///
+/// let spec = Pathspec::new(":(glob)src/*.c").unwrap();
+/// assert!(!spec.matches(&OsPath::from("src/net/http.c")));
+///
+/// let spec = Pathspec::with_prefix(":(icase)../README", &OsPath::from("docs/")).unwrap();
+/// assert!(spec.matches(&OsPath::from("readme")));
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Pathspec {
+    spec: String,
+    fixed: Vec<String>,
+    pattern: String,
+    directory: bool,
+    exclude: bool,
+    icase: bool,
+    literal: bool,
+    glob: bool,
+}
+
+impl Pathspec {
+    /// Parses a pathspec given at the top of the repository.
+    pub fn new(spec: &str) -> Result<Self, PathspecError> {
+        Self::with_prefix(spec, &OsPath::new())
+    }
+
+    /// Parses a pathspec given in the prefix directory, which is relative to the top of the repository.
+    pub fn with_prefix(spec: &str, prefix: &OsPath) -> Result<Self, PathspecError> {
+        let mut parsed = Self {
+            spec: spec.to_string(),
+            fixed: Vec::new(),
+            pattern: String::new(),
+            directory: false,
+            exclude: false,
+            icase: false,
+            literal: false,
+            glob: false,
+        };
+        let mut top = false;
+        let rest = if let Some(long) = spec.strip_prefix(":(") {
+            let (magic, rest) = long.split_once(')').ok_or(PathspecError::Unclosed)?;
+            for word in magic.split(',').map(str::trim).filter(|w| !w.is_empty()) {
+                match word {
+                    "top" => top = true,
+                    "exclude" => parsed.exclude = true,
+                    "icase" => parsed.icase = true,
+                    "literal" => parsed.literal = true,
+                    "glob" => parsed.glob = true,
+                    _ => return Err(PathspecError::UnknownMagic(word.to_string())),
+                }
+            }
+            rest
+        } else if let Some(short) = spec.strip_prefix(':') {
+            let mut rest = short;
+            while let Some(c) = rest.chars().next() {
+                match c {
+                    '/' => top = true,
+                    '!' | '^' => parsed.exclude = true,
+                    ':' => {
+                        rest = &rest[1..];
+                        break;
+                    }
+                    c if c.is_ascii_punctuation() && !"*?[\\".contains(c) => {
+                        return Err(PathspecError::UnknownMagic(c.to_string()));
+                    }
+                    _ => break,
+                }
+                rest = &rest[c.len_utf8()..];
+            }
+            rest
+        } else {
+            spec
+        };
+        if parsed.literal && parsed.glob {
+            return Err(PathspecError::Incompatible);
+        }
+
+        let mut components: Vec<String> = if top {
+            Vec::new()
+        } else {
+            prefix.components.clone()
+        };
+        let mut fixed = components.len();
+        for part in rest.split('/').filter(|p| !p.is_empty() && *p != ".") {
+            if part == crate::UP {
+                components.pop().ok_or(PathspecError::OutsideRepository)?;
+                fixed = fixed.min(components.len());
+            } else {
+                components.push(crate::raw::escape_nul(part).into_owned());
+            }
+        }
+        parsed.pattern = components.split_off(fixed).join("/");
+        parsed.fixed = components;
+        parsed.directory = rest.ends_with('/') && !parsed.pattern.is_empty();
+        Ok(parsed)
+    }
+
+    /// Returns the pathspec as it was written.
+    pub fn as_str(&self) -> &str {
+        &self.spec
+    }
+
+    /// Returns true if the pathspec has the `exclude` magic.
+    pub fn is_exclude(&self) -> bool {
+        self.exclude
+    }
+
+    /// Returns true if the path, relative to the top of the repository, is matched by the pathspec. The `exclude`
+    /// magic is not applied here; `PathspecSet` applies it.
+    pub fn matches(&self, path: &OsPath) -> bool {
+        if path.absolute || !path.components.starts_with(&self.fixed) {
+            return false;
+        }
+        if self.pattern.is_empty() {
+            return true;
+        }
+        let mut text = path.components[self.fixed.len()..].join("/");
+        let mut pattern = Cow::Borrowed(self.pattern.as_str());
+        if self.icase {
+            text = text.to_lowercase();
+            pattern = Cow::Owned(pattern.to_lowercase());
+        }
+        let wild = !self.literal && pattern.contains(['*', '?', '[', '\\']);
+        if !wild {
+            return match text.strip_prefix(pattern.as_ref()) {
+                Some("") => path.directory || !self.directory,
+                Some(below) => below.starts_with('/'),
+                None => false,
+            };
+        }
+        let pattern: Vec<char> = pattern.chars().collect();
+        let text: Vec<char> = text.chars().collect();
+        (path.directory || !self.directory) && wildmatch(&pattern, &text, self.glob)
+    }
+}
+
+impl fmt::Display for Pathspec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.spec)
+    }
+}
+
+/// How far a wildcard match got, as git's wildmatch reports it. The two aborts tell an enclosing `*` that moving on
+/// through the text cannot help, which keeps matching polynomial where retrying every split would be exponential.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Wild {
+    Match,
+    NoMatch,
+    /// The text ran out before the pattern did, so it will for every later split too.
+    AbortAll,
+    /// A `*` reached a `/` it cannot cross, so only an enclosing `**` can carry on.
+    AbortToStarStar,
+}
+
+/// Matches text against the git wildcard pattern. With `pathname`, `*`, `?` and classes do not match `/`, and a `**`
+/// standing between slashes matches any number of whole directories.
+fn wildmatch(pattern: &[char], text: &[char], pathname: bool) -> bool {
+    dowild(pattern, 0, text, pathname) == Wild::Match
+}
+
+/// Matches the text against the pattern from position `at` on.
+fn dowild(pattern: &[char], mut at: usize, mut text: &[char], pathname: bool) -> Wild {
+    while let Some(&p) = pattern.get(at) {
+        if p == '*' {
+            return star(pattern, at, text, pathname);
+        }
+        let Some((&c, tail)) = text.split_first() else {
+            return Wild::AbortAll;
+        };
+        let matched = match p {
+            '?' => !(pathname && c == '/'),
+            '[' => match Class::parse(&pattern[at + 1..]) {
+                Some(class) => {
+                    at += class.len;
+                    !(pathname && c == '/') && class.contains(c)
+                }
+                None => c == p,
+            },
+            '\\' => match pattern.get(at + 1) {
+                Some(&escaped) => {
+                    at += 1;
+                    c == escaped
+                }
+                None => c == p,
+            },
+            _ => c == p,
+        };
+        if !matched {
+            return Wild::NoMatch;
+        }
+        at += 1;
+        text = tail;
+    }
+    match text.is_empty() {
+        true => Wild::Match,
+        false => Wild::NoMatch,
+    }
+}
+
+/// Matches a run of `*` at `at`, then the rest of the pattern, against the text.
+fn star(pattern: &[char], at: usize, text: &[char], pathname: bool) -> Wild {
+    let stars = pattern[at..].iter().take_while(|c| **c == '*').count();
+    let after = at + stars;
+    let whole = at == 0 || pattern[at - 1] == '/';
+    let match_slash = match pattern.get(after) {
+        _ if !pathname => true,
+        None | Some('/') if stars > 1 && whole => {
+            // `**/` also matches no directory at all.
+            if pattern.get(after) == Some(&'/')
+                && dowild(pattern, after + 1, text, true) == Wild::Match
+            {
+                return Wild::Match;
+            }
+            true
+        }
+        _ => false,
+    };
+    if after == pattern.len() {
+        return match !match_slash && text.contains(&'/') {
+            true => Wild::AbortToStarStar,
+            false => Wild::Match,
+        };
+    }
+    for (i, &c) in text.iter().enumerate() {
+        match dowild(pattern, after, &text[i..], pathname) {
+            Wild::NoMatch if !match_slash && c == '/' => return Wild::AbortToStarStar,
+            Wild::NoMatch => {}
+            Wild::AbortToStarStar if match_slash => {}
+            matched => return matched,
+        }
+    }
+    Wild::AbortAll
+}
+
+/// A bracketed character class in a wildcard pattern.
+struct Class {
+    negated: bool,
+    ranges: Vec<(char, char)>,
+    /// The length of the class after its `[`, up to and including its `]`.
+    len: usize,
+}
+
+impl Class {
+    /// Parses a class after its `[`, or returns None if it is not closed.
+    fn parse(pattern: &[char]) -> Option<Self> {
+        let negated = matches!(pattern.first(), Some('!') | Some('^'));
+        let mut i = usize::from(negated);
+        let mut ranges = Vec::new();
+        loop {
+            let mut c = *pattern.get(i)?;
+            if c == ']' && !ranges.is_empty() {
+                return Some(Self {
+                    negated,
+                    ranges,
+                    len: i + 1,
+                });
+            }
+            if c == '\\' {
+                i += 1;
+                c = *pattern.get(i)?;
+            }
+            match (pattern.get(i + 1), pattern.get(i + 2)) {
+                (Some('-'), Some(&end)) if end != ']' => {
+                    ranges.push((c, end));
+                    i += 3;
+                }
+                _ => {
+                    ranges.push((c, c));
+                    i += 1;
+                }
+            }
+        }
+    }
+
+    fn contains(&self, c: char) -> bool {
+        self.ranges.iter().any(|(lo, hi)| (*lo..=*hi).contains(&c)) != self.negated
+    }
+}
+
+/// A list of pathspecs matched together as git matches them: a path is matched when it matches any pathspec without
+/// the `exclude` magic and none with it. If every pathspec is an exclude, paths only have to avoid them.
+/// ```rust
+/// use os_path::{OsPath, PathspecSet};
+///
+/// let specs = PathspecSet::new(["src/", ":(icase)*.MD", ":!src/vendor/"]).unwrap();
+/// assert!(specs.matches(&OsPath::from("src/lib.rs")));
+/// assert!(specs.matches(&OsPath::from("docs/Guide.md")));
+/// assert!(!specs.matches(&OsPath::from("src/vendor/zlib.c")));
+/// assert!(!specs.matches(&OsPath::from("build.rs")));
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct PathspecSet {
+    specs: Vec<Pathspec>,
+}
+
+impl PathspecSet {
+    /// Parses pathspecs given at the top of the repository.
+    pub fn new<I, S>(specs: I) -> Result<Self, PathspecError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self::with_prefix(specs, &OsPath::new())
+    }
+
+    /// Parses pathspecs given in the prefix directory, which is relative to the top of the repository.
+    pub fn with_prefix<I, S>(specs: I, prefix: &OsPath) -> Result<Self, PathspecError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let specs = specs
+            .into_iter()
+            .map(|s| Pathspec::with_prefix(s.as_ref(), prefix))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { specs })
+    }
+
+    /// Returns the pathspecs in the order they were given.
+    pub fn specs(&self) -> &[Pathspec] {
+        &self.specs
+    }
+
+    /// Returns true if the path, relative to the top of the repository, is matched.
+    pub fn matches(&self, path: &OsPath) -> bool {
+        let mut includes = self.specs.iter().filter(|s| !s.exclude).peekable();
+        let included = includes.peek().is_none() || includes.any(|s| s.matches(path));
+        included && !self.specs.iter().any(|s| s.exclude && s.matches(path))
+    }
+}
@@ -0,0 +1,189 @@
+//! Ordered allow and deny rules evaluated against paths.
+
+use crate::glob::{Glob, GlobError};
+use crate::OsPath;
+use std::fmt;
+use std::path::Path;
+
+/// Whether a rule lets a path through.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Effect {
+    Allow,
+    Deny,
+}
+
+/// What a `Rule` is matched against.
+#[derive(Clone, PartialEq, Debug)]
+pub enum RuleMatcher {
+    /// Matches paths matching the glob.
+    Glob(Glob),
+    /// Matches the path itself and everything below it.
+    Prefix(OsPath),
+}
+
+impl RuleMatcher {
+    fn matches(&self, path: &OsPath) -> bool {
+        match self {
+            RuleMatcher::Glob(glob) => glob.matches(path),
+            RuleMatcher::Prefix(prefix) => {
+                prefix.absolute == path.absolute && path.components.starts_with(&prefix.components)
+            }
+        }
+    }
+}
+
+/// A single entry of a `Policy`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Rule {
+    pub matcher: RuleMatcher,
+    pub effect: Effect,
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let effect = match self.effect {
+            Effect::Allow => "allow",
+            Effect::Deny => "deny",
+        };
+        match &self.matcher {
+            RuleMatcher::Glob(glob) => write!(f, "{} glob `{}`", effect, glob),
+            RuleMatcher::Prefix(prefix) => write!(f, "{} prefix `{}`", effect, prefix),
+        }
+    }
+}
+
+/// The outcome of `Policy::evaluate()`, recording which rule decided it.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Decision {
+    effect: Effect,
+    rule: Option<(usize, Rule)>,
+}
+
+impl Decision {
+    pub fn effect(&self) -> Effect {
+        self.effect
+    }
+
+    pub fn is_allowed(&self) -> bool {
+        self.effect == Effect::Allow
+    }
+
+    /// Returns the index of the deciding rule, or None if no rule matched and the default applied.
+    pub fn rule_index(&self) -> Option<usize> {
+        self.rule.as_ref().map(|(i, _)| *i)
+    }
+
+    /// Returns the deciding rule, or None if no rule matched and the default applied.
+    pub fn rule(&self) -> Option<&Rule> {
+        self.rule.as_ref().map(|(_, rule)| rule)
+    }
+}
+
+impl fmt::Display for Decision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verdict = match self.effect {
+            Effect::Allow => "allowed",
+            Effect::Deny => "denied",
+        };
+        match &self.rule {
+            Some((i, rule)) => write!(f, "{} by rule {} ({})", verdict, i, rule),
+            None => write!(f, "{} by default", verdict),
+        }
+    }
+}
+
+/// An ordered list of allow and deny rules. The first rule matching a path decides; if none match, the policy's
+/// default applies. Paths have their `..` components resolved before matching, so they cannot sidestep a prefix.
+/// ```rust
+/// use os_path::{Effect, OsPath, Policy};
+///
+/// let policy = Policy::new(Effect::Deny)
+///     .deny_glob("**/*.key").unwrap()
+///     .allow_prefix("/plugins/data/")
+///     .allow_glob("/plugins/**/*.toml").unwrap();
+///
+/// assert!(policy.is_allowed(&OsPath::from("/plugins/data/cache.bin")));
+/// assert!(policy.is_allowed(&OsPath::from("/plugins/x/config.toml")));
+///
+/// let decision = policy.evaluate(&OsPath::from("/plugins/data/secret.key"));
+/// assert!(!decision.is_allowed());
+/// assert_eq!(decision.to_string(), "denied by rule 0 (deny glob `**/*.key`)");
+///
+/// let decision = policy.evaluate(&OsPath::from("/plugins/data/../../etc/passwd"));
+/// assert_eq!(decision.to_string(), "denied by default");
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct Policy {
+    rules: Vec<Rule>,
+    default: Effect,
+}
+
+impl Policy {
+    /// Creates an empty policy that decides `default` for every path.
+    pub fn new(default: Effect) -> Self {
+        Self {
+            rules: Vec::new(),
+            default,
+        }
+    }
+
+    /// Appends a rule.
+    pub fn rule(mut self, matcher: RuleMatcher, effect: Effect) -> Self {
+        self.rules.push(Rule { matcher, effect });
+        self
+    }
+
+    /// Appends a rule allowing paths matching the glob.
+    pub fn allow_glob(self, pattern: &str) -> Result<Self, GlobError> {
+        Ok(self.rule(RuleMatcher::Glob(Glob::new(pattern)?), Effect::Allow))
+    }
+
+    /// Appends a rule denying paths matching the glob.
+    pub fn deny_glob(self, pattern: &str) -> Result<Self, GlobError> {
+        Ok(self.rule(RuleMatcher::Glob(Glob::new(pattern)?), Effect::Deny))
+    }
+
+    /// Appends a rule allowing the prefix and everything below it.
+    pub fn allow_prefix<P: AsRef<Path>>(self, prefix: P) -> Self {
+        self.rule(RuleMatcher::Prefix(Self::prefix(prefix)), Effect::Allow)
+    }
+
+    /// Appends a rule denying the prefix and everything below it.
+    pub fn deny_prefix<P: AsRef<Path>>(self, prefix: P) -> Self {
+        self.rule(RuleMatcher::Prefix(Self::prefix(prefix)), Effect::Deny)
+    }
+
+    /// Returns the rules in evaluation order.
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    /// Decides whether the path is allowed, and which rule decided it.
+    pub fn evaluate(&self, path: &OsPath) -> Decision {
+        let mut path = path.clone();
+        path.resolve();
+        for (i, rule) in self.rules.iter().enumerate() {
+            if rule.matcher.matches(&path) {
+                return Decision {
+                    effect: rule.effect,
+                    rule: Some((i, rule.clone())),
+                };
+            }
+        }
+        Decision {
+            effect: self.default,
+            rule: None,
+        }
+    }
+
+    /// Returns true if the policy allows the path.
+    pub fn is_allowed(&self, path: &OsPath) -> bool {
+        self.evaluate(path).is_allowed()
+    }
+
+    fn prefix<P: AsRef<Path>>(prefix: P) -> OsPath {
+        let mut prefix = OsPath::from(prefix.as_ref());
+        prefix.resolve();
+        prefix
+    }
+}
@@ -0,0 +1,80 @@
+//! Keeping the exact text a path was written as next to its normalized form.
+
+use crate::OsPath;
+use std::ops::Deref;
+use std::path::Path;
+
+/// A path together with the exact string it was parsed from, made by `OsPath::preserving()`, for tools such as linters
+/// and formatters that analyse paths but must echo back precisely what the user wrote.
+///
+/// It dereferences to the normalized `OsPath`, and compares equal to another when the normalized paths are equal.
+#[derive(Clone, Debug)]
+pub struct PreservedPath {
+    original: String,
+    path: OsPath,
+}
+
+impl PreservedPath {
+    /// Returns the string exactly as it was given.
+    pub fn original(&self) -> &str {
+        &self.original
+    }
+
+    /// Returns the normalized path.
+    pub fn path(&self) -> &OsPath {
+        &self.path
+    }
+
+    /// Returns the normalized path, dropping the original string.
+    pub fn into_path(self) -> OsPath {
+        self.path
+    }
+}
+
+impl Deref for PreservedPath {
+    type Target = OsPath;
+
+    fn deref(&self) -> &OsPath {
+        &self.path
+    }
+}
+
+impl AsRef<Path> for PreservedPath {
+    fn as_ref(&self) -> &Path {
+        self.path.as_ref()
+    }
+}
+
+impl PartialEq for PreservedPath {
+    fn eq(&self, other: &Self) -> bool {
+        self.path == other.path
+    }
+}
+
+impl PartialEq<OsPath> for PreservedPath {
+    fn eq(&self, other: &OsPath) -> bool {
+        self.path == *other
+    }
+}
+
+/// Preservation Methods
+impl OsPath {
+    /// Parses a string into a path as `from()` does, keeping the exact string alongside it.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// let written = OsPath::preserving("./src//main.rs");
+    /// assert_eq!(written.original(), "./src//main.rs");
+    /// assert_eq!(written, OsPath::from("./src/main.rs"));
+    /// assert_eq!(written.name().unwrap(), "main.rs");
+    ///
+    /// let other = OsPath::preserving("./src/main.rs");
+    /// assert_eq!(written, other);
+    /// assert_ne!(written.original(), other.original());
+    /// ```
+    pub fn preserving<S: Into<String>>(input: S) -> PreservedPath {
+        let original = input.into();
+        let path = Self::build_from_str(&original);
+        PreservedPath { original, path }
+    }
+}
@@ -0,0 +1,95 @@
+//! Working directory and child process helpers.
+
+use crate::OsPath;
+use std::ffi::OsStr;
+use std::io;
+use std::process::{Child, Command};
+
+/// Restores the previous working directory when dropped. Created by `OsPath::push_current_dir()`.
+#[derive(Debug)]
+#[must_use = "the previous directory is restored as soon as the guard is dropped"]
+pub struct CurrentDirGuard {
+    previous: OsPath,
+}
+
+impl CurrentDirGuard {
+    /// Returns the directory that will be restored.
+    pub fn previous(&self) -> &OsPath {
+        &self.previous
+    }
+}
+
+impl Drop for CurrentDirGuard {
+    fn drop(&mut self) {
+        let _ = std::env::set_current_dir(&self.previous);
+    }
+}
+
+/// Process Methods
+impl OsPath {
+    /// Makes this directory the working directory until the returned guard is dropped.
+    ///
+    /// The working directory belongs to the whole process, so while the guard lives, every thread sees the change,
+    /// and guards held on different threads undo each other. Tests using this should not run in parallel with tests
+    /// that depend on the working directory. Guards on one thread must be dropped in the reverse order they were
+    /// created, which scoping does naturally.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// let start = std::env::current_dir().unwrap();
+    /// {
+    ///     let _guard = OsPath::from(std::env::temp_dir()).push_current_dir().unwrap();
+    ///     assert_ne!(std::env::current_dir().unwrap(), start);
+    /// }
+    /// assert_eq!(std::env::current_dir().unwrap(), start);
+    /// ```
+    pub fn push_current_dir(&self) -> io::Result<CurrentDirGuard> {
+        let previous = OsPath::from(std::env::current_dir()?);
+        std::env::set_current_dir(self)?;
+        Ok(CurrentDirGuard { previous })
+    }
+
+    /// Starts the executable at this path with the given arguments, inheriting the working directory, environment,
+    /// and standard streams.
+    /// ```rust
+    /// #[cfg(unix)]
+    /// {
+    /// use os_path::OsPath;
+    ///
+    /// let status = OsPath::from("/bin/sh").run(["-c", "exit 3"]).unwrap().wait().unwrap();
+    /// assert_eq!(status.code(), Some(3));
+    /// }
+    /// ```
+    pub fn run<I, S>(&self, args: I) -> io::Result<Child>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        Command::new(self).args(args).spawn()
+    }
+
+    /// Starts `program` with the given arguments, using this directory as its working directory. The program is
+    /// looked up on `PATH` as `Command::new()` does.
+    /// ```rust
+    /// #[cfg(unix)]
+    /// {
+    /// use os_path::OsPath;
+    ///
+    /// let dir = OsPath::from(std::env::temp_dir()).join("os_path_run_in_doc/");
+    /// std::fs::create_dir_all(&dir).unwrap();
+    /// std::fs::write(dir.join("marker"), "").unwrap();
+    ///
+    /// let status = dir.run_in("test", ["-f", "marker"]).unwrap().wait().unwrap();
+    /// assert!(status.success());
+    /// # std::fs::remove_dir_all(&dir).unwrap();
+    /// }
+    /// ```
+    pub fn run_in<P, I, S>(&self, program: P, args: I) -> io::Result<Child>
+    where
+        P: AsRef<OsStr>,
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        Command::new(program).args(args).current_dir(self).spawn()
+    }
+}
@@ -0,0 +1,94 @@
+//! Removing files that have not been modified recently.
+
+use crate::error::IoResultExt;
+use crate::filter::PathFilter;
+use crate::fs::{require_os, FsBackend, StdFs};
+use crate::OsPath;
+use std::io;
+use std::time::{Duration, SystemTime};
+
+/// Prune Methods
+impl OsPath {
+    /// Deletes the files below this directory that match the filter and were last modified more than `older_than`
+    /// ago, returning the files deleted. Directories are left in place, even if they end up empty.
+    /// ```rust
+    /// use os_path::{OsPath, PathFilter};
+    /// use std::time::Duration;
+    ///
+    /// let cache = OsPath::from(std::env::temp_dir()).join("os_path_prune_doc/");
+    /// std::fs::create_dir_all(&cache).unwrap();
+    /// std::fs::write(cache.join("fresh.tmp"), "").unwrap();
+    ///
+    /// let removed = cache.prune(Duration::from_secs(3600), &PathFilter::new()).unwrap();
+    /// assert!(removed.is_empty());
+    /// assert!(cache.join("fresh.tmp").exists());
+    /// # std::fs::remove_dir_all(&cache).unwrap();
+    /// ```
+    pub fn prune(&self, older_than: Duration, filter: &PathFilter) -> io::Result<Vec<OsPath>> {
+        let stale = self.prune_dry_run(older_than, filter)?;
+        for path in &stale {
+            std::fs::remove_file(path).with_path_context("remove", path)?;
+        }
+        Ok(stale)
+    }
+
+    /// Returns the files `prune()` would delete, without deleting anything.
+    pub fn prune_dry_run(
+        &self,
+        older_than: Duration,
+        filter: &PathFilter,
+    ) -> io::Result<Vec<OsPath>> {
+        require_os()?;
+        self.prune_dry_run_in(older_than, filter, &StdFs)
+    }
+
+    /// Returns the files in the given backend that `prune()` would delete, in walk order. Files without a
+    /// modification time are never stale.
+    /// ```rust
+    /// #[cfg(unix)]
+    /// {
+    /// use os_path::{MemoryFs, OsPath, PathFilter};
+    /// use std::time::{Duration, SystemTime};
+    ///
+    /// let day = Duration::from_secs(24 * 60 * 60);
+    /// let now = SystemTime::now();
+    /// let mut fs = MemoryFs::new();
+    /// fs.add_file_modified("/cache/old.bin", "", now - 10 * day).unwrap()
+    ///     .add_file_modified("/cache/old.keep", "", now - 10 * day).unwrap()
+    ///     .add_file_modified("/cache/new.bin", "", now).unwrap();
+    ///
+    /// let filter = PathFilter::new().exclude("*.keep").unwrap();
+    /// let stale = OsPath::from("/cache/").prune_dry_run_in(7 * day, &filter, &fs).unwrap();
+    /// assert_eq!(stale, vec![OsPath::from("/cache/old.bin")]);
+    /// }
+    /// ```
+    pub fn prune_dry_run_in(
+        &self,
+        older_than: Duration,
+        filter: &PathFilter,
+        fs: &dyn FsBackend,
+    ) -> io::Result<Vec<OsPath>> {
+        if cfg!(all(target_family = "wasm", target_os = "unknown")) {
+            // The backend may need no operating system, but reading the clock does.
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "this platform has no clock",
+            ));
+        }
+        let cutoff = SystemTime::now()
+            .checked_sub(older_than)
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        let mut stale = Vec::new();
+        for entry in self.walk_in(fs).with_filter(filter.clone()) {
+            let entry = entry?;
+            if entry.is_dir() {
+                continue;
+            }
+            let meta = fs.metadata(&entry)?;
+            if meta.is_file() && meta.modified().is_some_and(|m| m < cutoff) {
+                stale.push(entry);
+            }
+        }
+        Ok(stale)
+    }
+}
@@ -0,0 +1,346 @@
+//! The part of path handling that needs no operating system: parsing text into components, joining, resolving `..`,
+//! and rendering. It only needs `alloc`, so it is all that is built without the `std` feature, and `OsPath` is built
+//! on top of it.
+
+use crate::components::Components;
+use crate::raw;
+#[cfg(not(windows))]
+use crate::ROOT;
+use crate::{BS, FS, SLASH, SLASH_STR, UP};
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+/// A path held as its components alone, with the same parsing, joining, resolution and display as `OsPath` but no
+/// `PathBuf` behind it, so it works without the standard library, as in a WebAssembly plugin that has no filesystem.
+///
+/// With the `std` feature, a `PurePath` converts to and from an `OsPath` without being parsed again.
+/// ```rust
+/// #[cfg(unix)]
+/// {
+/// use os_path::PurePath;
+///
+/// let mut path = PurePath::from("/srv/www/");
+/// path.push("/static/../index.html");
+/// assert_eq!(path.to_string(), "/srv/www/index.html");
+/// assert_eq!(path.components().collect::<Vec<_>>(), ["srv", "www", "index.html"]);
+///
+/// let mut relative = PurePath::from("a\\b/../../../c/");
+/// relative.resolve();
+/// assert_eq!(relative.to_string(), "../c/");
+/// assert!(relative.is_dir() && !relative.is_absolute());
+/// }
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct PurePath {
+    pub(crate) components: Vec<String>,
+    pub(crate) absolute: bool,
+    pub(crate) directory: bool,
+}
+
+/// Public Methods
+impl PurePath {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a new path with the text joined to this one, as `OsPath::join()` does: a leading separator does not
+    /// replace the path, and `..` climbs out of it.
+    pub fn join<S: AsRef<str>>(&self, path: S) -> Self {
+        let mut new_self = self.clone();
+        new_self.push(path);
+        new_self
+    }
+
+    /// Appends the text to the path, as `OsPath::push()` does.
+    pub fn push<S: AsRef<str>>(&mut self, path: S) {
+        self.merge(Self::from(path.as_ref()));
+    }
+
+    /// Resolves the `..` components, as `OsPath::resolve()` does. A relative path keeps any `..` that climbs above
+    /// its start, while an absolute path stops at its root.
+    pub fn resolve(&mut self) {
+        self.components = Self::resolved(&self.components, self.absolute);
+    }
+
+    /// Returns an iterator over the components of the path, from the first to the name.
+    pub fn components(&self) -> Components<'_> {
+        Components::new(&self.components)
+    }
+
+    /// Returns the last component, if there is one.
+    pub fn name(&self) -> Option<&str> {
+        self.components.last().map(String::as_str)
+    }
+
+    /// Returns true if the path is absolute.
+    pub fn is_absolute(&self) -> bool {
+        self.absolute
+    }
+
+    /// Returns true if the path names a file, which is any path not written with a trailing separator.
+    pub fn is_file(&self) -> bool {
+        !self.directory
+    }
+
+    /// Returns true if the path names a directory.
+    pub fn is_dir(&self) -> bool {
+        self.directory
+    }
+
+    /// Marks the path as a directory.
+    pub fn force_dir(&mut self) {
+        self.directory = true;
+    }
+}
+
+/// Private Methods
+impl PurePath {
+    /// Parses text already in the escaped form components are stored in.
+    pub(crate) fn parse(path: &str) -> Self {
+        #[cfg(windows)]
+        if let Some((prefix, rest)) = Self::split_unc(path) {
+            let mut unc = Self::parse(rest);
+            unc.components.insert(0, prefix);
+            unc.absolute = true;
+            unc.directory = unc.directory || unc.components.len() == 1;
+            return unc;
+        }
+
+        #[cfg(not(windows))]
+        let absolute = path.starts_with(ROOT) || path.starts_with(BS) || path.starts_with(FS);
+
+        let directory = path.ends_with(SLASH) || path.ends_with(UP);
+        let components: Vec<String> = path
+            .split([BS, FS])
+            .filter_map(|s| {
+                if s.is_empty() {
+                    None
+                } else {
+                    Some(s.to_string())
+                }
+            })
+            .collect();
+
+        #[cfg(windows)]
+        let (components, absolute) = Self::split_drive(components);
+
+        Self {
+            components,
+            absolute,
+            directory,
+        }
+    }
+
+    /// Detects a leading drive, which makes the path absolute, and upper-cases its letter so that `c:\` and `C:\`
+    /// are the same path.
+    #[cfg(any(windows, feature = "std"))]
+    pub(crate) fn split_drive(mut components: Vec<String>) -> (Vec<String>, bool) {
+        match components.first_mut() {
+            Some(first) if Self::is_drive(first) => {
+                first.make_ascii_uppercase();
+                (components, true)
+            }
+            _ => (components, false),
+        }
+    }
+
+    /// Splits a leading UNC prefix such as `\\server\share`, or a verbatim prefix such as `\\?\C:` or
+    /// `\\?\UNC\server\share`, from the rest of the path. The prefix is kept whole as the first component.
+    #[cfg(any(windows, feature = "std"))]
+    pub(crate) fn split_unc(path: &str) -> Option<(String, &str)> {
+        let rest = path.strip_prefix(r"\\")?;
+        if let Some(verbatim) = rest.strip_prefix(r"?\") {
+            if let Some(unc) = verbatim.strip_prefix(r"UNC\") {
+                let (server, share, rest) = Self::split_server_share(unc)?;
+                return Some((alloc::format!(r"\\?\UNC\{}\{}", server, share), rest));
+            }
+            let end = verbatim.find(BS).unwrap_or(verbatim.len());
+            if end == 0 {
+                return None;
+            }
+            return Some((
+                alloc::format!(r"\\?\{}", &verbatim[..end]),
+                &verbatim[end..],
+            ));
+        }
+        let (server, share, rest) = Self::split_server_share(rest)?;
+        Some((alloc::format!(r"\\{}\{}", server, share), rest))
+    }
+
+    /// Splits `server\share\rest` into its server, share, and the rest, which starts with a separator if not empty.
+    #[cfg(any(windows, feature = "std"))]
+    pub(crate) fn split_server_share(text: &str) -> Option<(&str, &str, &str)> {
+        let mut parts = text.splitn(3, [BS, FS]);
+        let server = parts.next().filter(|p| !p.is_empty())?;
+        let share = parts.next().filter(|p| !p.is_empty())?;
+        Some((server, share, &text[server.len() + 1 + share.len()..]))
+    }
+
+    /// Returns true if the component is a UNC or verbatim prefix, which only ever starts a Windows path.
+    #[cfg(any(windows, feature = "std"))]
+    pub(crate) fn is_unc_prefix(component: &str) -> bool {
+        component.starts_with(r"\\")
+    }
+
+    /// Returns true if the component is a drive, or a UNC or verbatim prefix: the root of an absolute Windows path.
+    #[cfg(any(windows, feature = "std"))]
+    pub(crate) fn is_prefix(component: &str) -> bool {
+        Self::is_drive(component) || Self::is_unc_prefix(component)
+    }
+
+    /// Returns true if the component is a drive such as `C:`.
+    #[cfg(any(windows, feature = "std"))]
+    pub(crate) fn is_drive(component: &str) -> bool {
+        let bytes = component.as_bytes();
+        bytes.len() == 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':'
+    }
+
+    /// Returns true if walking the components from their starting point ever goes above it.
+    #[cfg(feature = "std")]
+    pub(crate) fn climbs_out(components: &[String]) -> bool {
+        Self::escape_depth(components) > 0
+    }
+
+    /// Returns the furthest the components climb above their starting point while walking them in order.
+    #[cfg(feature = "std")]
+    pub(crate) fn escape_depth(components: &[String]) -> usize {
+        let mut depth: isize = 0;
+        let mut lowest: isize = 0;
+        for c in components {
+            if c == UP {
+                depth -= 1;
+                lowest = lowest.min(depth);
+            } else {
+                depth += 1;
+            }
+        }
+        lowest.unsigned_abs()
+    }
+
+    /// Returns the components with each `..` applied to the ones before it.
+    pub(crate) fn resolved(components: &[String], absolute: bool) -> Vec<String> {
+        let mut new_vec: Vec<String> = Vec::new();
+        for c in components {
+            if c != UP {
+                new_vec.push(c.clone());
+            } else {
+                Self::pop_component(&mut new_vec, absolute);
+            }
+        }
+        new_vec
+    }
+
+    /// Applies a `..` to the components. Relative paths keep a `..` that has nothing left to remove, while absolute
+    /// paths stop at their root (and on Windows, their drive or share).
+    pub(crate) fn pop_component(components: &mut Vec<String>, absolute: bool) {
+        match components.last() {
+            None if absolute => {}
+            None => components.push(UP.to_string()),
+            Some(c) if c == UP => components.push(UP.to_string()),
+            #[cfg(windows)]
+            Some(c) if absolute && components.len() == 1 && Self::is_prefix(c) => {}
+            Some(_) => {
+                components.pop();
+            }
+        }
+    }
+
+    /// Appends the second path to this one, applying each of its `..` components as it goes. An empty relative path
+    /// is replaced by the second path.
+    /// Returns how many components were appended if that is all that happened, with the ones before them left as they
+    /// were, or None if earlier components were removed or replaced.
+    pub(crate) fn merge(&mut self, mut second: Self) -> Option<usize> {
+        if second.components.is_empty() {
+            return Some(0);
+        }
+        if self.components.is_empty() && !self.absolute {
+            *self = second;
+            return None;
+        }
+        let mut appended = Some(0);
+        if !self.directory
+            && second.components.first().is_some_and(|c| c == UP)
+            && self.components.last().is_some_and(|c| c != UP)
+        {
+            self.components.pop();
+            appended = None;
+        }
+        let absolute = self.absolute;
+        for c in second.components.drain(..) {
+            if c == UP {
+                Self::pop_component(&mut self.components, absolute);
+                appended = None;
+                continue;
+            }
+            self.components.push(c);
+            appended = appended.map(|n| n + 1);
+        }
+        self.directory = second.directory;
+        appended
+    }
+
+    /// Renders a path with the platform's separators, showing anything that is not valid Unicode as U+FFFD.
+    pub(crate) fn render(components: &[String], absolute: bool, directory: bool) -> String {
+        let text = match (absolute, directory) {
+            #[cfg(not(windows))]
+            (true, true) => ROOT.to_string() + &components.join(SLASH_STR) + SLASH_STR,
+            #[cfg(not(windows))]
+            (true, false) => ROOT.to_string() + &components.join(SLASH_STR),
+
+            #[cfg(windows)]
+            (true, true) => components.join(SLASH_STR) + SLASH_STR,
+            #[cfg(windows)]
+            (true, false) => components.join(SLASH_STR),
+
+            (false, false) => components.join(SLASH_STR),
+            (false, true) => components.join(SLASH_STR) + SLASH_STR,
+        };
+        Self::displayable(text)
+    }
+
+    /// Shows anything kept from a platform string that is not valid Unicode as U+FFFD.
+    pub(crate) fn displayable(text: String) -> String {
+        match raw::display(&text) {
+            Cow::Borrowed(_) => text,
+            Cow::Owned(shown) => shown,
+        }
+    }
+}
+
+impl fmt::Display for PurePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&Self::render(
+            &self.components,
+            self.absolute,
+            self.directory,
+        ))
+    }
+}
+
+impl From<&str> for PurePath {
+    fn from(s: &str) -> Self {
+        Self::parse(&raw::escape_nul(s))
+    }
+}
+
+impl From<String> for PurePath {
+    fn from(s: String) -> Self {
+        Self::from(s.as_str())
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<PurePath> for crate::OsPath {
+    fn from(p: PurePath) -> Self {
+        Self::from_pure(p)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<&crate::OsPath> for PurePath {
+    fn from(p: &crate::OsPath) -> Self {
+        p.to_pure()
+    }
+}
@@ -0,0 +1,161 @@
+//! Lossless conversion between platform strings and the text of path components.
+//!
+//! Components are stored as `String`s. Anything in a platform string that is not valid Unicode, a byte that is not
+//! UTF-8 on Unix or an unpaired surrogate on Windows, is stored as a NUL followed by its value in upper-case hex: two
+//! digits for a byte, four for a surrogate. A NUL already in the input, which no platform allows in a name but a
+//! string can hold, is escaped too, as the value 0, so every NUL in a component starts an escape and text can never
+//! spell one that stands for `.` or `/`. Converting back gives exactly the original. Other targets, such as
+//! WebAssembly, read names lossily.
+
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::String;
+#[cfg(feature = "std")]
+use std::ffi::{OsStr, OsString};
+
+pub(crate) const ESCAPE: char = '\0';
+
+#[cfg(not(windows))]
+pub(crate) const DIGITS: usize = 2;
+#[cfg(windows)]
+pub(crate) const DIGITS: usize = 4;
+
+/// Returns the text with each NUL in it escaped, as `decode()` escapes them.
+pub(crate) fn escape_nul(text: &str) -> Cow<'_, str> {
+    match text.contains(ESCAPE) {
+        true => Cow::Owned(text.replace(ESCAPE, &format!("{}{}", ESCAPE, "0".repeat(DIGITS)))),
+        false => Cow::Borrowed(text),
+    }
+}
+
+/// Returns the text of a platform string, escaping NUL and whatever is not valid Unicode.
+#[cfg(all(unix, feature = "std"))]
+pub(crate) fn decode(os: &OsStr) -> Cow<'_, str> {
+    use std::fmt::Write;
+    use std::os::unix::ffi::OsStrExt;
+
+    if let Some(text) = os.to_str() {
+        return escape_nul(text);
+    }
+    let mut text = String::new();
+    for chunk in os.as_bytes().utf8_chunks() {
+        text.push_str(&escape_nul(chunk.valid()));
+        for b in chunk.invalid() {
+            let _ = write!(text, "{}{:02X}", ESCAPE, b);
+        }
+    }
+    Cow::Owned(text)
+}
+
+/// Returns the text of a platform string, escaping NUL and whatever is not valid Unicode.
+#[cfg(all(windows, feature = "std"))]
+pub(crate) fn decode(os: &OsStr) -> Cow<'_, str> {
+    use std::fmt::Write;
+    use std::os::windows::ffi::OsStrExt;
+
+    if let Some(text) = os.to_str() {
+        return escape_nul(text);
+    }
+    let mut text = String::new();
+    for unit in char::decode_utf16(os.encode_wide()) {
+        match unit {
+            Ok(ESCAPE) => text.push_str(&escape_nul("\0")),
+            Ok(c) => text.push(c),
+            Err(e) => {
+                let _ = write!(text, "{}{:04X}", ESCAPE, e.unpaired_surrogate());
+            }
+        }
+    }
+    Cow::Owned(text)
+}
+
+/// Returns the text of a platform string. Targets that are neither Unix nor Windows, such as WebAssembly, give no
+/// access to the raw contents, so anything that is not valid Unicode is replaced with U+FFFD.
+#[cfg(all(not(any(unix, windows)), feature = "std"))]
+pub(crate) fn decode(os: &OsStr) -> Cow<'_, str> {
+    match os.to_string_lossy() {
+        Cow::Borrowed(text) => escape_nul(text),
+        Cow::Owned(text) => Cow::Owned(escape_nul(&text).into_owned()),
+    }
+}
+
+/// Returns the platform string that `decode()` turned into this text.
+#[cfg(feature = "std")]
+pub(crate) fn encode(text: &str) -> OsString {
+    if !text.contains(ESCAPE) {
+        return OsString::from(text);
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStringExt;
+
+        let mut bytes = Vec::with_capacity(text.len());
+        let mut rest = text;
+        while !rest.is_empty() {
+            if let Some((value, after)) = escaped(rest) {
+                bytes.push(value as u8);
+                rest = after;
+                continue;
+            }
+            let c = rest.chars().next().unwrap_or_default();
+            bytes.extend_from_slice(&rest.as_bytes()[..c.len_utf8()]);
+            rest = &rest[c.len_utf8()..];
+        }
+        OsString::from_vec(bytes)
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::ffi::OsStringExt;
+
+        let mut wide = Vec::with_capacity(text.len());
+        let mut rest = text;
+        while !rest.is_empty() {
+            if let Some((value, after)) = escaped(rest) {
+                wide.push(value);
+                rest = after;
+                continue;
+            }
+            let c = rest.chars().next().unwrap_or_default();
+            wide.extend_from_slice(c.encode_utf16(&mut [0; 2]));
+            rest = &rest[c.len_utf8()..];
+        }
+        OsString::from_wide(&wide)
+    }
+    #[cfg(not(any(unix, windows)))]
+    OsString::from(text.replace(&*escape_nul("\0"), "\0"))
+}
+
+/// Returns the text for display, showing each escape as U+FFFD.
+pub(crate) fn display(text: &str) -> Cow<'_, str> {
+    if !text.contains(ESCAPE) {
+        return Cow::Borrowed(text);
+    }
+    let mut shown = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(i) = rest.find(ESCAPE) {
+        shown.push_str(&rest[..i]);
+        rest = &rest[i..];
+        match escaped(rest) {
+            Some((_, after)) => {
+                shown.push(char::REPLACEMENT_CHARACTER);
+                rest = after;
+            }
+            None => {
+                shown.push(ESCAPE);
+                rest = &rest[1..];
+            }
+        }
+    }
+    shown.push_str(rest);
+    Cow::Owned(shown)
+}
+
+/// Reads an escape from the start of the text, returning its value and the text after it.
+fn escaped(text: &str) -> Option<(u16, &str)> {
+    let digits = text.strip_prefix(ESCAPE)?.get(..DIGITS)?;
+    if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let value = u16::from_str_radix(digits, 16).ok()?;
+    Some((value, &text[1 + DIGITS..]))
+}
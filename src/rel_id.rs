@@ -0,0 +1,160 @@
+//! Identifiers for entries below a project root that read the same on every platform, for storing in asset databases,
+//! manifests and save files.
+
+use crate::validate::validate_name;
+use crate::{OsPath, OsPathError};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// The reasons a `RelId` can be refused.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum RelIdError {
+    /// The id was empty, or the path was the root itself.
+    Empty,
+    /// The id started with `/`.
+    Absolute,
+    /// The id held a `..` component.
+    ParentRef,
+    /// The id held a `\`, an empty or `.` component, or a trailing `/`, so it is not the one spelling of its path.
+    NotCanonical,
+    /// A name in the id could not be created on every platform.
+    InvalidName(OsPathError),
+    /// The path was not below the root.
+    OutsideRoot,
+}
+
+impl fmt::Display for RelIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RelIdError::Empty => f.write_str("id is empty"),
+            RelIdError::Absolute => f.write_str("id starts with a separator"),
+            RelIdError::ParentRef => f.write_str("id contains a `..` component"),
+            RelIdError::NotCanonical => {
+                f.write_str("id is not canonical: it has a backslash, an empty or `.` component, or a trailing slash")
+            }
+            RelIdError::InvalidName(e) => write!(f, "id has an invalid name: {}", e),
+            RelIdError::OutsideRoot => f.write_str("path is not below the root"),
+        }
+    }
+}
+
+impl std::error::Error for RelIdError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RelIdError::InvalidName(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// A path below some root, written one way only: relative, with `/` separators, no `.` or `..` components, no
+/// trailing `/`, and names that `OsPath::validate()` accepts. Two ids are equal exactly when their text is, so an id
+/// can key a database or a manifest, while `to_path()` and `from_path()` move between it and the platform path under
+/// whichever root the project lives in today.
+/// ```rust
+/// use os_path::{OsPath, RelId, RelIdError};
+///
+/// let id: RelId = "textures/grass.png".parse().unwrap();
+/// assert_eq!(id.as_str(), "textures/grass.png");
+///
+/// let root = OsPath::from("game/assets/");
+/// let path = id.to_path(&root);
+/// assert_eq!(path, OsPath::from("game/assets/textures/grass.png"));
+/// assert_eq!(RelId::from_path(&root, &path), Ok(id));
+///
+/// assert_eq!(RelId::new("../secrets.txt"), Err(RelIdError::ParentRef));
+/// assert_eq!(RelId::new("textures\\grass.png"), Err(RelIdError::NotCanonical));
+/// assert_eq!(RelId::from_path(&root, &OsPath::from("game/saves/1.dat")), Err(RelIdError::OutsideRoot));
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct RelId(String);
+
+impl RelId {
+    /// Checks the text and wraps it, refusing any other spelling than the canonical one.
+    pub fn new(id: &str) -> Result<Self, RelIdError> {
+        if id.is_empty() {
+            return Err(RelIdError::Empty);
+        }
+        if id.starts_with('/') {
+            return Err(RelIdError::Absolute);
+        }
+        if id.contains('\\') {
+            return Err(RelIdError::NotCanonical);
+        }
+        for name in id.split('/') {
+            match name {
+                crate::UP => return Err(RelIdError::ParentRef),
+                "" | "." => return Err(RelIdError::NotCanonical),
+                _ => validate_name(name).map_err(RelIdError::InvalidName)?,
+            }
+        }
+        Ok(Self(id.to_string()))
+    }
+
+    /// Returns the id for a path below `root`, after resolving `..` in both. Fails if the path is not below the root,
+    /// is the root itself, or has a name that is not valid on every platform.
+    pub fn from_path(root: &OsPath, path: &OsPath) -> Result<Self, RelIdError> {
+        let (mut root, mut path) = (root.clone(), path.clone());
+        root.resolve();
+        path.resolve();
+        let tail = path
+            .strip_prefix(&root)
+            .map_err(|_| RelIdError::OutsideRoot)?;
+        Self::new(&tail.components.join("/"))
+    }
+
+    /// Returns the platform path the id names below `root`.
+    pub fn to_path(&self, root: &OsPath) -> OsPath {
+        root.join(&self.0)
+    }
+
+    /// Returns the id's text.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the names in the id, from the first to the last.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.0.split('/')
+    }
+}
+
+impl fmt::Display for RelId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for RelId {
+    type Err = RelIdError;
+
+    fn from_str(id: &str) -> Result<Self, RelIdError> {
+        Self::new(id)
+    }
+}
+
+impl AsRef<str> for RelId {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<RelId> for String {
+    fn from(id: RelId) -> Self {
+        id.0
+    }
+}
+
+impl Serialize for RelId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for RelId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = String::deserialize(deserializer)?;
+        Self::new(&id).map_err(de::Error::custom)
+    }
+}
@@ -0,0 +1,241 @@
+//! Planning and executing many renames at once.
+
+use crate::error::IoResultExt;
+use crate::fs::{FsBackend, StdFs};
+use crate::OsPath;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+type Key = (bool, Vec<String>);
+
+fn key(path: &OsPath) -> Key {
+    (path.absolute, path.components.clone())
+}
+
+/// A single step of a `RenamePlan`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct RenameOp {
+    pub from: OsPath,
+    pub to: OsPath,
+}
+
+impl fmt::Display for RenameOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} -> {}", self.from, self.to)
+    }
+}
+
+/// The reasons a `RenamePlan` can fail.
+#[derive(Debug)]
+pub enum RenameError {
+    /// The same source was given more than once.
+    DuplicateSource(OsPath),
+    /// Two sources were given the same target.
+    DuplicateTarget(OsPath),
+    /// A target already exists and is not itself being renamed away.
+    TargetExists(OsPath),
+    /// A rename failed. Every rename completed before it has been undone.
+    Io(RenameOp, io::Error),
+    /// A rename failed, as the `Io` error inside describes, and undoing the renames completed before it failed too.
+    /// The renames listed with their errors could not be undone and are still in effect; the others were undone.
+    Rollback(Box<RenameError>, Vec<(RenameOp, io::Error)>),
+}
+
+impl fmt::Display for RenameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RenameError::DuplicateSource(p) => write!(f, "{} is renamed more than once", p),
+            RenameError::DuplicateTarget(p) => {
+                write!(f, "{} is the target of more than one rename", p)
+            }
+            RenameError::TargetExists(p) => write!(f, "{} already exists", p),
+            RenameError::Io(op, e) => write!(f, "renaming {} failed: {}", op, e),
+            RenameError::Rollback(e, stuck) => {
+                write!(
+                    f,
+                    "{}; {} earlier renames could not be undone",
+                    e,
+                    stuck.len()
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for RenameError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RenameError::Io(_, e) => Some(e),
+            RenameError::Rollback(e, _) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// A set of renames to perform together, such as those produced by a mass-rename tool.
+///
+/// `ops()` orders the renames so that nothing is overwritten, breaking cycles like `a -> b, b -> a` by moving one
+/// file to a temporary sibling first. `execute()` runs them and undoes the completed ones if any rename fails.
+/// ```rust
+/// #[cfg(unix)]
+/// {
+/// use os_path::{OsPath, RenamePlan};
+///
+/// let mut plan = RenamePlan::new();
+/// plan.add("/x/a", "/x/b").add("/x/b", "/x/a").add("/x/c", "/x/d");
+///
+/// let ops: Vec<String> = plan.ops().unwrap().iter().map(|op| op.to_string()).collect();
+/// assert_eq!(ops, vec![
+///     "/x/c -> /x/d",
+///     "/x/a -> /x/a.~rename0",
+///     "/x/b -> /x/a",
+///     "/x/a.~rename0 -> /x/b",
+/// ]);
+/// }
+/// ```
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct RenamePlan {
+    pairs: Vec<(OsPath, OsPath)>,
+}
+
+impl RenamePlan {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a rename of `from` to `to`.
+    pub fn add<P: AsRef<Path>, Q: AsRef<Path>>(&mut self, from: P, to: Q) -> &mut Self {
+        self.pairs
+            .push((OsPath::from(from.as_ref()), OsPath::from(to.as_ref())));
+        self
+    }
+
+    /// Returns the renames in the order they were added.
+    pub fn pairs(&self) -> &[(OsPath, OsPath)] {
+        &self.pairs
+    }
+
+    /// Orders the renames so none overwrites a file that has yet to be moved, without touching the filesystem.
+    /// Renames of a path onto itself are dropped.
+    pub fn ops(&self) -> Result<Vec<RenameOp>, RenameError> {
+        self.ops_avoiding(|_| false)
+    }
+
+    /// Orders the renames as `ops()` does, also passing over temporary names for which `taken` is true.
+    fn ops_avoiding(&self, taken: impl Fn(&OsPath) -> bool) -> Result<Vec<RenameOp>, RenameError> {
+        let mut sources = BTreeSet::new();
+        let mut targets = BTreeSet::new();
+        let mut pending: Vec<(OsPath, OsPath)> = Vec::new();
+        for (from, to) in &self.pairs {
+            if !sources.insert(key(from)) {
+                return Err(RenameError::DuplicateSource(from.clone()));
+            }
+            if !targets.insert(key(to)) {
+                return Err(RenameError::DuplicateTarget(to.clone()));
+            }
+            if key(from) != key(to) {
+                pending.push((from.clone(), to.clone()));
+            }
+        }
+
+        let mut ops = Vec::new();
+        let mut temp_count = 0;
+        while !pending.is_empty() {
+            let waiting: BTreeMap<Key, usize> = pending
+                .iter()
+                .enumerate()
+                .map(|(i, (from, _))| (key(from), i))
+                .collect();
+            let ready: Vec<usize> = (0..pending.len())
+                .filter(|i| !waiting.contains_key(&key(&pending[*i].1)))
+                .collect();
+            if ready.is_empty() {
+                // Every remaining target is still occupied, so the rest are cycles. Park one source on a temporary
+                // name to free its slot.
+                let temp = loop {
+                    let temp = Self::temp_name(&pending[0].0, temp_count);
+                    temp_count += 1;
+                    if !sources.contains(&key(&temp))
+                        && !targets.contains(&key(&temp))
+                        && !taken(&temp)
+                    {
+                        break temp;
+                    }
+                };
+                ops.push(RenameOp {
+                    from: pending[0].0.clone(),
+                    to: temp.clone(),
+                });
+                pending[0].0 = temp;
+                continue;
+            }
+            let mut remaining = Vec::new();
+            for (i, pair) in pending.into_iter().enumerate() {
+                if ready.contains(&i) {
+                    ops.push(RenameOp {
+                        from: pair.0,
+                        to: pair.1,
+                    });
+                } else {
+                    remaining.push(pair);
+                }
+            }
+            pending = remaining;
+        }
+        Ok(ops)
+    }
+
+    /// Checks the plan against the filesystem, failing if a target exists that is not itself renamed away.
+    pub fn check_in(&self, fs: &dyn FsBackend) -> Result<(), RenameError> {
+        let sources: BTreeSet<Key> = self.pairs.iter().map(|(from, _)| key(from)).collect();
+        for (_, to) in &self.pairs {
+            if !sources.contains(&key(to)) && to.exists_in(fs) {
+                return Err(RenameError::TargetExists(to.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks and performs the renames on disk, returning the renames performed. Temporary names are picked among
+    /// those free on disk. If one fails, the renames already done are reversed before the error is returned; any
+    /// that cannot be are reported in a `Rollback` error.
+    pub fn execute(&self) -> Result<Vec<RenameOp>, RenameError> {
+        let ops = self.ops_avoiding(|temp| std::fs::symlink_metadata(temp).is_ok())?;
+        // Temporary names are sources of later renames too, but nothing on disk may already hold them.
+        let sources: BTreeSet<Key> = self.pairs.iter().map(|(from, _)| key(from)).collect();
+        for op in &ops {
+            if !sources.contains(&key(&op.to)) && op.to.exists_in(&StdFs) {
+                return Err(RenameError::TargetExists(op.to.clone()));
+            }
+        }
+        for (i, op) in ops.iter().enumerate() {
+            let renamed = std::fs::rename(&op.from, &op.to);
+            if let Err(e) = renamed.with_paths_context("rename", &op.from, &op.to) {
+                let mut stuck = Vec::new();
+                for done in ops[..i].iter().rev() {
+                    let undone = std::fs::rename(&done.to, &done.from);
+                    if let Err(undo) = undone.with_paths_context("rename", &done.to, &done.from) {
+                        stuck.push((done.clone(), undo));
+                    }
+                }
+                let error = RenameError::Io(op.clone(), e);
+                return Err(match stuck.is_empty() {
+                    true => error,
+                    false => RenameError::Rollback(Box::new(error), stuck),
+                });
+            }
+        }
+        Ok(ops)
+    }
+
+    fn temp_name(path: &OsPath, n: usize) -> OsPath {
+        let mut temp = path.clone();
+        if let Some(last) = temp.components.last_mut() {
+            last.push_str(&format!(".~rename{}", n));
+        }
+        temp.path = OsPath::build_pathbuf(&temp.components, temp.absolute);
+        temp
+    }
+}
@@ -0,0 +1,154 @@
+//! Naming and discovery of rotated log files.
+
+use crate::fs::{FsBackend, StdFs};
+use crate::OsPath;
+use std::io;
+
+/// Log Rotation Methods
+impl OsPath {
+    /// Returns the name of the `n`th rotation of this file, `app.log` becoming `app.log.1`.
+    /// ```rust
+    /// #[cfg(unix)]
+    /// {
+    /// use os_path::OsPath;
+    ///
+    /// let log = OsPath::from("/var/log/app.log");
+    /// assert_eq!(log.rotated(1).to_string(), "/var/log/app.log.1");
+    /// }
+    /// ```
+    pub fn rotated(&self, n: usize) -> OsPath {
+        self.with_rotation_suffix(&n.to_string())
+    }
+
+    /// Returns the name of the `n`th rotation of this file after compression, `app.log` becoming `app.log.2.gz`.
+    /// ```rust
+    /// #[cfg(unix)]
+    /// {
+    /// use os_path::OsPath;
+    ///
+    /// let log = OsPath::from("/var/log/app.log");
+    /// assert_eq!(log.rotated_compressed(2, "gz").to_string(), "/var/log/app.log.2.gz");
+    /// }
+    /// ```
+    pub fn rotated_compressed(&self, n: usize, ext: &str) -> OsPath {
+        self.with_rotation_suffix(&format!("{}.{}", n, ext))
+    }
+
+    /// Returns the name of a dated rotation of this file, `app.log` becoming `app.log.2024-05-01`.
+    /// Use a sortable date format such as ISO 8601 so `rotation_set()` can order the results.
+    /// ```rust
+    /// #[cfg(unix)]
+    /// {
+    /// use os_path::OsPath;
+    ///
+    /// let log = OsPath::from("/var/log/app.log");
+    /// assert_eq!(log.rotated_dated("2024-05-01").to_string(), "/var/log/app.log.2024-05-01");
+    /// }
+    /// ```
+    pub fn rotated_dated(&self, date: &str) -> OsPath {
+        self.with_rotation_suffix(date)
+    }
+
+    /// Finds this file and its existing rotations on disk, newest first. See `rotation_set_in()`.
+    pub fn rotation_set(&self) -> io::Result<Vec<OsPath>> {
+        self.rotation_set_in(&StdFs)
+    }
+
+    /// Finds this file and its existing rotations in the given backend, newest first.
+    ///
+    /// The live file comes first, then numbered rotations in ascending order, then dated rotations from the most
+    /// recent date back. Compressed rotations are ordered as if they were not compressed.
+    /// ```rust
+    /// #[cfg(unix)]
+    /// {
+    /// use os_path::{MemoryFs, OsPath};
+    ///
+    /// let mut fs = MemoryFs::new();
+    /// for name in ["app.log", "app.log.10.gz", "app.log.2.gz", "app.log.1", "app.log.bak", "other.log.1"] {
+    ///     fs.add_file(OsPath::from("/logs/").join(name), "").unwrap();
+    /// }
+    ///
+    /// let set: Vec<String> = OsPath::from("/logs/app.log")
+    ///     .rotation_set_in(&fs)
+    ///     .unwrap()
+    ///     .iter()
+    ///     .map(|p| p.name().unwrap().clone())
+    ///     .collect();
+    /// assert_eq!(set, vec!["app.log", "app.log.1", "app.log.2.gz", "app.log.10.gz"]);
+    /// }
+    /// ```
+    pub fn rotation_set_in(&self, fs: &dyn FsBackend) -> io::Result<Vec<OsPath>> {
+        let name = match self.name() {
+            Some(name) => name.clone(),
+            None => return Ok(Vec::new()),
+        };
+        let dir = self.parent().unwrap_or_else(|| OsPath::from("./"));
+
+        let mut live = Vec::new();
+        let mut numbered = Vec::new();
+        let mut dated = Vec::new();
+        for entry in dir.read_dir_in(fs)? {
+            if entry.is_dir() {
+                continue;
+            }
+            let entry_name = match entry.name() {
+                Some(n) => n.clone(),
+                None => continue,
+            };
+            if entry_name == name {
+                live.push(entry);
+                continue;
+            }
+            let suffix = match entry_name
+                .strip_prefix(&name)
+                .and_then(|s| s.strip_prefix('.'))
+            {
+                Some(suffix) => suffix,
+                None => continue,
+            };
+            let label = Self::strip_compression(suffix);
+            if let Ok(n) = label.parse::<u64>() {
+                numbered.push((n, entry));
+            } else if Self::is_date_label(label) {
+                dated.push((label.to_string(), entry));
+            }
+        }
+        numbered.sort_by_key(|(n, _)| *n);
+        dated.sort_by(|a, b| b.0.cmp(&a.0));
+
+        live.extend(numbered.into_iter().map(|(_, p)| p));
+        live.extend(dated.into_iter().map(|(_, p)| p));
+        Ok(live)
+    }
+
+    fn with_rotation_suffix(&self, suffix: &str) -> OsPath {
+        let mut new_self = self.clone();
+        if let Some(last) = new_self.components.last_mut() {
+            last.push('.');
+            last.push_str(suffix);
+        }
+        new_self.directory = false;
+        new_self.path = Self::build_pathbuf(&new_self.components, new_self.absolute);
+        new_self
+    }
+
+    fn strip_compression(suffix: &str) -> &str {
+        match suffix.rsplit_once('.') {
+            Some((label, ext))
+                if !ext.is_empty()
+                    && ext.chars().all(|c| c.is_ascii_alphanumeric())
+                    && !ext.chars().all(|c| c.is_ascii_digit()) =>
+            {
+                label
+            }
+            _ => suffix,
+        }
+    }
+
+    fn is_date_label(label: &str) -> bool {
+        label.starts_with(|c: char| c.is_ascii_digit())
+            && label
+                .chars()
+                .all(|c| c.is_ascii_digit() || matches!(c, '-' | '_' | 'T' | ':'))
+    }
+}
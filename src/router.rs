@@ -0,0 +1,162 @@
+//! Longest-prefix lookup of values by path.
+
+use crate::OsPath;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const WILDCARD: &str = "*";
+
+#[derive(Clone, Debug)]
+struct Node<V> {
+    value: Option<V>,
+    children: BTreeMap<String, Node<V>>,
+    wildcard: Option<Box<Node<V>>>,
+}
+
+impl<V> Default for Node<V> {
+    fn default() -> Self {
+        Self {
+            value: None,
+            children: BTreeMap::new(),
+            wildcard: None,
+        }
+    }
+}
+
+/// Maps path prefixes to values, looking paths up by their longest matching prefix.
+///
+/// A route matches its own path and everything below it. A `*` component matches any single component, so
+/// `/api/*` matches `/api/v1` and `/api/v1/users`, but not `/api` itself. When routes of equal length match, the
+/// one with fewer wildcards wins. Routes and looked up paths are compared by their components, so `/api` and `api`
+/// are the same route, and looked up paths have their `..` components resolved first.
+/// ```rust
+/// use os_path::{OsPath, PrefixRouter};
+///
+/// let mut router = PrefixRouter::new();
+/// router.insert("/", "root");
+/// router.insert("/static", "files");
+/// router.insert("/api/*", "api");
+/// router.insert("/api/*/admin", "admin");
+///
+/// assert_eq!(router.lookup(&OsPath::from("/static/css/site.css")), Some(&"files"));
+/// assert_eq!(router.lookup(&OsPath::from("/api/v2/users")), Some(&"api"));
+/// assert_eq!(router.lookup(&OsPath::from("/api/v2/admin/keys")), Some(&"admin"));
+/// assert_eq!(router.lookup(&OsPath::from("/api")), Some(&"root"));
+/// assert_eq!(router.lookup(&OsPath::from("/static/../etc/passwd")), Some(&"root"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct PrefixRouter<V> {
+    root: Node<V>,
+    len: usize,
+}
+
+impl<V> Default for PrefixRouter<V> {
+    fn default() -> Self {
+        Self {
+            root: Node::default(),
+            len: 0,
+        }
+    }
+}
+
+impl<V> PrefixRouter<V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a route, returning the value it replaced if the route already existed.
+    pub fn insert<P: AsRef<Path>>(&mut self, prefix: P, value: V) -> Option<V> {
+        let prefix = OsPath::from(prefix.as_ref());
+        let mut node = &mut self.root;
+        for c in prefix.components {
+            node = if c == WILDCARD {
+                node.wildcard.get_or_insert_with(Box::default)
+            } else {
+                node.children.entry(c).or_default()
+            };
+        }
+        let old = node.value.replace(value);
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+
+    /// Removes a route, returning its value.
+    pub fn remove<P: AsRef<Path>>(&mut self, prefix: P) -> Option<V> {
+        let prefix = OsPath::from(prefix.as_ref());
+        let mut node = &mut self.root;
+        for c in prefix.components {
+            node = if c == WILDCARD {
+                node.wildcard.as_deref_mut()?
+            } else {
+                node.children.get_mut(&c)?
+            };
+        }
+        let old = node.value.take();
+        if old.is_some() {
+            self.len -= 1;
+        }
+        old
+    }
+
+    /// Returns the value of the longest route matching the path.
+    pub fn lookup(&self, path: &OsPath) -> Option<&V> {
+        self.lookup_with_rest(path).map(|(value, _)| value)
+    }
+
+    /// Returns the value of the longest route matching the path, along with the part of the path below the route.
+    /// ```rust
+    /// #[cfg(unix)]
+    /// {
+    /// use os_path::{OsPath, PrefixRouter};
+    ///
+    /// let mut router = PrefixRouter::new();
+    /// router.insert("/static", 1);
+    ///
+    /// let (_, rest) = router.lookup_with_rest(&OsPath::from("/static/css/site.css")).unwrap();
+    /// assert_eq!(rest.to_string(), "css/site.css");
+    /// }
+    /// ```
+    pub fn lookup_with_rest(&self, path: &OsPath) -> Option<(&V, OsPath)> {
+        let mut path = path.clone();
+        path.resolve();
+        let (depth, _, value) = Self::search(&self.root, &path.components, 0, 0)?;
+
+        Some((value, path.tail_from(depth)))
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Finds the deepest value below `node`, as `(depth, wildcards used, value)`.
+    fn search<'a>(
+        node: &'a Node<V>,
+        components: &[String],
+        depth: usize,
+        wildcards: usize,
+    ) -> Option<(usize, usize, &'a V)> {
+        let own = node.value.as_ref().map(|v| (depth, wildcards, v));
+        let (literal, wildcard) = match components.split_first() {
+            Some((first, rest)) => (
+                node.children
+                    .get(first)
+                    .and_then(|child| Self::search(child, rest, depth + 1, wildcards)),
+                node.wildcard
+                    .as_deref()
+                    .and_then(|child| Self::search(child, rest, depth + 1, wildcards + 1)),
+            ),
+            None => (None, None),
+        };
+        [own, literal, wildcard]
+            .into_iter()
+            .flatten()
+            .min_by_key(|(depth, wildcards, _)| (Reverse(*depth), *wildcards))
+    }
+}
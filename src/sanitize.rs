@@ -0,0 +1,122 @@
+//! Cleaning up paths from untrusted input, such as upload names and archive entries, into relative paths that can be
+//! joined onto a directory without leaving it.
+
+use crate::name::truncate_str;
+use crate::short_name::ShortName;
+use crate::validate::{is_reserved, ILLEGAL, MAX_NAME_BYTES};
+use crate::{OsPath, PurePath};
+
+/// Sanitizing Methods
+impl OsPath {
+    /// Turns text from an untrusted source into a relative path that stays inside whatever directory it is joined
+    /// onto. Both `/` and `\` separate components on every platform.
+    ///
+    /// - Roots, drives and UNC shares at the start are dropped, so the path is always relative.
+    /// - `..` removes the name before it, but never climbs above the start, and `.` is dropped.
+    /// - Control characters are removed and the characters `<>:"|?*` become `_`.
+    /// - Trailing dots and spaces are trimmed, and names longer than 255 bytes are shortened.
+    /// - A Windows device name such as `CON` or `aux.txt` gets a leading `_`.
+    /// - Names left empty are dropped.
+    ///
+    /// Use `parse_validated()` instead to refuse such input rather than clean it up.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// assert_eq!(OsPath::from_untrusted("../../etc/passwd"), OsPath::from("etc/passwd"));
+    /// assert_eq!(OsPath::from_untrusted("/uploads/a/../b.txt"), OsPath::from("uploads/b.txt"));
+    /// assert_eq!(OsPath::from_untrusted("C:\\Windows\\con.txt"), OsPath::from("Windows/_con.txt"));
+    /// assert_eq!(OsPath::from_untrusted("what?\u{7}.pdf "), OsPath::from("what_.pdf"));
+    /// assert_eq!(OsPath::from_untrusted("/../"), OsPath::from(""));
+    /// ```
+    pub fn from_untrusted(input: &str) -> Self {
+        let mut parts = input.split(['/', '\\']).peekable();
+        // A root, or the two separators a UNC share starts with, leave empty parts that are dropped like any other.
+        if parts.peek().is_some_and(|p| PurePath::is_drive(p)) {
+            parts.next();
+        }
+        Self::sanitized(parts, input.ends_with(['/', '\\']))
+    }
+
+    /// Cleans up the path as `from_untrusted()` does with text, so the result is relative and stays inside any
+    /// directory it is joined onto.
+    /// ```rust
+    /// #[cfg(unix)]
+    /// {
+    /// use os_path::OsPath;
+    ///
+    /// let path = OsPath::from("/var/../../tmp/lpt1/");
+    /// assert_eq!(path.sanitize().to_string(), "tmp/_lpt1/");
+    /// }
+    /// ```
+    pub fn sanitize(&self) -> Self {
+        let skip = usize::from(
+            self.absolute
+                && self
+                    .components
+                    .first()
+                    .is_some_and(|c| PurePath::is_prefix(c)),
+        );
+        let parts = self.components[skip..].iter().map(String::as_str);
+        Self::sanitized(parts, self.directory)
+    }
+
+    /// Joins untrusted text onto the path after cleaning it up with `from_untrusted()`. The result is always the path
+    /// itself or below it, going by the names alone; symbolic links inside the path are not looked at.
+    /// ```rust
+    /// #[cfg(unix)]
+    /// {
+    /// use os_path::OsPath;
+    ///
+    /// let uploads = OsPath::from("/srv/uploads/");
+    /// assert_eq!(uploads.join_untrusted("../../etc/shadow").to_string(), "/srv/uploads/etc/shadow");
+    /// assert_eq!(uploads.join_untrusted("/photos/cat.jpg").to_string(), "/srv/uploads/photos/cat.jpg");
+    /// }
+    /// ```
+    pub fn join_untrusted(&self, input: &str) -> Self {
+        self.join_os(&Self::from_untrusted(input))
+    }
+
+    fn sanitized<'a>(parts: impl Iterator<Item = &'a str>, directory: bool) -> Self {
+        let mut components: Vec<String> = Vec::new();
+        for part in parts {
+            if part == crate::UP {
+                components.pop();
+                continue;
+            }
+            if let Some(name) = sanitize_name(part) {
+                components.push(name.into());
+            }
+        }
+        OsPath {
+            path: Self::build_pathbuf(&components, false),
+            directory: directory && !components.is_empty(),
+            components,
+            absolute: false,
+            style: Default::default(),
+        }
+    }
+}
+
+/// Returns the name with everything Windows or a Unix filesystem would refuse or misread taken out, or None if
+/// nothing usable is left.
+pub(crate) fn sanitize_name(part: &str) -> Option<ShortName> {
+    let mut name = ShortName::empty();
+    for c in part.chars().filter(|c| !c.is_control()) {
+        name.push(if ILLEGAL.contains(c) { '_' } else { c });
+    }
+    name.truncate(name.as_str().trim_end_matches(['.', ' ']).len());
+    if name.as_str().is_empty() {
+        return None;
+    }
+    if is_reserved(name.as_str()) {
+        let mut prefixed = ShortName::empty();
+        prefixed.push('_');
+        prefixed.push_str(name.as_str());
+        name = prefixed;
+    }
+    if name.as_str().len() > MAX_NAME_BYTES {
+        name.truncate(truncate_str(name.as_str(), MAX_NAME_BYTES).len());
+        name.truncate(name.as_str().trim_end_matches(['.', ' ']).len());
+    }
+    Some(name)
+}
@@ -0,0 +1,158 @@
+//! Joining untrusted paths onto a directory with every `..` and symbolic link checked, so the result cannot leave it.
+
+use crate::error::IoResultExt;
+use crate::fs::LinkCycle;
+use crate::{OsPath, PurePath};
+use std::collections::VecDeque;
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+/// The most symbolic links followed in one join, as Linux allows in one lookup.
+const MAX_LINKS: usize = 40;
+
+/// The error `secure_join()` reports, inside an `io::Error` of kind `PermissionDenied`, for a path that leads out of
+/// the base directory.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PathEscape {
+    base: OsPath,
+    path: OsPath,
+}
+
+impl PathEscape {
+    /// Returns the base directory, as the filesystem names it.
+    pub fn base(&self) -> &OsPath {
+        &self.base
+    }
+
+    /// Returns the path that was asked for: the untrusted path joined onto the base, before any resolving.
+    pub fn path(&self) -> &OsPath {
+        &self.path
+    }
+
+    /// Returns the escape an I/O error reports, or None if it is some other error.
+    pub fn from_io(error: &io::Error) -> Option<&Self> {
+        error.get_ref()?.downcast_ref()
+    }
+
+    fn into_io(base: OsPath, path: OsPath) -> io::Error {
+        io::Error::new(io::ErrorKind::PermissionDenied, Self { base, path })
+    }
+}
+
+impl fmt::Display for PathEscape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` leads outside of `{}`", self.path, self.base)
+    }
+}
+
+impl std::error::Error for PathEscape {}
+
+/// Contained Join Methods
+impl OsPath {
+    /// Joins an untrusted relative path onto this directory on the real filesystem, failing rather than leaving it.
+    ///
+    /// The directory is canonicalized first, then the untrusted path is walked one component at a time. A `..` that
+    /// would climb above the directory, or a symbolic link whose target lies outside it, fails the join with a
+    /// `PathEscape` error; links that stay inside are followed, relative or absolute. Components that do not exist
+    /// yet are appended as they are, so the result can name a file about to be created. A leading root is ignored as
+    /// `join()` ignores it, and a Windows drive or share fails the join. A name holding a NUL character, which no
+    /// filesystem allows, fails with `InvalidInput`.
+    ///
+    /// Unlike `join()`, which resolves `..` by the names alone and so happily climbs out of the directory, the result
+    /// is always the canonical directory or a path below it. The check is only as good as the moment it runs: a link
+    /// created inside the directory afterwards is not caught.
+    /// ```rust
+    /// use os_path::{OsPath, PathEscape};
+    ///
+    /// let root = OsPath::from("src/");
+    /// let lib = root.secure_join("./missing/../lib.rs").unwrap();
+    /// assert!(lib.is_absolute());
+    /// assert_eq!(lib, OsPath::from("src/lib.rs").canonicalize().unwrap());
+    /// assert!(root.secure_join("not/yet/made.rs").is_ok());
+    ///
+    /// let error = root.secure_join("../Cargo.toml").unwrap_err();
+    /// assert_eq!(error.kind(), std::io::ErrorKind::PermissionDenied);
+    /// assert!(PathEscape::from_io(&error).is_some());
+    /// assert!(!root.is_secure_join("../../etc/passwd"));
+    /// ```
+    pub fn secure_join<P: AsRef<Path>>(&self, untrusted: P) -> io::Result<OsPath> {
+        let base = self.canonicalize()?;
+        let input = OsPath::from(untrusted.as_ref());
+        let escape = || PathEscape::into_io(base.clone(), base.join(untrusted.as_ref()));
+        if input.absolute
+            && input
+                .components
+                .first()
+                .is_some_and(|c| PurePath::is_prefix(c))
+        {
+            return Err(escape());
+        }
+
+        let depth = base.components.len();
+        let mut components = base.components.clone();
+        let mut path = base.path.clone();
+        let mut pending: VecDeque<String> = input.components.iter().cloned().collect();
+        let mut links = 0;
+        while let Some(name) = pending.pop_front() {
+            if name == "." {
+                continue;
+            }
+            if name == crate::UP {
+                if components.len() == depth {
+                    return Err(escape());
+                }
+                components.pop();
+                path.pop();
+                continue;
+            }
+            let encoded = crate::raw::encode(&name);
+            if encoded.to_string_lossy().contains('\0') {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("`{}` has a NUL character in a name", input),
+                ));
+            }
+            let next = path.join(encoded);
+            if !std::fs::symlink_metadata(&next).is_ok_and(|m| m.file_type().is_symlink()) {
+                components.push(name);
+                path = next;
+                continue;
+            }
+
+            let link = OsPath::from(next.as_path());
+            links += 1;
+            let target = std::fs::read_link(&next).with_path_context("read link", &link)?;
+            let target = OsPath::from(target);
+            if links > MAX_LINKS {
+                return Err(LinkCycle::into_io(link, target));
+            }
+            let rest = match target.absolute {
+                true if target.is_within(&base) => {
+                    components.truncate(depth);
+                    path = base.path.clone();
+                    &target.components[depth..]
+                }
+                true => return Err(escape()),
+                false => &target.components[..],
+            };
+            for part in rest.iter().rev() {
+                pending.push_front(part.clone());
+            }
+        }
+
+        Ok(OsPath {
+            directory: input.directory || path.is_dir(),
+            path: Self::build_pathbuf(&components, true),
+            components,
+            absolute: true,
+            style: self.style,
+        })
+    }
+
+    /// Returns true if `secure_join()` would succeed: the untrusted path stays inside this directory on the real
+    /// filesystem, and nothing along the way fails to be read.
+    pub fn is_secure_join<P: AsRef<Path>>(&self, untrusted: P) -> bool {
+        self.secure_join(untrusted).is_ok()
+    }
+}
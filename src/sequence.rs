@@ -0,0 +1,190 @@
+//! Numbered file sequences, such as the frames of a render, handled as a pattern and a set of frame numbers.
+//!
+//! In a pattern, a run of `#` in the file name stands for the frame number, zero-padded to at least as many digits
+//! as there are `#`s: `frame_####.png` names `frame_0001.png` through `frame_9999.png`, then `frame_10000.png`.
+
+use crate::OsPath;
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+
+/// A group of numbered files that differ only in their frame number, found by `detect_sequence()`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Sequence {
+    pattern: OsPath,
+    frames: Vec<u32>,
+}
+
+impl Sequence {
+    /// Returns the pattern naming every file of the sequence, such as `renders/frame_####.png`.
+    pub fn pattern(&self) -> &OsPath {
+        &self.pattern
+    }
+
+    /// Returns the frame numbers found, in ascending order.
+    pub fn frames(&self) -> &[u32] {
+        &self.frames
+    }
+
+    /// Returns the range from the first frame to the last.
+    pub fn range(&self) -> RangeInclusive<u32> {
+        self.frames[0]..=self.frames[self.frames.len() - 1]
+    }
+
+    /// Returns the frames inside the range that were not found.
+    pub fn missing(&self) -> Vec<u32> {
+        let mut found = self.frames.iter().peekable();
+        self.range()
+            .filter(|n| {
+                while found.next_if(|f| *f < n).is_some() {}
+                found.peek() != Some(&n)
+            })
+            .collect()
+    }
+
+    /// Returns the paths of the frames found.
+    pub fn paths(&self) -> Vec<OsPath> {
+        self.pattern.expand_sequence(self.frames.iter().copied())
+    }
+}
+
+/// Groups the numbered files among the paths into sequences. Files belong together when they are in the same
+/// directory and their names differ only in the last run of digits, and that run is padded the same way. Groups of a
+/// single file are not sequences and are left out. Sequences are ordered by pattern.
+/// ```rust
+/// use os_path::{detect_sequence, OsPath};
+///
+/// let paths: Vec<OsPath> = ["shot/frame_0001.png", "shot/frame_0002.png", "shot/frame_0004.png", "shot/notes.txt"]
+///     .into_iter()
+///     .map(OsPath::from)
+///     .collect();
+///
+/// let found = detect_sequence(&paths);
+/// assert_eq!(found.len(), 1);
+/// assert_eq!(found[0].pattern(), &OsPath::from("shot/frame_####.png"));
+/// assert_eq!(found[0].range(), 1..=4);
+/// assert_eq!(found[0].missing(), vec![3]);
+/// ```
+pub fn detect_sequence<'a, I: IntoIterator<Item = &'a OsPath>>(paths: I) -> Vec<Sequence> {
+    // Files are first grouped by everything but the number, then split by padding.
+    type Key = (bool, Vec<String>, String, String);
+    let mut buckets: BTreeMap<Key, (&OsPath, Vec<Number>)> = BTreeMap::new();
+    for path in paths {
+        if path.directory {
+            continue;
+        }
+        let Some(name) = path.name() else { continue };
+        let Some((start, end)) = last_digit_run(name) else {
+            continue;
+        };
+        let Ok(frame) = name[start..end].parse::<u32>() else {
+            continue;
+        };
+        let padded = end - start > 1 && name.as_bytes()[start] == b'0';
+        let key = (
+            path.absolute,
+            path.components[..path.components.len() - 1].to_vec(),
+            name[..start].to_string(),
+            name[end..].to_string(),
+        );
+        let bucket = buckets.entry(key).or_insert_with(|| (path, Vec::new()));
+        bucket.1.push(Number {
+            frame,
+            len: end - start,
+            padded,
+        });
+    }
+
+    let mut sequences = Vec::new();
+    for ((_, _, prefix, suffix), (example, numbers)) in buckets {
+        let mut widths: Vec<usize> = numbers.iter().filter(|n| n.padded).map(|n| n.len).collect();
+        widths.sort_unstable();
+        widths.dedup();
+        let mut groups: BTreeMap<usize, Vec<u32>> = BTreeMap::new();
+        for Number { frame, len, padded } in numbers {
+            // Unpadded numbers at least as long as a padding width belong with it, as padding only ever adds digits.
+            let width = if padded {
+                len
+            } else {
+                widths
+                    .iter()
+                    .rev()
+                    .find(|w| **w <= len)
+                    .copied()
+                    .unwrap_or(1)
+            };
+            groups.entry(width).or_default().push(frame);
+        }
+        for (width, mut frames) in groups {
+            frames.sort_unstable();
+            frames.dedup();
+            if frames.len() < 2 {
+                continue;
+            }
+            let name = format!("{}{}{}", prefix, "#".repeat(width), suffix);
+            sequences.push(Sequence {
+                pattern: example.with_name(name),
+                frames,
+            });
+        }
+    }
+    sequences.sort_by_key(|s| s.pattern.to_string());
+    sequences
+}
+
+/// A frame number as written in a file name.
+struct Number {
+    frame: u32,
+    len: usize,
+    padded: bool,
+}
+
+/// Returns the byte range of the last run of ASCII digits in the text.
+fn last_digit_run(text: &str) -> Option<(usize, usize)> {
+    let bytes = text.as_bytes();
+    let end = bytes.iter().rposition(u8::is_ascii_digit)? + 1;
+    let start = bytes[..end]
+        .iter()
+        .rposition(|b| !b.is_ascii_digit())
+        .map_or(0, |i| i + 1);
+    Some((start, end))
+}
+
+/// Sequence Methods
+impl OsPath {
+    /// Returns the path of each frame, replacing the last run of `#` in the name with the frame number padded to its
+    /// length. A name without `#` gives no paths.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// let frames = OsPath::from("renders/frame_####.png").expand_sequence(1..=120);
+    /// assert_eq!(frames.len(), 120);
+    /// assert_eq!(frames[0], OsPath::from("renders/frame_0001.png"));
+    /// assert_eq!(frames[119], OsPath::from("renders/frame_0120.png"));
+    ///
+    /// let takes = OsPath::from("take#.wav").expand_sequence([9, 10]);
+    /// assert_eq!(takes, vec![OsPath::from("take9.wav"), OsPath::from("take10.wav")]);
+    /// ```
+    pub fn expand_sequence<I: IntoIterator<Item = u32>>(&self, frames: I) -> Vec<OsPath> {
+        let name = match self.name() {
+            Some(name) if !self.directory => name,
+            _ => return Vec::new(),
+        };
+        let Some(end) = name.rfind('#').map(|i| i + 1) else {
+            return Vec::new();
+        };
+        let start = name[..end].trim_end_matches('#').len();
+        let width = end - start;
+        frames
+            .into_iter()
+            .map(|frame| {
+                self.with_name(format!(
+                    "{}{:0width$}{}",
+                    &name[..start],
+                    frame,
+                    &name[end..],
+                    width = width
+                ))
+            })
+            .collect()
+    }
+}
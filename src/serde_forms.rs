@@ -0,0 +1,175 @@
+//! Other shapes for a path in serialized data than the single string `OsPath` itself uses.
+
+use crate::{OsPath, PurePath};
+use serde::de::{self, SeqAccess, Visitor};
+use serde::ser::SerializeSeq;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// Serializes a path as a sequence of components, and deserializes one from a sequence, a string, or bytes.
+///
+/// An absolute Unix path starts with a `/` element, a Windows path starts with its drive or UNC share, and a
+/// directory ends with an empty element, so `/srv/www/` is written as `["/", "srv", "www", ""]`.
+/// ```rust
+/// use os_path::{AsComponents, OsPath};
+///
+/// let path = AsComponents(OsPath::from("assets/icons/"));
+/// assert_eq!(serde_json::to_string(&path).unwrap(), r#"["assets","icons",""]"#);
+///
+/// let read: AsComponents = serde_json::from_str(r#"["assets","logo.png"]"#).unwrap();
+/// assert_eq!(read.0, OsPath::from("assets/logo.png"));
+/// let read: AsComponents = serde_json::from_str(r#""assets/logo.png""#).unwrap();
+/// assert_eq!(read.0, OsPath::from("assets/logo.png"));
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct AsComponents(pub OsPath);
+
+/// Serializes a path as a string, as `OsPath` does, but deserializes one from a string, a sequence of components as
+/// `AsComponents` writes them, or bytes, such as the platform bytes of a Unix path.
+///
+/// `OsPath` only asks the format for a string, which keeps it working with formats that are not self-describing.
+/// `Lenient` needs a self-describing format, such as JSON, TOML or MessagePack.
+/// ```rust
+/// use os_path::{Lenient, OsPath};
+///
+/// let read: Vec<Lenient> = serde_json::from_str(r#"["docs/intro.md", ["docs", "guide.md"]]"#).unwrap();
+/// assert_eq!(read[0].0, OsPath::from("docs/intro.md"));
+/// assert_eq!(read[1].0, OsPath::from("docs/guide.md"));
+/// assert_eq!(serde_json::to_string(&read[1]).unwrap(), serde_json::to_string(&read[1].0).unwrap());
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Lenient(pub OsPath);
+
+impl From<OsPath> for AsComponents {
+    fn from(path: OsPath) -> Self {
+        Self(path)
+    }
+}
+
+impl From<AsComponents> for OsPath {
+    fn from(path: AsComponents) -> Self {
+        path.0
+    }
+}
+
+impl From<OsPath> for Lenient {
+    fn from(path: OsPath) -> Self {
+        Self(path)
+    }
+}
+
+impl From<Lenient> for OsPath {
+    fn from(path: Lenient) -> Self {
+        path.0
+    }
+}
+
+impl Serialize for AsComponents {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let path = &self.0;
+        let root = path.absolute
+            && !path
+                .components
+                .first()
+                .is_some_and(|c| PurePath::is_prefix(c));
+        let trailing = path.directory && !path.components.is_empty();
+        let len = path.components.len() + usize::from(root) + usize::from(trailing);
+        let mut seq = serializer.serialize_seq(Some(len))?;
+        if root {
+            seq.serialize_element("/")?;
+        }
+        for component in &path.components {
+            seq.serialize_element(&PurePath::displayable(component.clone()))?;
+        }
+        if trailing {
+            seq.serialize_element("")?;
+        }
+        seq.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for AsComponents {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(AnyFormVisitor).map(Self)
+    }
+}
+
+impl Serialize for Lenient {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Lenient {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(AnyFormVisitor).map(Self)
+    }
+}
+
+/// Reads a path from whichever form the data holds.
+struct AnyFormVisitor;
+
+impl<'de> Visitor<'de> for AnyFormVisitor {
+    type Value = OsPath;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a path as a string, bytes, or a sequence of components")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<OsPath, E> {
+        Ok(OsPath::from(value))
+    }
+
+    fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<OsPath, E> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStrExt;
+            Ok(OsPath::build_self(std::ffi::OsStr::from_bytes(value)))
+        }
+        #[cfg(not(unix))]
+        match std::str::from_utf8(value) {
+            Ok(text) => Ok(OsPath::from(text)),
+            Err(_) => Err(E::invalid_value(de::Unexpected::Bytes(value), &self)),
+        }
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<OsPath, A::Error> {
+        let mut names: Vec<String> = Vec::new();
+        while let Some(name) = seq.next_element()? {
+            names.push(name);
+        }
+        let root = names.first().is_some_and(|n| n == "/");
+        if root {
+            names.remove(0);
+        }
+        let trailing = names.last().is_some_and(String::is_empty);
+        if trailing {
+            names.pop();
+        }
+        let prefix =
+            cfg!(windows) && !root && names.first().is_some_and(|n| PurePath::is_prefix(n));
+        for (i, name) in names.iter().enumerate() {
+            let bad_char = match i == 0 && prefix {
+                true => name.contains(['/', '\0']),
+                false => name.contains(['/', '\\', '\0']),
+            };
+            if name.is_empty() || bad_char {
+                return Err(de::Error::invalid_value(
+                    de::Unexpected::Str(name),
+                    &"a path component",
+                ));
+            }
+        }
+        let (components, absolute) = match prefix {
+            true => (PurePath::split_drive(names).0, true),
+            false => (names, root),
+        };
+        Ok(OsPath {
+            path: OsPath::build_pathbuf(&components, absolute),
+            directory: trailing || (absolute && components.len() <= usize::from(prefix)),
+            components,
+            absolute,
+            style: Default::default(),
+        })
+    }
+}
@@ -0,0 +1,217 @@
+//! Sets of paths and the differences between them.
+
+use crate::{CaseSensitivity, OsPath};
+use std::collections::btree_map;
+use std::collections::BTreeMap;
+
+/// A set of paths, ordered and deduplicated by their portable `/` separated form.
+/// ```rust
+/// use os_path::{OsPath, OsPathSet};
+///
+/// let mut set = OsPathSet::new();
+/// assert!(set.insert("assets/a.png"));
+/// assert!(!set.insert("assets\\a.png"));
+/// assert!(set.contains("assets/a.png"));
+/// assert_eq!(set.len(), 1);
+/// ```
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct OsPathSet {
+    paths: BTreeMap<String, OsPath>,
+    case: CaseSensitivity,
+}
+
+impl OsPathSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty set that tells paths apart by case or not, as given. A case-insensitive set keeps the first
+    /// spelling of each path inserted.
+    /// ```rust
+    /// use os_path::{CaseSensitivity, OsPath, OsPathSet};
+    ///
+    /// let mut set = OsPathSet::with_case(CaseSensitivity::Insensitive);
+    /// assert!(set.insert("Docs/Readme.md"));
+    /// assert!(!set.insert("docs/README.md"));
+    /// assert!(set.contains("DOCS/readme.MD"));
+    /// assert_eq!(set.iter().next(), Some(&OsPath::from("Docs/Readme.md")));
+    /// ```
+    pub fn with_case(case: CaseSensitivity) -> Self {
+        Self {
+            paths: BTreeMap::new(),
+            case,
+        }
+    }
+
+    /// Returns whether the set tells paths apart by case.
+    pub fn case(&self) -> CaseSensitivity {
+        self.case
+    }
+
+    /// Adds a path, returning false if an equal path was already present.
+    pub fn insert<P: Into<OsPath>>(&mut self, path: P) -> bool {
+        let path = path.into();
+        let key = self.key(&path);
+        match self.paths.entry(key) {
+            btree_map::Entry::Occupied(_) => false,
+            btree_map::Entry::Vacant(e) => {
+                e.insert(path);
+                true
+            }
+        }
+    }
+
+    /// Removes a path, returning it if it was present.
+    pub fn remove<P: Into<OsPath>>(&mut self, path: P) -> Option<OsPath> {
+        self.paths.remove(&self.key(&path.into()))
+    }
+
+    /// Returns true if the set contains the path.
+    pub fn contains<P: Into<OsPath>>(&self, path: P) -> bool {
+        self.paths.contains_key(&self.key(&path.into()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    /// Iterates the paths in order of their portable form.
+    pub fn iter(&self) -> impl Iterator<Item = &OsPath> {
+        self.paths.values()
+    }
+
+    fn key(&self, path: &OsPath) -> String {
+        match self.case {
+            CaseSensitivity::Sensitive => path.build_portable_key(),
+            CaseSensitivity::Insensitive => path.normalize_case().build_portable_key(),
+        }
+    }
+}
+
+impl<P: Into<OsPath>> FromIterator<P> for OsPathSet {
+    fn from_iter<I: IntoIterator<Item = P>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<P: Into<OsPath>> Extend<P> for OsPathSet {
+    fn extend<I: IntoIterator<Item = P>>(&mut self, iter: I) {
+        for path in iter {
+            self.insert(path);
+        }
+    }
+}
+
+impl IntoIterator for OsPathSet {
+    type Item = OsPath;
+    type IntoIter = btree_map::IntoValues<String, OsPath>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.paths.into_values()
+    }
+}
+
+/// Controls how paths are matched up by `diff_sets_with()`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct DiffOptions {
+    ignore_case: bool,
+    #[cfg(feature = "unicode")]
+    unicode_nfc: bool,
+}
+
+impl DiffOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Treats paths differing only in letter case as the same path.
+    pub fn ignore_case(mut self, yes: bool) -> Self {
+        self.ignore_case = yes;
+        self
+    }
+
+    /// Treats paths that are equal after Unicode NFC normalization as the same path, so names written by macOS
+    /// (decomposed) and by other systems (composed) match up.
+    #[cfg(feature = "unicode")]
+    pub fn unicode_nfc(mut self, yes: bool) -> Self {
+        self.unicode_nfc = yes;
+        self
+    }
+
+    fn key(&self, path: &OsPath) -> String {
+        let mut key = path.build_portable_key();
+        #[cfg(feature = "unicode")]
+        if self.unicode_nfc {
+            use unicode_normalization::UnicodeNormalization;
+            key = key.nfc().collect();
+        }
+        if self.ignore_case {
+            key = key.to_lowercase();
+        }
+        key
+    }
+}
+
+/// The result of `diff_sets()`. Each list is ordered by the paths' portable form.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct SetDiff {
+    /// Paths only in the new set.
+    pub added: Vec<OsPath>,
+    /// Paths only in the old set.
+    pub removed: Vec<OsPath>,
+    /// Paths in both sets, as spelled in the new set.
+    pub common: Vec<OsPath>,
+}
+
+/// Compares two path sets exactly. See `diff_sets_with()`.
+/// ```rust
+/// use os_path::{diff_sets, OsPathSet};
+///
+/// let old: OsPathSet = ["a.txt", "b.txt"].into_iter().collect();
+/// let new: OsPathSet = ["b.txt", "c.txt"].into_iter().collect();
+///
+/// let diff = diff_sets(&old, &new);
+/// assert_eq!(diff.added[0].to_string(), "c.txt");
+/// assert_eq!(diff.removed[0].to_string(), "a.txt");
+/// assert_eq!(diff.common[0].to_string(), "b.txt");
+/// ```
+pub fn diff_sets(old: &OsPathSet, new: &OsPathSet) -> SetDiff {
+    diff_sets_with(old, new, DiffOptions::new())
+}
+
+/// Compares two path sets, matching paths up according to `options`.
+/// ```rust
+/// use os_path::{diff_sets_with, DiffOptions, OsPathSet};
+///
+/// let old: OsPathSet = ["Docs/README.md"].into_iter().collect();
+/// let new: OsPathSet = ["docs/readme.md"].into_iter().collect();
+///
+/// let diff = diff_sets_with(&old, &new, DiffOptions::new().ignore_case(true));
+/// assert!(diff.added.is_empty() && diff.removed.is_empty());
+/// assert_eq!(diff.common[0].to_string(), "docs/readme.md");
+/// ```
+pub fn diff_sets_with(old: &OsPathSet, new: &OsPathSet, options: DiffOptions) -> SetDiff {
+    let old: BTreeMap<String, &OsPath> = old.iter().map(|p| (options.key(p), p)).collect();
+    let new: BTreeMap<String, &OsPath> = new.iter().map(|p| (options.key(p), p)).collect();
+
+    let mut diff = SetDiff::default();
+    for (key, path) in &new {
+        if old.contains_key(key) {
+            diff.common.push((*path).clone());
+        } else {
+            diff.added.push((*path).clone());
+        }
+    }
+    for (key, path) in &old {
+        if !new.contains_key(key) {
+            diff.removed.push((*path).clone());
+        }
+    }
+    diff
+}
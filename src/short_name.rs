@@ -0,0 +1,255 @@
+//! Single names kept inline, so that checking or cleaning up a file name on its own does not allocate.
+
+use crate::raw::{DIGITS, ESCAPE};
+use crate::sanitize::sanitize_name;
+use crate::{OsPath, BS, FS};
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+/// The most bytes a name keeps inline. With its length, an inline name takes no more room than a `String`.
+const INLINE: usize = 22;
+
+/// A single path component that keeps names of up to 22 bytes inline rather than on the heap, since many paths are
+/// just a file name. Making one from a short name, checking its extension and `sanitize()` never allocate; a longer
+/// name works the same but is kept in a `String`.
+///
+/// The text is the name as an `OsPath` stores its components, so anything that is not valid Unicode stays escaped
+/// and is shown as U+FFFD.
+/// ```rust
+/// use os_path::{OsPath, ShortName};
+///
+/// let name = ShortName::new("Report.PDF").unwrap();
+/// assert!(name.is_inline());
+/// assert!(name.has_extension(".pdf"));
+/// assert_eq!(name.file_stem(), "Report");
+/// assert_eq!(name.with_extension("txt"), "Report.txt");
+///
+/// assert_eq!(ShortName::new("aux.txt ").unwrap().sanitize().unwrap(), "_aux.txt");
+/// assert_eq!(ShortName::new("what?.txt").unwrap().sanitize().unwrap(), "what_.txt");
+/// assert_eq!(ShortName::new("a/b"), None);
+///
+/// let path = OsPath::from("docs/notes.md");
+/// assert_eq!(path.short_name().unwrap(), "notes.md");
+/// assert_eq!(OsPath::from(ShortName::new("notes.md").unwrap()), OsPath::from("notes.md"));
+/// ```
+#[derive(Clone)]
+pub struct ShortName(Repr);
+
+#[derive(Clone)]
+enum Repr {
+    Inline(u8, [u8; INLINE]),
+    Heap(String),
+}
+
+impl ShortName {
+    /// Wraps a single name. Returns `None` when the text is empty or holds a separator, so it is not one component.
+    pub fn new(name: &str) -> Option<Self> {
+        if name.is_empty() || name.contains([BS, FS]) {
+            return None;
+        }
+        let mut short = Self::empty();
+        short.push_escaped(name);
+        Some(short)
+    }
+
+    /// Returns the name's text, escapes and all.
+    pub fn as_str(&self) -> &str {
+        match &self.0 {
+            Repr::Inline(len, bytes) => {
+                std::str::from_utf8(&bytes[..*len as usize]).expect("inline names are whole UTF-8")
+            }
+            Repr::Heap(name) => name,
+        }
+    }
+
+    /// Returns true if the name is short enough to be kept inline.
+    pub fn is_inline(&self) -> bool {
+        matches!(self.0, Repr::Inline(..))
+    }
+
+    /// Returns the final extension, as `OsPath::extension()` does for a file. Dot-files such as `.bashrc` and names
+    /// without a dot have none.
+    pub fn extension(&self) -> Option<&str> {
+        self.split_extension().map(|(_, ext)| ext)
+    }
+
+    /// Returns the name without its final extension.
+    pub fn file_stem(&self) -> &str {
+        match self.split_extension() {
+            Some((stem, _)) => stem,
+            None => self.as_str(),
+        }
+    }
+
+    /// Returns true if the final extension is the given one, compared without the leading dot and ignoring case as
+    /// `PathFilter::extensions()` does.
+    pub fn has_extension(&self, extension: &str) -> bool {
+        let wanted = extension.strip_prefix('.').unwrap_or(extension);
+        self.extension().is_some_and(|ext| {
+            ext.chars()
+                .flat_map(char::to_lowercase)
+                .eq(wanted.chars().flat_map(char::to_lowercase))
+        })
+    }
+
+    /// Returns the name with its final extension replaced or added, as `OsPath::set_extension()` does. Only
+    /// allocates when the result no longer fits inline.
+    pub fn with_extension(&self, extension: &str) -> Self {
+        let extension = extension.strip_prefix('.').unwrap_or(extension);
+        let mut name = Self::empty();
+        name.push_str(self.file_stem());
+        if !extension.is_empty() {
+            name.push_str(".");
+            name.push_escaped(extension);
+        }
+        name
+    }
+
+    /// Cleans up the name as `OsPath::from_untrusted()` does each of its names, or returns `None` if nothing usable
+    /// is left. Only allocates when the result no longer fits inline.
+    pub fn sanitize(&self) -> Option<Self> {
+        sanitize_name(self.as_str())
+    }
+
+    pub(crate) fn empty() -> Self {
+        Self(Repr::Inline(0, [0; INLINE]))
+    }
+
+    pub(crate) fn push_str(&mut self, text: &str) {
+        if let Repr::Inline(len, bytes) = &mut self.0 {
+            let start = *len as usize;
+            if let Some(room) = bytes.get_mut(start..start + text.len()) {
+                room.copy_from_slice(text.as_bytes());
+                *len += text.len() as u8;
+                return;
+            }
+            let mut spilled = String::with_capacity(start + text.len());
+            spilled.push_str(self.as_str());
+            self.0 = Repr::Heap(spilled);
+        }
+        if let Repr::Heap(name) = &mut self.0 {
+            name.push_str(text);
+        }
+    }
+
+    pub(crate) fn push(&mut self, c: char) {
+        self.push_str(c.encode_utf8(&mut [0; 4]));
+    }
+
+    /// Shortens the name to `new_len` bytes, which must fall on a character boundary.
+    pub(crate) fn truncate(&mut self, new_len: usize) {
+        assert!(self.as_str().is_char_boundary(new_len));
+        match &mut self.0 {
+            Repr::Inline(len, _) => *len = (*len).min(new_len as u8),
+            Repr::Heap(name) => name.truncate(new_len),
+        }
+    }
+
+    /// Appends text, escaping each NUL in it as `OsPath` does when it parses a name.
+    fn push_escaped(&mut self, text: &str) {
+        for (i, part) in text.split('\0').enumerate() {
+            if i > 0 {
+                self.push(ESCAPE);
+                (0..DIGITS).for_each(|_| self.push('0'));
+            }
+            self.push_str(part);
+        }
+    }
+
+    fn split_extension(&self) -> Option<(&str, &str)> {
+        let (stem, ext) = self.as_str().rsplit_once('.')?;
+        if stem.is_empty() {
+            return None;
+        }
+        Some((stem, ext))
+    }
+}
+
+impl fmt::Display for ShortName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&crate::raw::display(self.as_str()))
+    }
+}
+
+impl fmt::Debug for ShortName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl PartialEq for ShortName {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for ShortName {}
+
+impl PartialEq<str> for ShortName {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for ShortName {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialOrd for ShortName {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ShortName {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl Hash for ShortName {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+
+impl AsRef<str> for ShortName {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl From<ShortName> for String {
+    fn from(name: ShortName) -> Self {
+        match name.0 {
+            Repr::Inline(..) => name.as_str().to_string(),
+            Repr::Heap(name) => name,
+        }
+    }
+}
+
+impl From<ShortName> for OsPath {
+    fn from(name: ShortName) -> Self {
+        let components = vec![String::from(name)];
+        OsPath {
+            path: OsPath::build_pathbuf(&components, false),
+            components,
+            absolute: false,
+            directory: false,
+            style: Default::default(),
+        }
+    }
+}
+
+/// Short Name Methods
+impl OsPath {
+    /// Returns the last component as a `ShortName`, which does not allocate when the name is short.
+    pub fn short_name(&self) -> Option<ShortName> {
+        let mut name = ShortName::empty();
+        name.push_str(self.name()?);
+        Some(name)
+    }
+}
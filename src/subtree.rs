@@ -0,0 +1,98 @@
+//! Selecting whole subtrees by path prefix, in the manner of a sparse checkout.
+
+use crate::OsPath;
+use std::path::Path;
+
+/// Selects the subtrees below include prefixes, minus the subtrees below exclude prefixes, with paths given relative
+/// to the root of a traversal.
+///
+/// The rule with the longest prefix of a path decides whether it is selected, so an exclude can cut a subtree out of
+/// an include and a deeper include can bring part of it back. With no includes at all, everything not excluded is
+/// selected. Unlike a `PathFilter`, a selector knows which directories can never hold a selected path, so
+/// `Walk::select()` skips them without reading them.
+/// ```rust
+/// use os_path::{OsPath, SubtreeSelector};
+///
+/// let selector = SubtreeSelector::new()
+///     .include("src/")
+///     .include("docs/api/")
+///     .exclude("src/generated/")
+///     .include("src/generated/keep/");
+///
+/// assert!(selector.selects(&OsPath::from("src/lib.rs")));
+/// assert!(!selector.selects(&OsPath::from("src/generated/api.rs")));
+/// assert!(selector.selects(&OsPath::from("src/generated/keep/mod.rs")));
+/// assert!(!selector.selects(&OsPath::from("docs/intro.md")));
+///
+/// assert!(selector.may_select_below(&OsPath::from("docs/")));
+/// assert!(selector.may_select_below(&OsPath::from("src/generated/")));
+/// assert!(!selector.may_select_below(&OsPath::from("target/")));
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct SubtreeSelector {
+    rules: Vec<(Vec<String>, bool)>,
+}
+
+impl SubtreeSelector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads a selector from lines of prefixes, one per line. A line starting with `!` is an exclude; blank lines
+    /// and lines starting with `#` are ignored.
+    /// ```rust
+    /// use os_path::{OsPath, SubtreeSelector};
+    ///
+    /// let selector = SubtreeSelector::parse("# services\nservices/billing/\n!services/billing/fixtures/\n");
+    /// assert!(selector.selects(&OsPath::from("services/billing/main.go")));
+    /// assert!(!selector.selects(&OsPath::from("services/billing/fixtures/big.json")));
+    /// ```
+    pub fn parse(text: &str) -> Self {
+        text.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .fold(Self::new(), |selector, line| match line.strip_prefix('!') {
+                Some(prefix) => selector.exclude(prefix),
+                None => selector.include(line),
+            })
+    }
+
+    /// Selects everything below the prefix, or the prefix itself if it names a file.
+    pub fn include<P: AsRef<Path>>(mut self, prefix: P) -> Self {
+        self.push(prefix.as_ref(), true);
+        self
+    }
+
+    /// Leaves out everything below the prefix, except below longer include prefixes.
+    pub fn exclude<P: AsRef<Path>>(mut self, prefix: P) -> Self {
+        self.push(prefix.as_ref(), false);
+        self
+    }
+
+    /// Returns true if the path, relative to the traversal root, is selected.
+    pub fn selects(&self, path: &OsPath) -> bool {
+        let rule = self
+            .rules
+            .iter()
+            .filter(|(prefix, _)| path.components.starts_with(prefix))
+            .max_by_key(|(prefix, include)| (prefix.len(), !include));
+        match rule {
+            Some((_, include)) => *include,
+            None => !self.rules.iter().any(|(_, include)| *include),
+        }
+    }
+
+    /// Returns true if anything below the directory, relative to the traversal root, could be selected.
+    pub fn may_select_below(&self, dir: &OsPath) -> bool {
+        self.selects(dir)
+            || self.rules.iter().any(|(prefix, include)| {
+                *include
+                    && prefix.len() > dir.components.len()
+                    && prefix.starts_with(&dir.components)
+            })
+    }
+
+    fn push(&mut self, prefix: &Path, include: bool) {
+        self.rules.push((OsPath::from(prefix).components, include));
+    }
+}
@@ -0,0 +1,141 @@
+//! Reproducible made-up paths, for benchmarking path handling without a real directory tree.
+
+use crate::OsPath;
+
+const SYLLABLES: [&str; 16] = [
+    "src", "lib", "doc", "app", "core", "test", "data", "img", "util", "net", "io", "ui", "cache",
+    "build", "log", "cfg",
+];
+const EXTENSIONS: [&str; 8] = ["rs", "txt", "json", "png", "md", "toml", "log", "tar.gz"];
+
+/// Generates the same paths every time for the same settings, so benchmark runs compare like with like.
+///
+/// Names are built from short syllables with a number and an extension, such as `core_17.json`, and are spread over
+/// up to `max_depth()` directories. A share of components can be `..`, to give `resolve()` something to do.
+/// ```rust
+/// use os_path::SyntheticPaths;
+///
+/// let paths = SyntheticPaths::new(7).max_depth(4).absolute(true).paths(100);
+/// assert_eq!(paths.len(), 100);
+/// assert!(paths.iter().all(|p| p.is_absolute() && p.is_file()));
+/// assert_eq!(paths, SyntheticPaths::new(7).max_depth(4).absolute(true).paths(100));
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SyntheticPaths {
+    seed: u64,
+    max_depth: usize,
+    absolute: bool,
+    parent_refs: u8,
+    dirs: u8,
+}
+
+impl SyntheticPaths {
+    /// Creates a generator of relative file paths up to 6 components deep, with no `..`.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            max_depth: 6,
+            absolute: false,
+            parent_refs: 0,
+            dirs: 0,
+        }
+    }
+
+    /// Sets the most components a path has, at least 1.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth.max(1);
+        self
+    }
+
+    /// Makes the paths absolute, or relative.
+    pub fn absolute(mut self, yes: bool) -> Self {
+        self.absolute = yes;
+        self
+    }
+
+    /// Sets the percentage of directory components written as `..`, capped at 100.
+    pub fn parent_refs(mut self, percent: u8) -> Self {
+        self.parent_refs = percent.min(100);
+        self
+    }
+
+    /// Sets the percentage of paths that name a directory rather than a file, capped at 100.
+    pub fn dirs(mut self, percent: u8) -> Self {
+        self.dirs = percent.min(100);
+        self
+    }
+
+    /// Returns the paths as text, with `/` separators.
+    pub fn strings(&self, count: usize) -> Vec<String> {
+        let mut rng = Rng::new(self.seed);
+        (0..count).map(|_| self.string(&mut rng)).collect()
+    }
+
+    /// Returns the paths.
+    pub fn paths(&self, count: usize) -> Vec<OsPath> {
+        self.strings(count).into_iter().map(OsPath::from).collect()
+    }
+
+    /// Returns single file names, such as those read from one directory.
+    pub fn names(&self, count: usize) -> Vec<String> {
+        let mut rng = Rng::new(self.seed);
+        (0..count).map(|_| file_name(&mut rng)).collect()
+    }
+
+    fn string(&self, rng: &mut Rng) -> String {
+        let depth = 1 + rng.below(self.max_depth as u64) as usize;
+        let mut text = String::new();
+        if self.absolute {
+            text.push_str(if cfg!(windows) { "C:/" } else { "/" });
+        }
+        for _ in 1..depth {
+            match rng.below(100) < u64::from(self.parent_refs) {
+                true => text.push_str(crate::UP),
+                false => text.push_str(&dir_name(rng)),
+            }
+            text.push('/');
+        }
+        match rng.below(100) < u64::from(self.dirs) {
+            true => {
+                text.push_str(&dir_name(rng));
+                text.push('/');
+            }
+            false => text.push_str(&file_name(rng)),
+        }
+        text
+    }
+}
+
+fn dir_name(rng: &mut Rng) -> String {
+    let first = SYLLABLES[rng.below(16) as usize];
+    match rng.below(3) {
+        0 => format!("{}_{}", first, SYLLABLES[rng.below(16) as usize]),
+        _ => first.to_string(),
+    }
+}
+
+fn file_name(rng: &mut Rng) -> String {
+    format!(
+        "{}_{}.{}",
+        SYLLABLES[rng.below(16) as usize],
+        rng.below(1000),
+        EXTENSIONS[rng.below(8) as usize]
+    )
+}
+
+/// A small xorshift generator, so the output does not depend on another crate's algorithm staying the same.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        // Xorshift stays at zero once there, so start from a nonzero state.
+        Self(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0 % n
+    }
+}
@@ -0,0 +1,197 @@
+//! A journal of filesystem changes that can be undone as a unit.
+
+use crate::error::IoResultExt;
+use crate::OsPath;
+use std::io;
+use std::path::Path;
+
+/// A change recorded by an `FsTransaction`, along with what is needed to undo it.
+#[derive(Clone, PartialEq, Debug)]
+pub enum JournalEntry {
+    /// A file or directory was created where nothing existed.
+    Created(OsPath),
+    /// A file or directory was moved.
+    Moved { from: OsPath, to: OsPath },
+    /// An existing file or directory was set aside before being replaced or deleted.
+    SetAside { path: OsPath, backup: OsPath },
+}
+
+/// Groups create, copy, move, and delete operations so they can be rolled back together if a later step fails,
+/// as installers and updaters need.
+///
+/// Nothing is destroyed until `commit()`: deleted and overwritten entries are renamed to hidden siblings, and only
+/// removed once the transaction commits. Rolling back undoes every recorded change in reverse order on a best
+/// effort basis. A transaction that is dropped without being committed is rolled back.
+/// ```rust
+/// use os_path::{FsTransaction, OsPath};
+///
+/// let dir = OsPath::from(std::env::temp_dir()).join("os_path_txn_doc/");
+/// std::fs::create_dir_all(&dir).unwrap();
+/// std::fs::write(dir.join("config.toml"), "old").unwrap();
+///
+/// let mut txn = FsTransaction::new();
+/// txn.write(dir.join("config.toml"), "new").unwrap();
+/// txn.write(dir.join("extra.toml"), "added").unwrap();
+/// txn.rollback().unwrap();
+///
+/// assert_eq!(std::fs::read_to_string(dir.join("config.toml")).unwrap(), "old");
+/// assert!(!dir.join("extra.toml").exists());
+/// # std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+#[derive(Debug, Default)]
+pub struct FsTransaction {
+    journal: Vec<JournalEntry>,
+    finished: bool,
+}
+
+impl FsTransaction {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the changes recorded so far, oldest first.
+    pub fn journal(&self) -> &[JournalEntry] {
+        &self.journal
+    }
+
+    /// Writes a file, setting any existing file aside first.
+    pub fn write<P: AsRef<Path>, C: AsRef<[u8]>>(
+        &mut self,
+        path: P,
+        contents: C,
+    ) -> io::Result<()> {
+        let path = OsPath::from(path.as_ref());
+        self.set_aside(&path)?;
+        std::fs::write(&path, contents).with_path_context("write", &path)?;
+        self.journal.push(JournalEntry::Created(path));
+        Ok(())
+    }
+
+    /// Creates a directory and any missing parents, recording each directory created.
+    pub fn create_dir_all<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        let path = OsPath::from(path.as_ref());
+        let mut missing = Vec::new();
+        let mut current = Some(path);
+        while let Some(dir) = current {
+            if dir.components.is_empty() || dir.exists() {
+                break;
+            }
+            current = dir.parent();
+            missing.push(dir);
+        }
+        for dir in missing.into_iter().rev() {
+            std::fs::create_dir(&dir).with_path_context("create directory", &dir)?;
+            self.journal.push(JournalEntry::Created(dir));
+        }
+        Ok(())
+    }
+
+    /// Copies a file, setting any existing target aside first.
+    pub fn copy<P: AsRef<Path>, Q: AsRef<Path>>(&mut self, from: P, to: Q) -> io::Result<u64> {
+        let from = OsPath::from(from.as_ref());
+        let to = OsPath::from(to.as_ref());
+        self.set_aside(&to)?;
+        let bytes = std::fs::copy(&from, &to).with_paths_context("copy", &from, &to)?;
+        self.journal.push(JournalEntry::Created(to));
+        Ok(bytes)
+    }
+
+    /// Moves a file or directory, setting any existing target aside first.
+    pub fn rename<P: AsRef<Path>, Q: AsRef<Path>>(&mut self, from: P, to: Q) -> io::Result<()> {
+        let from = OsPath::from(from.as_ref());
+        let to = OsPath::from(to.as_ref());
+        self.set_aside(&to)?;
+        std::fs::rename(&from, &to).with_paths_context("rename", &from, &to)?;
+        self.journal.push(JournalEntry::Moved { from, to });
+        Ok(())
+    }
+
+    /// Deletes a file or directory. It is only set aside until the transaction commits.
+    pub fn remove<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        let path = OsPath::from(path.as_ref());
+        if !self.set_aside(&path)? {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} does not exist", path),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Keeps every change and deletes whatever was set aside.
+    pub fn commit(mut self) -> io::Result<()> {
+        self.finished = true;
+        let mut result = Ok(());
+        for entry in std::mem::take(&mut self.journal) {
+            if let JournalEntry::SetAside { backup, .. } = entry {
+                if let Err(e) = Self::remove_any(&backup) {
+                    result = Err(e);
+                }
+            }
+        }
+        result
+    }
+
+    /// Undoes every change in reverse order, continuing past failures and returning the first one.
+    pub fn rollback(mut self) -> io::Result<()> {
+        self.finished = true;
+        self.undo()
+    }
+
+    fn undo(&mut self) -> io::Result<()> {
+        let mut result = Ok(());
+        while let Some(entry) = self.journal.pop() {
+            let undone = match entry {
+                JournalEntry::Created(path) => Self::remove_any(&path),
+                JournalEntry::Moved { from, to } => std::fs::rename(&to, &from),
+                JournalEntry::SetAside { path, backup } => std::fs::rename(&backup, &path),
+            };
+            if let (Err(e), Ok(())) = (undone, &result) {
+                result = Err(e);
+            }
+        }
+        result
+    }
+
+    /// Renames an existing entry to a hidden sibling, returning false if there was nothing to set aside.
+    fn set_aside(&mut self, path: &OsPath) -> io::Result<bool> {
+        if std::fs::symlink_metadata(path).is_err() {
+            return Ok(false);
+        }
+        let name = path.name().cloned().unwrap_or_default();
+        let mut n = 0;
+        let backup = loop {
+            let mut backup = path.clone();
+            if let Some(last) = backup.components.last_mut() {
+                *last = format!(".{}.~txn{}", name, n);
+            }
+            backup.path = OsPath::build_pathbuf(&backup.components, backup.absolute);
+            if std::fs::symlink_metadata(&backup).is_err() {
+                break backup;
+            }
+            n += 1;
+        };
+        std::fs::rename(path, &backup).with_paths_context("set aside", path, &backup)?;
+        self.journal.push(JournalEntry::SetAside {
+            path: path.clone(),
+            backup,
+        });
+        Ok(true)
+    }
+
+    fn remove_any(path: &OsPath) -> io::Result<()> {
+        if std::fs::symlink_metadata(path)?.is_dir() {
+            std::fs::remove_dir_all(path)
+        } else {
+            std::fs::remove_file(path)
+        }
+    }
+}
+
+impl Drop for FsTransaction {
+    fn drop(&mut self) {
+        if !self.finished {
+            let _ = self.undo();
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! Rendering directory trees as text.
+
+use crate::filter::PathFilter;
+use crate::fs::{FsBackend, StdFs};
+use crate::OsPath;
+use std::io;
+
+/// Controls the output of `render_tree()`.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct TreeOptions {
+    max_depth: Option<usize>,
+    filter: Option<PathFilter>,
+    ascii: bool,
+}
+
+impl TreeOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stops descending after `depth` levels below the root.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Only shows entries matching the filter, relative to the root. Directories the filter does not rule out
+    /// entirely are shown so their matching contents have somewhere to hang.
+    pub fn filter(mut self, filter: PathFilter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Draws the branches with plain ASCII instead of box-drawing characters.
+    pub fn ascii(mut self, yes: bool) -> Self {
+        self.ascii = yes;
+        self
+    }
+
+    fn glyphs(&self) -> [&'static str; 4] {
+        if self.ascii {
+            ["|-- ", "`-- ", "|   ", "    "]
+        } else {
+            ["├── ", "└── ", "│   ", "    "]
+        }
+    }
+}
+
+/// Renders the directory on disk in the style of the `tree` command. See `render_tree_in()`.
+pub fn render_tree(root: &OsPath, options: &TreeOptions) -> io::Result<String> {
+    render_tree_in(root, options, &StdFs)
+}
+
+/// Renders the directory in the given backend in the style of the `tree` command, entries sorted by name and
+/// directories marked with a trailing separator.
+/// ```rust
+/// #[cfg(unix)]
+/// {
+/// use os_path::{render_tree_in, MemoryFs, OsPath, TreeOptions};
+///
+/// let mut fs = MemoryFs::new();
+/// fs.add_file("/site/index.html", "").unwrap()
+///     .add_file("/site/css/main.css", "").unwrap()
+///     .add_file("/site/css/print.css", "").unwrap();
+///
+/// let text = render_tree_in(&OsPath::from("/site/"), &TreeOptions::new(), &fs).unwrap();
+/// assert_eq!(text, "\
+/// /site/
+/// ├── css/
+/// │   ├── main.css
+/// │   └── print.css
+/// └── index.html
+/// ");
+/// }
+/// ```
+pub fn render_tree_in(
+    root: &OsPath,
+    options: &TreeOptions,
+    fs: &dyn FsBackend,
+) -> io::Result<String> {
+    let mut out = root.to_string();
+    out.push('\n');
+    render_dir(root, root.components.len(), 0, "", options, fs, &mut out)?;
+    Ok(out)
+}
+
+fn render_dir(
+    dir: &OsPath,
+    root_len: usize,
+    depth: usize,
+    indent: &str,
+    options: &TreeOptions,
+    fs: &dyn FsBackend,
+    out: &mut String,
+) -> io::Result<()> {
+    if options.max_depth.is_some_and(|max| depth >= max) {
+        return Ok(());
+    }
+    let mut entries = Vec::new();
+    for entry in fs.read_dir(dir)? {
+        let relative = entry.tail_from(root_len);
+        let (shown, descend) = match &options.filter {
+            Some(filter) if entry.is_dir() => (
+                filter.admits(&relative, depth + 1),
+                filter.descends_at(&relative, depth + 1),
+            ),
+            Some(filter) => (filter.matches_at(&relative, depth + 1), false),
+            None => (true, entry.is_dir()),
+        };
+        if shown {
+            entries.push((entry, descend));
+        }
+    }
+
+    let [branch, last_branch, pipe, blank] = options.glyphs();
+    for (i, (entry, descend)) in entries.iter().enumerate() {
+        let last = i + 1 == entries.len();
+        out.push_str(indent);
+        out.push_str(if last { last_branch } else { branch });
+        if let Some(name) = entry.name() {
+            out.push_str(name);
+        }
+        if entry.is_dir() {
+            out.push(crate::SLASH);
+        }
+        out.push('\n');
+        if *descend {
+            let indent = format!("{}{}", indent, if last { blank } else { pipe });
+            render_dir(entry, root_len, depth + 1, &indent, options, fs, out)?;
+        }
+    }
+    Ok(())
+}
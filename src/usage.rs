@@ -0,0 +1,150 @@
+//! Disk usage totals by directory.
+
+use crate::fs::{FsBackend, StdFs};
+use crate::OsPath;
+use std::collections::BTreeMap;
+use std::io;
+use std::sync::Mutex;
+
+type Totals = BTreeMap<Vec<String>, u64>;
+
+/// Usage Methods
+impl OsPath {
+    /// Totals the size of the files below this directory on disk. See `usage_report_in()`.
+    pub fn usage_report(&self, depth: usize) -> io::Result<Vec<(OsPath, u64)>> {
+        self.usage_report_in(depth, &StdFs)
+    }
+
+    /// Like `usage_report()`, but reads the subdirectories of this directory on separate threads.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// let src = OsPath::from("src/");
+    /// assert_eq!(src.usage_report_parallel(1).unwrap(), src.usage_report(1).unwrap());
+    /// ```
+    pub fn usage_report_parallel(&self, depth: usize) -> io::Result<Vec<(OsPath, u64)>> {
+        let mut totals = Totals::new();
+        totals.insert(Vec::new(), 0);
+        let mut queue = Vec::new();
+        for entry in StdFs.read_dir(self)? {
+            if entry.is_dir() {
+                queue.push(entry);
+            } else {
+                self.tally(&entry, depth, &StdFs, &mut totals)?;
+            }
+        }
+
+        let workers = std::thread::available_parallelism()
+            .map_or(1, |n| n.get())
+            .min(queue.len());
+        let queue = Mutex::new(queue);
+        let results = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..workers)
+                .map(|_| {
+                    scope.spawn(|| -> io::Result<Totals> {
+                        let mut totals = Totals::new();
+                        loop {
+                            let next = queue.lock().unwrap_or_else(|e| e.into_inner()).pop();
+                            let Some(dir) = next else {
+                                return Ok(totals);
+                            };
+                            self.tally(&dir, depth, &StdFs, &mut totals)?;
+                            for entry in dir.walk() {
+                                self.tally(&entry?, depth, &StdFs, &mut totals)?;
+                            }
+                        }
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|h| h.join().unwrap_or_else(|e| std::panic::resume_unwind(e)))
+                .collect::<Vec<_>>()
+        });
+        for result in results {
+            for (key, bytes) in result? {
+                *totals.entry(key).or_insert(0) += bytes;
+            }
+        }
+        Ok(self.usage_entries(totals))
+    }
+
+    /// Totals the size of the files below this directory in the given backend, like `du -d depth`.
+    ///
+    /// Returns this directory and every subdirectory at most `depth` levels below it, each with the bytes of all
+    /// the files anywhere beneath it, in walk order.
+    /// ```rust
+    /// #[cfg(unix)]
+    /// {
+    /// use os_path::{MemoryFs, OsPath};
+    ///
+    /// let mut fs = MemoryFs::new();
+    /// fs.add_file("/cache/a.bin", vec![0; 100]).unwrap()
+    ///     .add_file("/cache/img/b.png", vec![0; 2000]).unwrap()
+    ///     .add_file("/cache/img/thumbs/c.png", vec![0; 30]).unwrap()
+    ///     .add_dir("/cache/empty/").unwrap();
+    ///
+    /// let report: Vec<(String, u64)> = OsPath::from("/cache/")
+    ///     .usage_report_in(1, &fs)
+    ///     .unwrap()
+    ///     .into_iter()
+    ///     .map(|(p, n)| (p.to_string(), n))
+    ///     .collect();
+    /// assert_eq!(report, vec![
+    ///     ("/cache/".to_string(), 2130),
+    ///     ("/cache/empty/".to_string(), 0),
+    ///     ("/cache/img/".to_string(), 2030),
+    /// ]);
+    /// }
+    /// ```
+    pub fn usage_report_in(
+        &self,
+        depth: usize,
+        fs: &dyn FsBackend,
+    ) -> io::Result<Vec<(OsPath, u64)>> {
+        let mut totals = Totals::new();
+        totals.insert(Vec::new(), 0);
+        for entry in self.walk_in(fs) {
+            self.tally(&entry?, depth, fs, &mut totals)?;
+        }
+        Ok(self.usage_entries(totals))
+    }
+
+    /// Adds a walked entry to the totals of the directories above it, down to `depth` levels below this one.
+    fn tally(
+        &self,
+        entry: &OsPath,
+        depth: usize,
+        fs: &dyn FsBackend,
+        totals: &mut Totals,
+    ) -> io::Result<()> {
+        let relative = &entry.components[self.components.len()..];
+        if entry.is_dir() {
+            if relative.len() <= depth {
+                totals.entry(relative.to_vec()).or_insert(0);
+            }
+            return Ok(());
+        }
+        let meta = fs.metadata(entry)?;
+        if !meta.is_file() {
+            return Ok(());
+        }
+        for n in 0..relative.len().min(depth + 1) {
+            *totals.entry(relative[..n].to_vec()).or_insert(0) += meta.len();
+        }
+        Ok(())
+    }
+
+    fn usage_entries(&self, totals: Totals) -> Vec<(OsPath, u64)> {
+        totals
+            .into_iter()
+            .map(|(relative, bytes)| {
+                let mut dir = self.clone();
+                dir.components.extend(relative);
+                dir.directory = true;
+                dir.path = OsPath::build_pathbuf(&dir.components, dir.absolute);
+                (dir, bytes)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,177 @@
+//! Checking that a path could be created on any platform, for input that has to be refused rather than cleaned up.
+
+use crate::{OsPath, ParseError, PurePath};
+use std::ffi::{OsStr, OsString};
+use std::fmt;
+
+/// The longest name, in bytes of UTF-8, that common filesystems allow.
+pub(crate) const MAX_NAME_BYTES: usize = 255;
+
+/// Names Windows reserves for devices, with or without an extension.
+const RESERVED: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Characters Windows does not allow in names, besides control characters.
+pub(crate) const ILLEGAL: &str = "<>:\"|?*";
+
+/// The reasons `OsPath::validate()`, `parse_validated()` and the `TryFrom` conversions refuse a path.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum OsPathError {
+    /// The input was empty.
+    Empty,
+    /// The input contained a NUL byte at this offset.
+    NulByte(usize),
+    /// The input was not valid UTF-8 from this offset.
+    InvalidUtf8(usize),
+    /// The name is reserved for a device on Windows, such as `CON` or `lpt1.txt`.
+    ReservedName(String),
+    /// The name holds a character Windows does not allow in names: a control character or one of `<>:"|?*`.
+    IllegalCharacter(String, char),
+    /// The name ends with a dot or a space, which Windows silently drops.
+    TrailingDotOrSpace(String),
+    /// The name is longer than 255 bytes.
+    NameTooLong(String),
+}
+
+impl fmt::Display for OsPathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OsPathError::Empty => f.write_str("path is empty"),
+            OsPathError::NulByte(i) => write!(f, "path contains a NUL byte at offset {}", i),
+            OsPathError::InvalidUtf8(i) => write!(f, "path is not valid UTF-8 at offset {}", i),
+            OsPathError::ReservedName(name) => write!(f, "'{}' is a reserved device name", name),
+            OsPathError::IllegalCharacter(name, c) => {
+                write!(
+                    f,
+                    "'{}' contains the illegal character {:?}",
+                    name.escape_debug(),
+                    c
+                )
+            }
+            OsPathError::TrailingDotOrSpace(name) => {
+                write!(f, "'{}' ends with a dot or a space", name)
+            }
+            OsPathError::NameTooLong(name) => {
+                write!(f, "'{}' is longer than {} bytes", name, MAX_NAME_BYTES)
+            }
+        }
+    }
+}
+
+impl std::error::Error for OsPathError {}
+
+impl From<ParseError> for OsPathError {
+    fn from(e: ParseError) -> Self {
+        match e {
+            ParseError::Empty => OsPathError::Empty,
+            ParseError::NulByte(i) => OsPathError::NulByte(i),
+            ParseError::InvalidUtf8(i) => OsPathError::InvalidUtf8(i),
+        }
+    }
+}
+
+/// Validation Methods
+impl OsPath {
+    /// Checks that every name in the path could be created on Windows, macOS and Linux alike: none is a reserved
+    /// device name, holds a character Windows refuses, ends with a dot or a space, or is longer than 255 bytes. A
+    /// Windows drive or share at the start of an absolute path, and `.` and `..` components, are accepted.
+    /// ```rust
+    /// use os_path::{OsPath, OsPathError};
+    ///
+    /// assert_eq!(OsPath::from("uploads/report.pdf").validate(), Ok(()));
+    /// assert_eq!(
+    ///     OsPath::from("uploads/con.txt").validate(),
+    ///     Err(OsPathError::ReservedName("con.txt".to_string()))
+    /// );
+    /// assert_eq!(
+    ///     OsPath::from("uploads/what?.pdf").validate(),
+    ///     Err(OsPathError::IllegalCharacter("what?.pdf".to_string(), '?'))
+    /// );
+    /// ```
+    pub fn validate(&self) -> Result<(), OsPathError> {
+        for (i, name) in self.components.iter().enumerate() {
+            if i == 0 && self.absolute && PurePath::is_prefix(name) {
+                continue;
+            }
+            if name == "." || name == crate::UP {
+                continue;
+            }
+            validate_name(name)?;
+        }
+        Ok(())
+    }
+
+    /// Parses a path as `try_parse()` does, then checks it with `validate()`. This is the checked counterpart of
+    /// `From<&str>`, which accepts everything and so rules out a `TryFrom<&str>` of its own.
+    /// ```rust
+    /// use os_path::{OsPath, OsPathError};
+    ///
+    /// assert!(OsPath::parse_validated("photos/2024/beach.jpg").is_ok());
+    /// assert_eq!(OsPath::parse_validated("a\0b"), Err(OsPathError::NulByte(1)));
+    /// assert_eq!(
+    ///     OsPath::parse_validated("notes. "),
+    ///     Err(OsPathError::TrailingDotOrSpace("notes. ".to_string()))
+    /// );
+    /// ```
+    pub fn parse_validated(input: &str) -> Result<Self, OsPathError> {
+        let path = Self::try_parse(input)?;
+        path.validate()?;
+        Ok(path)
+    }
+}
+
+/// Checks a single name as `validate()` checks each of a path's names.
+pub(crate) fn validate_name(name: &str) -> Result<(), OsPathError> {
+    if name.len() > MAX_NAME_BYTES {
+        return Err(OsPathError::NameTooLong(name.to_string()));
+    }
+    if let Some(c) = name
+        .chars()
+        .find(|c| c.is_ascii_control() || ILLEGAL.contains(*c))
+    {
+        return Err(OsPathError::IllegalCharacter(name.to_string(), c));
+    }
+    if name.ends_with(['.', ' ']) {
+        return Err(OsPathError::TrailingDotOrSpace(name.to_string()));
+    }
+    if is_reserved(name) {
+        return Err(OsPathError::ReservedName(name.to_string()));
+    }
+    Ok(())
+}
+
+/// Returns true if the name is a Windows device name, alone or followed by an extension.
+pub(crate) fn is_reserved(name: &str) -> bool {
+    let device = name.split('.').next().unwrap_or_default().trim_end();
+    RESERVED.iter().any(|r| r.eq_ignore_ascii_case(device))
+}
+
+/// Converts a platform string as `parse_validated()` does, refusing one that is not valid UTF-8 rather than escaping
+/// it as `From` does.
+/// ```rust
+/// use os_path::{OsPath, OsPathError};
+/// use std::ffi::OsString;
+///
+/// assert!(OsPath::try_from(OsString::from("docs/guide.md")).is_ok());
+/// assert_eq!(OsPath::try_from(OsString::from("docs/aux")), Err(OsPathError::ReservedName("aux".to_string())));
+/// ```
+impl TryFrom<&OsStr> for OsPath {
+    type Error = OsPathError;
+
+    fn try_from(input: &OsStr) -> Result<Self, OsPathError> {
+        match std::str::from_utf8(input.as_encoded_bytes()) {
+            Ok(text) => Self::parse_validated(text),
+            Err(e) => Err(OsPathError::InvalidUtf8(e.valid_up_to())),
+        }
+    }
+}
+
+impl TryFrom<OsString> for OsPath {
+    type Error = OsPathError;
+
+    fn try_from(input: OsString) -> Result<Self, OsPathError> {
+        Self::try_from(input.as_os_str())
+    }
+}
@@ -0,0 +1,230 @@
+//! Virtual views over one or more real directory trees.
+
+use crate::fs::{FsBackend, StdFs};
+use crate::{OsPath, PurePath};
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+
+/// An ordered stack of base directories where earlier layers override later ones, as used for mod and asset
+/// override schemes.
+/// ```rust
+/// use os_path::{MemoryFs, Overlay, OsPath};
+///
+/// let mut fs = MemoryFs::new();
+/// fs.add_file("/mods/textures/grass.png", "modded").unwrap();
+/// fs.add_file("/game/textures/grass.png", "original").unwrap();
+/// fs.add_file("/game/textures/stone.png", "original").unwrap();
+///
+/// let overlay = Overlay::new().layer("/mods/").layer("/game/");
+/// assert_eq!(
+///     overlay.resolve_in("textures/grass.png", &fs),
+///     Some(OsPath::from("/mods/textures/grass.png"))
+/// );
+/// assert_eq!(overlay.list_merged_in("textures/", &fs).unwrap().len(), 2);
+/// ```
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Overlay {
+    layers: Vec<OsPath>,
+}
+
+impl Overlay {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a base directory below all existing layers.
+    pub fn layer<P: AsRef<Path>>(mut self, base: P) -> Self {
+        self.push_layer(base);
+        self
+    }
+
+    /// Adds a base directory below all existing layers.
+    pub fn push_layer<P: AsRef<Path>>(&mut self, base: P) {
+        let mut base = OsPath::from(base.as_ref());
+        base.force_dir();
+        self.layers.push(base);
+    }
+
+    /// Returns the base directories, highest priority first.
+    pub fn layers(&self) -> &[OsPath] {
+        &self.layers
+    }
+
+    /// Returns the first layer's copy of `relative` that exists on disk.
+    pub fn resolve<P: AsRef<Path>>(&self, relative: P) -> Option<OsPath> {
+        self.resolve_in(relative, &StdFs)
+    }
+
+    /// Returns the first layer's copy of `relative` that exists in the given backend. Returns `None` when `relative`
+    /// climbs out of the layers with `..` or names a drive or share.
+    pub fn resolve_in<P: AsRef<Path>>(&self, relative: P, fs: &dyn FsBackend) -> Option<OsPath> {
+        let relative = Self::contained(relative.as_ref())?;
+        self.layers
+            .iter()
+            .map(|base| base.join(&relative))
+            .find(|path| path.exists_in(fs))
+    }
+
+    /// Lists the directory `relative_dir` merged across all layers on disk.
+    pub fn list_merged<P: AsRef<Path>>(&self, relative_dir: P) -> io::Result<Vec<OsPath>> {
+        self.list_merged_in(relative_dir, &StdFs)
+    }
+
+    /// Lists the directory `relative_dir` merged across all layers in the given backend.
+    ///
+    /// Each name appears once, taken from the highest priority layer that has it, and the result is sorted by name.
+    /// Layers that lack the directory are skipped; an error is only returned when no layer has it. A directory that
+    /// climbs out of the layers with `..` or names a drive or share lists as empty.
+    pub fn list_merged_in<P: AsRef<Path>>(
+        &self,
+        relative_dir: P,
+        fs: &dyn FsBackend,
+    ) -> io::Result<Vec<OsPath>> {
+        let Some(relative) = Self::contained(relative_dir.as_ref()) else {
+            return Ok(Vec::new());
+        };
+        let mut merged: BTreeMap<String, OsPath> = BTreeMap::new();
+        let mut last_err = None;
+        let mut found = false;
+        for base in &self.layers {
+            match base.join(&relative).read_dir_in(fs) {
+                Ok(entries) => {
+                    found = true;
+                    for entry in entries {
+                        if let Some(name) = entry.name() {
+                            merged.entry(name.clone()).or_insert(entry);
+                        }
+                    }
+                }
+                Err(e) => last_err = Some(e),
+            }
+        }
+        match (found, last_err) {
+            (false, Some(e)) => Err(e),
+            _ => Ok(merged.into_values().collect()),
+        }
+    }
+
+    /// Parses a path relative to the layers, as `MountTable::resolve()` does its tails, or returns `None` when it
+    /// would reach outside of them.
+    fn contained(relative: &Path) -> Option<OsPath> {
+        let mut relative = OsPath::from(relative);
+        if PurePath::climbs_out(&relative.components)
+            || relative.components.iter().any(|c| PurePath::is_prefix(c))
+        {
+            return None;
+        }
+        relative.resolve();
+        Some(relative)
+    }
+}
+
+/// Maps virtual prefixes such as `assets://` or `/config` onto real directories, so code can be handed virtual paths
+/// while the translation stays in one place.
+///
+/// Prefixes ending in `/` or `://` match as plain string prefixes, any other prefix only matches a whole leading
+/// component, so `/config` matches `/config/app.toml` but not `/configuration`. When several mounts match, the
+/// longest prefix wins. Virtual paths always use `/` as their separator.
+/// ```rust
+/// #[cfg(unix)]
+/// {
+/// use os_path::{MountTable, OsPath};
+///
+/// let mut mounts = MountTable::new();
+/// mounts.mount("assets://", "/opt/game/assets/");
+/// mounts.mount("/config", "/home/user/.config/game/");
+///
+/// let real = mounts.resolve("assets://textures/grass.png").unwrap();
+/// assert_eq!(real, OsPath::from("/opt/game/assets/textures/grass.png"));
+/// assert_eq!(mounts.to_virtual(&real).unwrap(), "assets://textures/grass.png");
+///
+/// assert_eq!(mounts.resolve("/configuration/app.toml"), None);
+/// assert_eq!(mounts.resolve("assets://../secrets.txt"), None);
+/// assert_eq!(mounts.resolve("assets://C:/Windows/win.ini"), None);
+/// assert_eq!(mounts.resolve("assets:///etc/passwd").unwrap(), OsPath::from("/opt/game/assets/etc/passwd"));
+/// }
+/// ```
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct MountTable {
+    mounts: Vec<(String, OsPath)>,
+}
+
+impl MountTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mounts `root` at the virtual `prefix`, replacing any existing mount with the same prefix.
+    pub fn mount<P: AsRef<Path>>(&mut self, prefix: &str, root: P) {
+        let mut root = OsPath::from(root.as_ref());
+        root.force_dir();
+        self.unmount(prefix);
+        self.mounts.push((prefix.to_string(), root));
+    }
+
+    /// Removes the mount at `prefix`, returning its root.
+    pub fn unmount(&mut self, prefix: &str) -> Option<OsPath> {
+        let i = self.mounts.iter().position(|(p, _)| p == prefix)?;
+        Some(self.mounts.remove(i).1)
+    }
+
+    /// Returns the mounted `(prefix, root)` pairs in the order they were added.
+    pub fn mounts(&self) -> impl Iterator<Item = (&str, &OsPath)> {
+        self.mounts.iter().map(|(p, r)| (p.as_str(), r))
+    }
+
+    /// Translates a virtual path to a real one. Returns None if no mount matches, or if the remainder of the path
+    /// would climb out of the mount's root with `..` or holds a Windows drive or share, which would replace the root.
+    /// A leading `/` in the remainder is taken from the mount's root.
+    pub fn resolve(&self, virtual_path: &str) -> Option<OsPath> {
+        let (prefix, root) = self
+            .mounts
+            .iter()
+            .filter(|(prefix, _)| Self::tail(prefix, virtual_path).is_some())
+            .max_by_key(|(prefix, _)| prefix.len())?;
+        let mut tail = OsPath::from(Self::tail(prefix, virtual_path)?);
+        if PurePath::climbs_out(&tail.components)
+            || tail.components.iter().any(|c| PurePath::is_prefix(c))
+        {
+            return None;
+        }
+        tail.resolve();
+        Some(root.join(tail))
+    }
+
+    /// Translates a real path back to its virtual form, using the mount with the deepest matching root.
+    pub fn to_virtual(&self, real: &OsPath) -> Option<String> {
+        let mut real = real.clone();
+        real.resolve();
+        let (prefix, root) = self
+            .mounts
+            .iter()
+            .filter(|(_, root)| {
+                root.absolute == real.absolute && real.components.starts_with(&root.components)
+            })
+            .max_by_key(|(_, root)| root.components.len())?;
+        let tail = real.components[root.components.len()..].join("/");
+        let mut virtual_path = prefix.clone();
+        if !tail.is_empty() && !Self::is_open(prefix) {
+            virtual_path.push('/');
+        }
+        virtual_path.push_str(&tail);
+        if real.directory && !tail.is_empty() {
+            virtual_path.push('/');
+        }
+        Some(virtual_path)
+    }
+
+    fn is_open(prefix: &str) -> bool {
+        prefix.ends_with('/')
+    }
+
+    fn tail<'a>(prefix: &str, virtual_path: &'a str) -> Option<&'a str> {
+        let tail = virtual_path.strip_prefix(prefix)?;
+        if Self::is_open(prefix) || tail.is_empty() || tail.starts_with('/') {
+            return Some(tail);
+        }
+        None
+    }
+}
@@ -0,0 +1,292 @@
+//! Coalescing bursts of filesystem change events.
+
+use crate::OsPath;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+type Key = (bool, Vec<String>);
+
+fn key(path: &OsPath) -> Key {
+    (path.absolute, path.components.clone())
+}
+
+/// A change to a single path.
+#[derive(Clone, PartialEq, Debug)]
+pub enum WatchEvent {
+    Created(OsPath),
+    Modified(OsPath),
+    Removed(OsPath),
+    Renamed { from: OsPath, to: OsPath },
+}
+
+impl fmt::Display for WatchEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WatchEvent::Created(p) => write!(f, "created {}", p),
+            WatchEvent::Modified(p) => write!(f, "modified {}", p),
+            WatchEvent::Removed(p) => write!(f, "removed {}", p),
+            WatchEvent::Renamed { from, to } => write!(f, "renamed {} -> {}", from, to),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Before {
+    Absent,
+    Present,
+    MovedFrom(OsPath),
+}
+
+#[derive(Clone, Debug)]
+struct Pending {
+    path: OsPath,
+    before: Before,
+    present: bool,
+    last: Instant,
+}
+
+/// Collects raw change events and reports one final event per path once the path has been quiet for the interval.
+///
+/// A file created and then written reports `Created`, one created and then deleted reports nothing, and a chain of
+/// renames reports a single `Renamed` from the first name to the last.
+/// ```rust
+/// #[cfg(unix)]
+/// {
+/// use os_path::{Debouncer, OsPath, WatchEvent};
+/// use std::time::{Duration, Instant};
+///
+/// let start = Instant::now();
+/// let mut debouncer = Debouncer::new(Duration::from_millis(50));
+/// debouncer.push(WatchEvent::Created(OsPath::from("/out/app.tmp")), start);
+/// debouncer.push(WatchEvent::Modified(OsPath::from("/out/app.tmp")), start);
+/// debouncer.push(
+///     WatchEvent::Renamed { from: OsPath::from("/out/app.tmp"), to: OsPath::from("/out/app") },
+///     start,
+/// );
+/// debouncer.push(WatchEvent::Modified(OsPath::from("/out/log.txt")), start);
+///
+/// assert!(debouncer.ready(start + Duration::from_millis(10)).is_empty());
+/// assert_eq!(debouncer.ready(start + Duration::from_millis(50)), vec![
+///     WatchEvent::Created(OsPath::from("/out/app")),
+///     WatchEvent::Modified(OsPath::from("/out/log.txt")),
+/// ]);
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Debouncer {
+    interval: Duration,
+    pending: BTreeMap<Key, Pending>,
+}
+
+impl Debouncer {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            pending: BTreeMap::new(),
+        }
+    }
+
+    /// Returns true if no events are waiting to be reported.
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Records a raw event that happened at the given time.
+    pub fn push(&mut self, event: WatchEvent, at: Instant) {
+        match event {
+            WatchEvent::Created(path) => self.update(path, Before::Absent, true, at),
+            WatchEvent::Modified(path) => self.update(path, Before::Present, true, at),
+            WatchEvent::Removed(path) => self.update(path, Before::Present, false, at),
+            WatchEvent::Renamed { from, to } => {
+                let before = match self.pending.remove(&key(&from)) {
+                    Some(Pending {
+                        before: Before::Absent,
+                        ..
+                    }) => Before::Absent,
+                    Some(Pending {
+                        before: Before::MovedFrom(origin),
+                        ..
+                    }) => Before::MovedFrom(origin),
+                    _ => Before::MovedFrom(from),
+                };
+                let pending = Pending {
+                    path: to.clone(),
+                    before,
+                    present: true,
+                    last: at,
+                };
+                self.pending.insert(key(&to), pending);
+            }
+        }
+    }
+
+    /// Removes and returns the events for paths that have been quiet for the interval as of `now`, oldest first.
+    pub fn ready(&mut self, now: Instant) -> Vec<WatchEvent> {
+        let quiet: Vec<Key> = self
+            .pending
+            .iter()
+            .filter(|(_, p)| now.saturating_duration_since(p.last) >= self.interval)
+            .map(|(k, _)| k.clone())
+            .collect();
+        let mut ready: Vec<Pending> = quiet
+            .iter()
+            .filter_map(|k| self.pending.remove(k))
+            .collect();
+        ready.sort_by_key(|p| p.last);
+        ready.into_iter().filter_map(Self::settle).collect()
+    }
+
+    /// Removes and returns every waiting event, quiet or not.
+    pub fn flush(&mut self) -> Vec<WatchEvent> {
+        let mut ready: Vec<Pending> = std::mem::take(&mut self.pending).into_values().collect();
+        ready.sort_by_key(|p| p.last);
+        ready.into_iter().filter_map(Self::settle).collect()
+    }
+
+    /// Returns how long until the next path becomes quiet, if any are waiting.
+    pub fn next_deadline(&self, now: Instant) -> Option<Duration> {
+        self.pending
+            .values()
+            .map(|p| (p.last + self.interval).saturating_duration_since(now))
+            .min()
+    }
+
+    fn update(&mut self, path: OsPath, before: Before, present: bool, at: Instant) {
+        let pending = self.pending.entry(key(&path)).or_insert(Pending {
+            path,
+            before,
+            present,
+            last: at,
+        });
+        pending.present = present;
+        pending.last = at;
+    }
+
+    fn settle(pending: Pending) -> Option<WatchEvent> {
+        let path = pending.path;
+        match (pending.before, pending.present) {
+            (Before::Absent, true) => Some(WatchEvent::Created(path)),
+            (Before::Absent, false) => None,
+            (Before::Present, true) => Some(WatchEvent::Modified(path)),
+            (Before::Present, false) => Some(WatchEvent::Removed(path)),
+            (Before::MovedFrom(from), true) => Some(WatchEvent::Renamed { from, to: path }),
+            (Before::MovedFrom(from), false) => Some(WatchEvent::Removed(from)),
+        }
+    }
+}
+
+#[cfg(feature = "watch")]
+pub use watcher::DebouncedWatcher;
+
+#[cfg(feature = "watch")]
+mod watcher {
+    use super::{Debouncer, WatchEvent};
+    use crate::OsPath;
+    use notify::event::{ModifyKind, RenameMode};
+    use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+    use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+    use std::time::{Duration, Instant};
+
+    /// A recursive watch on a directory whose events are coalesced by a `Debouncer`. Created by
+    /// `OsPath::watch_debounced()`.
+    ///
+    /// Iterating blocks until a batch of settled events is ready, and ends if the watch stops.
+    pub struct DebouncedWatcher {
+        _watcher: RecommendedWatcher,
+        events: Receiver<notify::Result<Event>>,
+        debouncer: Debouncer,
+    }
+
+    impl DebouncedWatcher {
+        /// Returns any settled events without blocking.
+        pub fn try_next(&mut self) -> notify::Result<Vec<WatchEvent>> {
+            while let Ok(event) = self.events.try_recv() {
+                self.record(event?);
+            }
+            Ok(self.debouncer.ready(Instant::now()))
+        }
+
+        fn record(&mut self, event: Event) {
+            let now = Instant::now();
+            let mut paths = event.paths.into_iter().map(OsPath::from);
+            let raw = match event.kind {
+                EventKind::Create(_) => paths.next().map(WatchEvent::Created),
+                EventKind::Remove(_) => paths.next().map(WatchEvent::Removed),
+                EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => {
+                    match (paths.next(), paths.next()) {
+                        (Some(from), Some(to)) => Some(WatchEvent::Renamed { from, to }),
+                        _ => None,
+                    }
+                }
+                EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+                    paths.next().map(WatchEvent::Removed)
+                }
+                EventKind::Modify(ModifyKind::Name(RenameMode::To)) => {
+                    paths.next().map(WatchEvent::Created)
+                }
+                EventKind::Modify(_) => paths.next().map(WatchEvent::Modified),
+                _ => None,
+            };
+            if let Some(raw) = raw {
+                self.debouncer.push(raw, now);
+            }
+        }
+    }
+
+    impl Iterator for DebouncedWatcher {
+        type Item = notify::Result<Vec<WatchEvent>>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            loop {
+                let received = match self.debouncer.next_deadline(Instant::now()) {
+                    Some(wait) => self.events.recv_timeout(wait),
+                    None => self
+                        .events
+                        .recv()
+                        .map_err(|_| RecvTimeoutError::Disconnected),
+                };
+                match received {
+                    Ok(Ok(event)) => self.record(event),
+                    Ok(Err(e)) => return Some(Err(e)),
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => {
+                        let rest = self.debouncer.flush();
+                        return (!rest.is_empty()).then_some(Ok(rest));
+                    }
+                }
+                let ready = self.debouncer.ready(Instant::now());
+                if !ready.is_empty() {
+                    return Some(Ok(ready));
+                }
+            }
+        }
+    }
+
+    /// Watch Methods
+    impl OsPath {
+        /// Watches this directory recursively, reporting each changed path once it has been quiet for `interval`.
+        /// ```rust,no_run
+        /// use os_path::OsPath;
+        /// use std::time::Duration;
+        ///
+        /// for batch in OsPath::from("src/").watch_debounced(Duration::from_millis(200)).unwrap() {
+        ///     for event in batch.unwrap() {
+        ///         println!("{}", event);
+        ///     }
+        /// }
+        /// ```
+        pub fn watch_debounced(&self, interval: Duration) -> notify::Result<DebouncedWatcher> {
+            let (sender, events) = mpsc::channel();
+            let mut watcher = notify::recommended_watcher(move |event| {
+                let _ = sender.send(event);
+            })?;
+            watcher.watch(&self.path, RecursiveMode::Recursive)?;
+            Ok(DebouncedWatcher {
+                _watcher: watcher,
+                events,
+                debouncer: Debouncer::new(interval),
+            })
+        }
+    }
+}
@@ -0,0 +1,325 @@
+//! Helpers for serving files out of a directory from request paths.
+
+use crate::glob::match_stars;
+use crate::{Effect, OsPath, PurePath};
+use std::fmt;
+use std::path::Path;
+
+const INDEX: &str = "index.html";
+
+/// The reasons a request path can be refused by `resolve_request_path()`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ResolveError {
+    /// A `%` escape was malformed, or the decoded bytes were not valid UTF-8.
+    InvalidEncoding,
+    /// The decoded path contained a NUL byte.
+    NulByte,
+    /// The decoded path contained a `..` component.
+    ParentTraversal,
+    /// The decoded path contained a drive prefix such as `C:`.
+    AbsolutePath,
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            ResolveError::InvalidEncoding => "request path is not valid percent-encoded UTF-8",
+            ResolveError::NulByte => "request path contains a NUL byte",
+            ResolveError::ParentTraversal => "request path contains a `..` component",
+            ResolveError::AbsolutePath => "request path contains a drive prefix",
+        };
+        f.write_str(msg)
+    }
+}
+
+impl std::error::Error for ResolveError {}
+
+/// Maps the path portion of a request URI onto a file below `base`.
+///
+/// The query string and fragment are dropped, the rest is percent-decoded, and every `/` becomes the platform
+/// separator. Anything that could land outside of `base` is refused rather than clamped.
+/// ```rust
+/// #[cfg(unix)]
+/// {
+/// use os_path::{resolve_request_path, ResolveError};
+///
+/// let path = resolve_request_path("/srv/www/", "/docs/My%20File.txt?v=2").unwrap();
+/// assert_eq!(path.to_string(), "/srv/www/docs/My File.txt");
+///
+/// assert_eq!(resolve_request_path("/srv/www/", "/%2e%2e/etc/passwd"), Err(ResolveError::ParentTraversal));
+/// }
+/// ```
+pub fn resolve_request_path<P: AsRef<Path>>(
+    base: P,
+    uri_path: &str,
+) -> Result<OsPath, ResolveError> {
+    resolve(base, uri_path, None)
+}
+
+/// Same as `resolve_request_path()`, but appends `index.html` when the request names a directory, either through a
+/// trailing slash or because the resolved path is a directory on disk.
+/// ```rust
+/// #[cfg(unix)]
+/// {
+/// use os_path::resolve_request_path_with_index;
+///
+/// let path = resolve_request_path_with_index("/srv/www/", "/blog/").unwrap();
+/// assert_eq!(path.to_string(), "/srv/www/blog/index.html");
+/// }
+/// ```
+pub fn resolve_request_path_with_index<P: AsRef<Path>>(
+    base: P,
+    uri_path: &str,
+) -> Result<OsPath, ResolveError> {
+    resolve(base, uri_path, Some(INDEX))
+}
+
+/// An allow and deny list written in robots.txt notation, applied to paths below a web root.
+///
+/// Each pattern is matched against the start of the path as a URL would show it, such as `/docs/intro.html` or
+/// `/blog/` for a directory. `*` matches any run of characters, `/` included, and a `$` at the end of a pattern
+/// requires the path to end there. Patterns may be percent-encoded. When several rules match, the longest pattern
+/// decides, and an allow rule beats a deny rule of the same length. A path no rule matches is allowed.
+/// ```rust
+/// use os_path::{OsPath, WebRules};
+///
+/// let rules = WebRules::new()
+///     .disallow("/private/").unwrap()
+///     .allow("/private/press-kit/").unwrap()
+///     .disallow("/*.bak$").unwrap();
+///
+/// assert!(rules.is_allowed(&OsPath::from("index.html")));
+/// assert!(!rules.is_allowed(&OsPath::from("private/notes.txt")));
+/// assert!(rules.is_allowed(&OsPath::from("private/press-kit/logo.png")));
+/// assert!(!rules.is_allowed(&OsPath::from("site/old.bak")));
+/// assert!(rules.is_allowed(&OsPath::from("site/old.bak.txt")));
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct WebRules {
+    rules: Vec<(String, Effect)>,
+}
+
+impl WebRules {
+    /// Creates an empty list, which allows every path.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a rule allowing paths that start with the pattern. An empty pattern matches nothing.
+    pub fn allow(self, pattern: &str) -> Result<Self, ResolveError> {
+        self.rule(pattern, Effect::Allow)
+    }
+
+    /// Adds a rule denying paths that start with the pattern. An empty pattern matches nothing.
+    pub fn disallow(self, pattern: &str) -> Result<Self, ResolveError> {
+        self.rule(pattern, Effect::Deny)
+    }
+
+    /// Adds a rule, failing if the pattern's percent-encoding is malformed.
+    pub fn rule(mut self, pattern: &str, effect: Effect) -> Result<Self, ResolveError> {
+        let mut pattern = percent_decode(pattern)?;
+        if !pattern.is_empty() {
+            if !pattern.starts_with(['/', '*']) {
+                pattern.insert(0, '/');
+            }
+            self.rules.push((pattern, effect));
+        }
+        Ok(self)
+    }
+
+    /// Returns the effect of the longest matching pattern, or None if no pattern matches. The path is relative to
+    /// the web root.
+    pub fn evaluate(&self, path: &OsPath) -> Option<Effect> {
+        let text = path.to_web_path();
+        self.rules
+            .iter()
+            .filter(|(pattern, _)| web_match(pattern.as_bytes(), text.as_bytes()))
+            .max_by_key(|(pattern, effect)| (pattern.len(), *effect == Effect::Allow))
+            .map(|(_, effect)| *effect)
+    }
+
+    /// Returns true if the path, relative to the web root, is allowed.
+    pub fn is_allowed(&self, path: &OsPath) -> bool {
+        self.evaluate(path) != Some(Effect::Deny)
+    }
+}
+
+/// Returns true if the text starts with the robots.txt pattern. Patterns come from untrusted files, so matching
+/// retries only the last `*`, as globs do, which keeps it from taking exponential time.
+fn web_match(pattern: &[u8], text: &[u8]) -> bool {
+    // A pattern matches a prefix of the text, as if it ended in `*`, unless it ends in `$`.
+    let mut pattern = pattern.to_vec();
+    if pattern.last() == Some(&b'$') {
+        pattern.pop();
+    } else {
+        pattern.push(b'*');
+    }
+    match_stars(&pattern, text, |p| *p == b'*', |p, t| p == t)
+}
+
+/// Web Methods
+impl OsPath {
+    /// Returns the path as it would appear in a URL below a web root: starting with `/`, with `/` separators, and
+    /// ending with `/` if it names a directory. Nothing is percent-encoded.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// assert_eq!(OsPath::from("docs\\My File.txt").to_web_path(), "/docs/My File.txt");
+    /// assert_eq!(OsPath::from("blog/").to_web_path(), "/blog/");
+    /// assert_eq!(OsPath::new().to_web_path(), "/");
+    /// ```
+    pub fn to_web_path(&self) -> String {
+        let mut web = String::from("/");
+        web.push_str(&self.components.join("/"));
+        if self.directory && !self.components.is_empty() {
+            web.push('/');
+        }
+        PurePath::displayable(web)
+    }
+
+    /// Returns the path with each component percent-encoded on its own, so that only the unreserved characters of
+    /// RFC 3986 (letters, digits, `-`, `.`, `_` and `~`) are left as they are, joined with `/`. The result is safe
+    /// in a URL path or a query string. It starts with `/` if the path is absolute and ends with `/` if it names a
+    /// directory, so `decode_components()` gives the path back.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// let path = OsPath::from("reports/Q1 & Q2/50%+.csv");
+    /// assert_eq!(path.encode_components(), "reports/Q1%20%26%20Q2/50%25%2B.csv");
+    /// assert_eq!(OsPath::decode_components(&path.encode_components()).unwrap(), path);
+    /// ```
+    pub fn encode_components(&self) -> String {
+        let encoded: Vec<String> = self
+            .components
+            .iter()
+            .map(|c| percent_encode(c, |b| b.is_ascii_alphanumeric() || b"-._~".contains(&b)))
+            .collect();
+        let mut text = encoded.join("/");
+        if self.absolute {
+            text.insert(0, '/');
+        }
+        if self.directory && !self.components.is_empty() {
+            text.push('/');
+        }
+        text
+    }
+
+    /// Builds a path from `/` separated, percent-encoded components, as made by `encode_components()`. Each
+    /// component is decoded on its own, so an escaped separator such as `%2F` is refused with
+    /// `ResolveError::InvalidEncoding` rather than splitting a name in two.
+    /// ```rust
+    /// use os_path::{OsPath, ResolveError};
+    ///
+    /// let path = OsPath::decode_components("photos/caf%C3%A9/").unwrap();
+    /// assert_eq!(path.name().unwrap(), "café");
+    /// assert!(path.is_dir());
+    ///
+    /// assert_eq!(OsPath::decode_components("a%2Fb"), Err(ResolveError::InvalidEncoding));
+    /// assert_eq!(OsPath::decode_components("a%00"), Err(ResolveError::NulByte));
+    /// ```
+    pub fn decode_components(text: &str) -> Result<Self, ResolveError> {
+        let mut names = Vec::new();
+        for segment in text.split('/').filter(|s| !s.is_empty()) {
+            let name = percent_decode(segment)?;
+            if name.contains('\0') {
+                return Err(ResolveError::NulByte);
+            }
+            if name.contains(['/', '\\']) {
+                return Err(ResolveError::InvalidEncoding);
+            }
+            names.push(name);
+        }
+        let mut decoded = names.join("/");
+        if text.starts_with('/') {
+            decoded.insert(0, '/');
+        }
+        if text.ends_with('/') && !names.is_empty() {
+            decoded.push('/');
+        }
+        Ok(OsPath::from(decoded))
+    }
+}
+
+fn resolve<P: AsRef<Path>>(
+    base: P,
+    uri_path: &str,
+    index: Option<&str>,
+) -> Result<OsPath, ResolveError> {
+    let uri_path = uri_path.split(['?', '#']).next().unwrap_or_default();
+    let decoded = percent_decode(uri_path)?;
+    if decoded.contains('\0') {
+        return Err(ResolveError::NulByte);
+    }
+
+    let mut path = OsPath::from(base.as_ref());
+    path.force_dir();
+    for c in decoded.split(['/', '\\']) {
+        if c.is_empty() || c == "." {
+            continue;
+        }
+        if c == ".." {
+            return Err(ResolveError::ParentTraversal);
+        }
+        if is_drive(c) {
+            return Err(ResolveError::AbsolutePath);
+        }
+        path.push(c);
+    }
+
+    let directory = decoded.is_empty() || decoded.ends_with('/') || decoded.ends_with('\\');
+    if directory {
+        path.force_dir();
+    }
+    if let Some(index) = index {
+        if directory || path.to_path().is_dir() {
+            path.force_dir();
+            path.push(index);
+        }
+    }
+    Ok(path)
+}
+
+fn is_drive(component: &str) -> bool {
+    let bytes = component.as_bytes();
+    bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':'
+}
+
+/// Decodes `%XX` escapes, failing on truncated escapes, bad hex digits, or a result that is not UTF-8.
+pub(crate) fn percent_decode(s: &str) -> Result<String, ResolveError> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = bytes
+                .get(i + 1..i + 3)
+                .ok_or(ResolveError::InvalidEncoding)?;
+            let (hi, lo) = match (hex_value(hex[0]), hex_value(hex[1])) {
+                (Some(hi), Some(lo)) => (hi, lo),
+                _ => return Err(ResolveError::InvalidEncoding),
+            };
+            out.push(hi << 4 | lo);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).map_err(|_| ResolveError::InvalidEncoding)
+}
+
+/// Encodes every byte the predicate does not keep as a `%XX` escape.
+pub(crate) fn percent_encode(s: &str, keep: impl Fn(u8) -> bool) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        match keep(b) {
+            true => out.push(b as char),
+            false => out.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    out
+}
+
+fn hex_value(b: u8) -> Option<u8> {
+    (b as char).to_digit(16).map(|d| d as u8)
+}
@@ -0,0 +1,204 @@
+//! Replacing file contents without ever leaving a partly written file behind.
+
+use crate::error::IoResultExt;
+use crate::fs::require_os;
+use crate::OsPath;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Controls the backups made by `backup_then_write()`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct BackupPolicy {
+    timestamped: bool,
+    keep: usize,
+}
+
+impl Default for BackupPolicy {
+    fn default() -> Self {
+        Self {
+            timestamped: false,
+            keep: 1,
+        }
+    }
+}
+
+impl BackupPolicy {
+    /// Creates a policy keeping a single `foo.txt.bak` backup, replaced on every write.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Names backups with the UTC time they were made, as in `foo.txt.20240131-235959.123.bak`, so that earlier
+    /// ones are kept.
+    pub fn timestamped(mut self, yes: bool) -> Self {
+        self.timestamped = yes;
+        self
+    }
+
+    /// Sets how many timestamped backups to keep, deleting the oldest beyond that. Never less than one.
+    pub fn keep(mut self, count: usize) -> Self {
+        self.keep = count.max(1);
+        self
+    }
+}
+
+/// Writing Methods
+impl OsPath {
+    /// Replaces the contents of the file atomically: the data is written and synced to a temporary sibling, which is
+    /// then renamed over the file, so readers see either the old contents or the new, never a mix. On Unix the
+    /// directory is synced after the rename, so the new contents are there after a crash. An existing file's
+    /// permissions are kept.
+    /// ```rust
+    /// use os_path::OsPath;
+    ///
+    /// let path = OsPath::from(std::env::temp_dir()).join("os_path_atomic_doc.txt");
+    /// path.write_atomic("first").unwrap();
+    /// path.write_atomic("second").unwrap();
+    /// assert_eq!(std::fs::read_to_string(&path).unwrap(), "second");
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn write_atomic<C: AsRef<[u8]>>(&self, contents: C) -> io::Result<()> {
+        require_os()?;
+        // The process ID and a counter keep concurrent writers, in this process or another, off each other's file.
+        let temp = self.with_name(format!(
+            ".{}.~tmp{}-{}",
+            self.name().map_or("", |n| n.as_str()),
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let mut created = false;
+        let written = (|| {
+            let mut file = std::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&temp)?;
+            created = true;
+            file.write_all(contents.as_ref())?;
+            file.sync_all()?;
+            if let Ok(meta) = std::fs::metadata(self) {
+                std::fs::set_permissions(&temp, meta.permissions())?;
+            }
+            std::fs::rename(&temp, self)?;
+            self.sync_parent()
+        })();
+        if written.is_err() && created {
+            let _ = std::fs::remove_file(&temp);
+        }
+        written.with_path_context("write", self)
+    }
+
+    /// Syncs the directory holding the path, so a rename into it survives a crash. Windows has no way to open a
+    /// directory for this, and NTFS journals the rename itself.
+    fn sync_parent(&self) -> io::Result<()> {
+        #[cfg(unix)]
+        {
+            let parent = self.parent();
+            let dir = parent
+                .as_ref()
+                .map_or(std::path::Path::new("."), |p| p.as_ref());
+            std::fs::File::open(dir)?.sync_all()?;
+        }
+        Ok(())
+    }
+
+    /// Copies the existing file to a backup sibling as the policy describes, then replaces it atomically with
+    /// `write_atomic()`. Returns the backup made, if the file existed.
+    /// ```rust
+    /// use os_path::{BackupPolicy, OsPath};
+    ///
+    /// let path = OsPath::from(std::env::temp_dir()).join("os_path_backup_doc.txt");
+    /// std::fs::write(&path, "v1").unwrap();
+    ///
+    /// let backup = path.backup_then_write("v2", &BackupPolicy::new()).unwrap().unwrap();
+    /// assert_eq!(backup.name().unwrap(), "os_path_backup_doc.txt.bak");
+    /// assert_eq!(std::fs::read_to_string(&backup).unwrap(), "v1");
+    /// assert_eq!(std::fs::read_to_string(&path).unwrap(), "v2");
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # std::fs::remove_file(&backup).unwrap();
+    /// ```
+    pub fn backup_then_write<C: AsRef<[u8]>>(
+        &self,
+        contents: C,
+        policy: &BackupPolicy,
+    ) -> io::Result<Option<OsPath>> {
+        let name = self.name().cloned().unwrap_or_default();
+        let backup = if std::fs::symlink_metadata(self).is_ok() {
+            let backup = if policy.timestamped {
+                self.with_name(format!("{}.{}.bak", name, timestamp(SystemTime::now())))
+            } else {
+                self.with_name(format!("{}.bak", name))
+            };
+            std::fs::copy(self, &backup).with_path_context("back up", self)?;
+            Some(backup)
+        } else {
+            None
+        };
+        self.write_atomic(contents)?;
+        if policy.timestamped {
+            self.trim_backups(&name, policy.keep)?;
+        }
+        Ok(backup)
+    }
+
+    fn trim_backups(&self, name: &str, keep: usize) -> io::Result<()> {
+        let dir = match self.parent() {
+            Some(dir) => dir,
+            None => return Ok(()),
+        };
+        let prefix = format!("{}.", name);
+        let mut backups: Vec<String> = std::fs::read_dir(&dir)?
+            .filter_map(|e| e.ok()?.file_name().into_string().ok())
+            .filter(|n| {
+                n.strip_prefix(&prefix)
+                    .and_then(|rest| rest.strip_suffix(".bak"))
+                    .is_some_and(is_timestamp)
+            })
+            .collect();
+        backups.sort();
+        let excess = backups.len().saturating_sub(keep);
+        for old in &backups[..excess] {
+            std::fs::remove_file(self.with_name(old))?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats the time as `YYYYMMDD-HHMMSS.mmm` in UTC, which sorts chronologically.
+fn timestamp(time: SystemTime) -> String {
+    let since = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since.as_secs();
+    let (days, rem) = (secs / 86400, secs % 86400);
+    // Civil date from days since 1970-01-01, after Howard Hinnant's `civil_from_days`.
+    let z = days as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}.{:03}",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60,
+        since.subsec_millis()
+    )
+}
+
+fn is_timestamp(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    bytes.len() == 19
+        && bytes.iter().enumerate().all(|(i, b)| match i {
+            8 => *b == b'-',
+            15 => *b == b'.',
+            _ => b.is_ascii_digit(),
+        })
+}
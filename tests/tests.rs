@@ -1,3 +1,5 @@
+#![cfg(feature = "std")]
+
 use os_path::OsPath;
 // use serde::{Deserialize, Serialize};
 // use serde_json;
@@ -186,6 +188,23 @@ fn test_froms() {
     }
 }
 
+#[test]
+fn test_collect_and_extend() {
+    let path: OsPath = ["usr", "local", "bin"].iter().collect();
+    assert_eq!(path, OsPath::from("usr/local/bin"));
+
+    let owned: OsPath = vec!["a".to_string(), "b/".to_string()]
+        .into_iter()
+        .collect();
+    assert_eq!(owned, OsPath::from("a/b/"));
+
+    let mut path = OsPath::from("/srv/");
+    path.extend(["www", "site", "index.html"]);
+    assert_eq!(path, OsPath::from("/srv/www/site/index.html"));
+    path.extend([OsPath::from("../style.css")]);
+    assert_eq!(path, OsPath::from("/srv/www/style.css"));
+}
+
 #[test]
 fn test_some_edge_cases() {
     #[cfg(unix)]
@@ -207,3 +226,2370 @@ fn test_some_edge_cases() {
         assert_eq!(OsPath::from("./").to_string(), "./");
     }
 }
+
+#[test]
+fn test_resolve_request_path() {
+    use os_path::{resolve_request_path, ResolveError};
+
+    #[cfg(unix)]
+    {
+        assert_eq!(
+            resolve_request_path("/srv/www", "/a//./b%20c.txt#top"),
+            Ok(OsPath::from("/srv/www/a/b c.txt"))
+        );
+        assert_eq!(
+            resolve_request_path("/srv/www", "/a/"),
+            Ok(OsPath::from("/srv/www/a/"))
+        );
+    }
+
+    assert_eq!(
+        resolve_request_path("www", "/a/..%2F..%2Fsecret"),
+        Err(ResolveError::ParentTraversal)
+    );
+    assert_eq!(
+        resolve_request_path("www", "/a%00.txt"),
+        Err(ResolveError::NulByte)
+    );
+    assert_eq!(
+        resolve_request_path("www", "/C:/Windows"),
+        Err(ResolveError::AbsolutePath)
+    );
+    assert_eq!(
+        resolve_request_path("www", "/%zz"),
+        Err(ResolveError::InvalidEncoding)
+    );
+    assert_eq!(
+        resolve_request_path("www", "/%+1"),
+        Err(ResolveError::InvalidEncoding)
+    );
+}
+
+#[test]
+fn test_memory_fs_walk() {
+    use os_path::{FsBackend, LimitExceeded, MemoryFs, TraversalLimits};
+    use std::io::ErrorKind;
+
+    let mut fs = MemoryFs::new();
+    fs.add_file("/root/a/one.txt", "1")
+        .unwrap()
+        .add_file("/root/a/b/two.txt", "22")
+        .unwrap()
+        .add_dir("/root/empty/")
+        .unwrap();
+
+    let root = OsPath::from("/root/");
+    let all: Vec<OsPath> = root.walk_in(&fs).map(Result::unwrap).collect();
+    assert_eq!(all.len(), 5);
+    assert!(all.iter().all(|p| p.exists_in(&fs)));
+
+    let shallow: Vec<OsPath> = root.walk_in(&fs).max_depth(1).map(Result::unwrap).collect();
+    assert_eq!(shallow.len(), 2);
+    assert!(shallow.iter().all(|p| p.is_dir()));
+    assert_eq!(root.walk_in(&fs).max_depth(0).count(), 0);
+
+    assert_eq!(
+        fs.metadata(&OsPath::from("/root/a/b/two.txt"))
+            .unwrap()
+            .len(),
+        2
+    );
+    assert_eq!(fs.read("/root/a/one.txt").unwrap(), b"1");
+
+    let limited = |limits| root.walk_in(&fs).limits(limits).collect::<Vec<_>>();
+    let entries = limited(TraversalLimits::new().max_entries(3));
+    assert_eq!(entries.len(), 4);
+    let err = entries[3].as_ref().unwrap_err();
+    assert_eq!(
+        LimitExceeded::from_io(err),
+        Some(&LimitExceeded::Entries(3))
+    );
+    assert!(limited(TraversalLimits::new().max_depth(3))
+        .iter()
+        .all(Result::is_ok));
+    assert_eq!(root.dir_size_in(TraversalLimits::new(), &fs).unwrap(), 3);
+    let err = root
+        .dir_size_in(TraversalLimits::new().max_bytes(2), &fs)
+        .unwrap_err();
+    assert_eq!(LimitExceeded::from_io(&err), Some(&LimitExceeded::Bytes(2)));
+    assert!(OsPath::from("/missing/")
+        .walk_in(&fs)
+        .next()
+        .unwrap()
+        .is_err());
+
+    // A file cannot hold entries, and files and directories do not replace each other.
+    let kind = |result: std::io::Result<&mut MemoryFs>| result.map(|_| ()).unwrap_err().kind();
+    assert_eq!(
+        kind(fs.add_file("/root/a/one.txt/x.txt", "")),
+        ErrorKind::NotADirectory
+    );
+    assert_eq!(
+        kind(fs.add_dir("/root/a/one.txt/sub/")),
+        ErrorKind::NotADirectory
+    );
+    assert_eq!(
+        kind(fs.add_dir("/root/a/one.txt")),
+        ErrorKind::AlreadyExists
+    );
+    assert_eq!(kind(fs.add_file("/root/a/b", "")), ErrorKind::IsADirectory);
+    assert!(!OsPath::from("/root/a/one.txt/sub/").exists_in(&fs));
+    assert_eq!(fs.read("/root/a/one.txt").unwrap(), b"1");
+    fs.add_file("/root/a/one.txt", "one").unwrap();
+    fs.add_dir("/root/a/b/").unwrap();
+    assert_eq!(root.walk_in(&fs).count(), 5);
+}
+
+#[test]
+fn test_dir_size_reads_metadata_once() {
+    use os_path::{FsBackend, MemoryFs, Metadata, TraversalLimits};
+    use std::cell::Cell;
+
+    struct Counting {
+        fs: MemoryFs,
+        reads: Cell<usize>,
+    }
+
+    impl FsBackend for Counting {
+        fn metadata(&self, path: &OsPath) -> std::io::Result<Metadata> {
+            self.reads.set(self.reads.get() + 1);
+            self.fs.metadata(path)
+        }
+
+        fn read_dir(&self, path: &OsPath) -> std::io::Result<Vec<OsPath>> {
+            self.fs.read_dir(path)
+        }
+    }
+
+    let mut fs = MemoryFs::new();
+    fs.add_file("/d/a.txt", "1")
+        .unwrap()
+        .add_file("/d/sub/b.txt", "22")
+        .unwrap();
+    let counting = Counting {
+        fs,
+        reads: Cell::new(0),
+    };
+    let limits = TraversalLimits::new().max_bytes(10);
+    assert_eq!(
+        OsPath::from("/d/").dir_size_in(limits, &counting).unwrap(),
+        3
+    );
+    assert_eq!(counting.reads.get(), 2);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_walk_subtree_selector() {
+    use os_path::{FsBackend, MemoryFs, Metadata, PathFilter, SubtreeSelector};
+    use std::cell::RefCell;
+
+    struct Counting {
+        fs: MemoryFs,
+        reads: RefCell<Vec<String>>,
+    }
+
+    impl FsBackend for Counting {
+        fn metadata(&self, path: &OsPath) -> std::io::Result<Metadata> {
+            self.fs.metadata(path)
+        }
+
+        fn read_dir(&self, path: &OsPath) -> std::io::Result<Vec<OsPath>> {
+            self.reads.borrow_mut().push(path.to_string());
+            self.fs.read_dir(path)
+        }
+    }
+
+    let mut fs = MemoryFs::new();
+    fs.add_file("/repo/README.md", "")
+        .unwrap()
+        .add_file("/repo/services/billing/main.go", "")
+        .unwrap()
+        .add_file("/repo/services/billing/fixtures/big.json", "")
+        .unwrap()
+        .add_file("/repo/services/search/main.go", "")
+        .unwrap()
+        .add_file("/repo/web/node_modules/dep/index.js", "")
+        .unwrap();
+    let counting = Counting {
+        fs,
+        reads: RefCell::new(Vec::new()),
+    };
+
+    let selector = SubtreeSelector::parse("services/billing/\n!services/billing/fixtures/\n");
+    let found: Vec<String> = OsPath::from("/repo/")
+        .walk_in(&counting)
+        .select(selector.clone())
+        .map(|p| p.unwrap().to_string())
+        .collect();
+    assert_eq!(
+        found,
+        vec!["/repo/services/billing/", "/repo/services/billing/main.go"]
+    );
+    assert_eq!(
+        *counting.reads.borrow(),
+        vec!["/repo/", "/repo/services/", "/repo/services/billing/"]
+    );
+
+    let go_only = PathFilter::new().extensions(["go"]);
+    let found: Vec<OsPath> = OsPath::from("/repo/")
+        .walk_in(&counting)
+        .select(selector)
+        .with_filter(go_only)
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(found, vec![OsPath::from("/repo/services/billing/main.go")]);
+
+    let everything_but_web = SubtreeSelector::new().exclude("web/");
+    assert!(everything_but_web.selects(&OsPath::from("README.md")));
+    assert!(!everything_but_web.may_select_below(&OsPath::from("web/")));
+}
+
+#[test]
+fn test_walk_hidden_policy() {
+    use os_path::{HiddenPolicy, MemoryFs, PathFilter};
+
+    let mut fs = MemoryFs::new();
+    fs.add_file("/site/index.md", "")
+        .unwrap()
+        .add_file("/site/_drafts/post.md", "")
+        .unwrap()
+        .add_file("/site/~$notes.docx", "")
+        .unwrap()
+        .add_file("/site/.env", "")
+        .unwrap();
+
+    let policy = HiddenPolicy::none().prefix("_").prefix("~$");
+    let filter = PathFilter::new().hidden_policy(policy);
+    let found: Vec<OsPath> = OsPath::from("/site/")
+        .walk_in(&fs)
+        .with_filter(filter)
+        .map(Result::unwrap)
+        .collect();
+    let names: Vec<&String> = found.iter().filter_map(OsPath::name).collect();
+    assert_eq!(names, [".env", "index.md"]);
+}
+
+#[test]
+fn test_translate() {
+    #[cfg(unix)]
+    {
+        let path = OsPath::from("/mnt/old/projects/app/");
+        assert_eq!(
+            path.translate("/mnt/old", "/srv/new/"),
+            Ok(OsPath::from("/srv/new/projects/app/"))
+        );
+        assert_eq!(
+            OsPath::from("/mnt/old").translate("/mnt/old/", "/srv/new"),
+            Ok(OsPath::from("/srv/new"))
+        );
+        assert!(OsPath::from("/mnt/older/a")
+            .translate("/mnt/old", "/")
+            .is_err());
+        assert!(OsPath::from("/mnt/old/../etc")
+            .translate("/mnt/old", "/")
+            .is_err());
+        assert!(OsPath::from("mnt/old/a")
+            .translate("/mnt/old", "/")
+            .is_err());
+    }
+}
+
+#[test]
+fn test_diff_sets() {
+    use os_path::{diff_sets, OsPathSet};
+
+    let old: OsPathSet = ["a/1.txt", "a/2.txt", "b/"].into_iter().collect();
+    let mut new = old.clone();
+    new.remove("a/1.txt");
+    new.insert("c/3.txt");
+
+    let diff = diff_sets(&old, &new);
+    assert_eq!(diff.added, vec![OsPath::from("c/3.txt")]);
+    assert_eq!(diff.removed, vec![OsPath::from("a/1.txt")]);
+    assert_eq!(diff.common.len(), 2);
+
+    #[cfg(feature = "unicode")]
+    {
+        use os_path::{diff_sets_with, DiffOptions};
+
+        let old: OsPathSet = ["caf\u{e9}.txt"].into_iter().collect();
+        let new: OsPathSet = ["cafe\u{301}.txt"].into_iter().collect();
+        assert_eq!(diff_sets(&old, &new).common.len(), 0);
+        let diff = diff_sets_with(&old, &new, DiffOptions::new().unicode_nfc(true));
+        assert_eq!(diff.common.len(), 1);
+    }
+
+    // Names that are not Unicode, and so display alike, stay apart.
+    #[cfg(unix)]
+    {
+        use os_path::{diff_sets_with, DiffOptions};
+        use std::os::unix::ffi::OsStrExt;
+
+        let odd =
+            |bytes: &[u8]| OsPath::from(std::path::Path::new(std::ffi::OsStr::from_bytes(bytes)));
+        let (fe, ff) = (odd(b"x\xfe"), odd(b"x\xff"));
+        assert_eq!(fe.to_string(), ff.to_string());
+        let old: OsPathSet = [fe.clone(), ff.clone()].into_iter().collect();
+        assert_eq!(old.len(), 2);
+        let new: OsPathSet = [fe.clone()].into_iter().collect();
+        assert_eq!(diff_sets(&old, &new).removed, vec![ff.clone()]);
+        let diff = diff_sets_with(&old, &new, DiffOptions::new().ignore_case(true));
+        assert_eq!(diff.removed, vec![ff]);
+    }
+}
+
+#[test]
+fn test_glob_and_policy() {
+    use os_path::{Effect, Glob, Policy};
+
+    let matches =
+        |pattern: &str, path: &str| Glob::new(pattern).unwrap().matches(&OsPath::from(path));
+    assert!(matches("*.txt", "a/b/notes.txt"));
+    assert!(!matches("*.txt", "a/b/notes.txt.bak"));
+    assert!(matches("file?.[0-9]", "file1.7"));
+    assert!(!matches("file[!0-9].txt", "file1.txt"));
+    assert!(matches("a/**/z", "a/z"));
+    assert!(matches("a/**/z", "a/b/c/z"));
+    assert!(!matches("a/*/z", "a/b/c/z"));
+    assert!(matches("**/target/**", "/home/me/proj/target/debug/app"));
+    assert!(!matches("/etc/*", "etc/hosts"));
+    assert!(Glob::new("[abc").is_err());
+    assert!(matches("*a*b*", "xaybz"));
+    assert!(!matches("*a*b", "xaybz"));
+    assert!(matches("a/**/b/**/c", "a/x/b/y/b/z/c"));
+
+    // Patterns that once backtracked exponentially finish at once.
+    let name = "a".repeat(64);
+    assert!(!matches(&format!("{}b", "a*".repeat(24)), &name));
+    let deep = vec!["a"; 64].join("/");
+    assert!(!matches(&format!("{}b", "a/**/".repeat(24)), &deep));
+
+    let policy = Policy::new(Effect::Allow)
+        .deny_prefix("/secrets")
+        .allow_glob("/secrets/public/*")
+        .unwrap();
+    let decision = policy.evaluate(&OsPath::from("/secrets/public/readme.md"));
+    assert_eq!(decision.effect(), Effect::Deny);
+    assert_eq!(decision.rule_index(), Some(0));
+    assert!(policy.is_allowed(&OsPath::from("/secretsauce")));
+}
+
+#[test]
+fn test_glob_set_agrees_with_globs() {
+    use os_path::{Glob, GlobSet};
+
+    let patterns = [
+        "*.txt",
+        "**",
+        "file?.[0-9]",
+        "file[!0-9].txt",
+        "[z-a]*",
+        "a/**/z",
+        "a/*/z",
+        "**/target/**",
+        "/etc/*",
+        "src/**",
+        "a+b(1).[.]*",
+    ];
+    let paths = [
+        "a/b/notes.txt",
+        "a/b/notes.txt.bak",
+        "file1.7",
+        "filex.txt",
+        "a/z",
+        "a/b/c/z",
+        "a/b/z",
+        "/home/me/proj/target/debug/app",
+        "/etc/hosts",
+        "etc/hosts",
+        "src/",
+        "src/lib.rs",
+        "a+b(1)..rs",
+        "",
+        "/",
+    ];
+    let set = GlobSet::new(patterns).unwrap();
+    assert_eq!(set.len(), patterns.len());
+    for path in paths.map(OsPath::from) {
+        let expected: Vec<usize> = patterns
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| Glob::new(p).unwrap().matches(&path))
+            .map(|(i, _)| i)
+            .collect();
+        assert_eq!(set.matching_indices(&path), expected, "{}", path);
+        assert_eq!(set.matches_any(&path), !expected.is_empty());
+    }
+    assert!(GlobSet::new(["ok", "[bad"]).is_err());
+}
+
+#[test]
+fn test_pathspec() {
+    use os_path::{Pathspec, PathspecError, PathspecSet};
+
+    let matches =
+        |spec: &str, path: &str| Pathspec::new(spec).unwrap().matches(&OsPath::from(path));
+    assert!(matches("src", "src/a/b.c"));
+    assert!(!matches("src", "srcs/b.c"));
+    assert!(matches("*.c", "src/a/b.c"));
+    assert!(!matches(":(glob)*.c", "src/a/b.c"));
+    assert!(matches(":(glob)**/*.c", "src/a/b.c"));
+    assert!(matches(":(glob)src/**/b.c", "src/b.c"));
+    assert!(matches(":(glob)src/**", "src/a/b.c"));
+    assert!(!matches(":(glob)s**/b.c", "src/a/b.c"));
+    assert!(matches(":(literal)a*b", "a*b/c"));
+    assert!(!matches(":(literal)a*b", "axb"));
+    assert!(matches("a\\*b", "a*b"));
+    assert!(!matches("a\\*b", "axb"));
+    assert!(matches("file[0-9].txt", "file7.txt"));
+    assert!(matches(":(icase)DOCS/Read*", "docs/readme.md"));
+    assert!(matches("build/", "build/"));
+    assert!(!matches("build/", "build"));
+    assert!(!matches("src", "/src"));
+    assert!(matches(":(glob)a/**/x/**/y", "a/1/x/2/x/3/y"));
+    assert!(!matches(":(glob)a/*/c", "a/b/b/c"));
+    assert!(matches("*a*b", "xa/yb"));
+
+    // Patterns that once backtracked exponentially finish at once.
+    let name = "a".repeat(60);
+    assert!(!matches(&format!("{}b", "*a".repeat(12)), &name));
+    assert!(!matches(&format!(":(glob){}b", "*a".repeat(12)), &name));
+    let deep = vec!["a"; 40].join("/");
+    assert!(!matches(&format!(":(glob){}b", "**/a/".repeat(12)), &deep));
+
+    let prefix = OsPath::from("lib/");
+    let in_lib = |spec: &str, path: &str| {
+        Pathspec::with_prefix(spec, &prefix)
+            .unwrap()
+            .matches(&OsPath::from(path))
+    };
+    assert!(in_lib("*.rs", "lib/x/y.rs"));
+    assert!(!in_lib("*.rs", "main.rs"));
+    assert!(in_lib(":/*.rs", "main.rs"));
+    assert!(in_lib("../bin/", "bin/tool/"));
+    assert!(in_lib(".", "lib/x"));
+    assert!(!in_lib(":(icase)foo", "LIB/foo"));
+
+    assert!(Pathspec::new(":!vendor").unwrap().is_exclude());
+    assert!(Pathspec::new(":(exclude)vendor").unwrap().is_exclude());
+    assert_eq!(
+        Pathspec::new(":(attr:x)a"),
+        Err(PathspecError::UnknownMagic("attr:x".to_string()))
+    );
+    assert_eq!(
+        Pathspec::new(":#a"),
+        Err(PathspecError::UnknownMagic("#".to_string()))
+    );
+    assert_eq!(Pathspec::new(":(top"), Err(PathspecError::Unclosed));
+    assert_eq!(
+        Pathspec::new(":(glob,literal)a"),
+        Err(PathspecError::Incompatible)
+    );
+    assert_eq!(Pathspec::new("../a"), Err(PathspecError::OutsideRepository));
+
+    let specs = PathspecSet::new([":^*.lock"]).unwrap();
+    assert!(specs.matches(&OsPath::from("src/main.rs")));
+    assert!(!specs.matches(&OsPath::from("Cargo.lock")));
+}
+
+#[test]
+fn test_editorconfig_sections() {
+    use os_path::EditorConfigGlob;
+
+    let matches = |section: &str, path: &str| {
+        EditorConfigGlob::new(section)
+            .unwrap()
+            .matches(&OsPath::from(path))
+    };
+    assert!(matches("*", "a/b/c.txt"));
+    assert!(matches("*.md", "README.md"));
+    assert!(!matches("/*.md", "docs/README.md"));
+    assert!(matches("docs/*.md", "docs/README.md"));
+    assert!(!matches("docs/*.md", "docs/api/README.md"));
+    assert!(matches("docs/**.md", "docs/api/README.md"));
+    assert!(matches("{src,tests}/**/*.rs", "tests/it/main.rs"));
+    assert!(matches("*.{js,{ts,tsx}}", "ui/app.tsx"));
+    assert!(matches("{single}.txt", "{single}.txt"));
+    assert!(matches("{a,}x", "x"));
+    assert!(matches("v{-3..3}", "v-2"));
+    assert!(!matches("v{-3..3}", "v4"));
+    assert!(matches("[Mm]akefile", "sub/makefile"));
+    assert!(!matches("[!M]akefile", "Makefile"));
+    assert!(matches("a\\*b", "a*b"));
+    assert!(!matches("a\\*b", "axb"));
+    assert!(matches("[unclosed", "[unclosed"));
+    assert!(matches("{unclosed", "{unclosed"));
+    assert!(!matches("*.md", "/abs/README.md"));
+}
+
+#[test]
+fn test_web_rules() {
+    use os_path::{Effect, ResolveError, WebRules};
+
+    let rules = WebRules::new()
+        .disallow("/a")
+        .unwrap()
+        .allow("/a")
+        .unwrap()
+        .disallow("/caf%C3%A9/")
+        .unwrap()
+        .disallow("")
+        .unwrap()
+        .disallow("tmp*$")
+        .unwrap();
+    assert_eq!(rules.evaluate(&OsPath::from("a/b")), Some(Effect::Allow));
+    assert!(!rules.is_allowed(&OsPath::from("café/menu.pdf")));
+    assert_eq!(rules.evaluate(&OsPath::from("b")), None);
+    assert!(!rules.is_allowed(&OsPath::from("tmp/x")));
+
+    let anchored = WebRules::new().disallow("/*.php$").unwrap();
+    assert!(!anchored.is_allowed(&OsPath::from("index.php")));
+    assert!(anchored.is_allowed(&OsPath::from("index.php/x")));
+
+    // A pattern that once backtracked exponentially finishes at once.
+    let slow = WebRules::new()
+        .disallow(&format!("/{}b", "*a".repeat(12)))
+        .unwrap();
+    assert!(slow.is_allowed(&OsPath::from("a".repeat(60))));
+    assert_eq!(
+        WebRules::new().allow("/%zz"),
+        Err(ResolveError::InvalidEncoding)
+    );
+}
+
+#[test]
+fn test_render_tree() {
+    use os_path::{render_tree_in, MemoryFs, PathFilter, TreeOptions};
+
+    let mut fs = MemoryFs::new();
+    fs.add_file("p/a/b/deep.rs", "")
+        .unwrap()
+        .add_file("p/a/x.rs", "")
+        .unwrap()
+        .add_file("p/a/x.md", "")
+        .unwrap()
+        .add_file("p/.hidden/y.rs", "")
+        .unwrap();
+
+    let options = TreeOptions::new()
+        .ascii(true)
+        .max_depth(2)
+        .filter(PathFilter::new().extensions(["rs"]));
+    let text = render_tree_in(&OsPath::from("p"), &options, &fs).unwrap();
+    let sep = std::path::MAIN_SEPARATOR;
+    assert_eq!(
+        text,
+        format!("p\n`-- a{sep}\n    |-- b{sep}\n    `-- x.rs\n")
+    );
+}
+
+#[test]
+fn test_rename_plan_execute() {
+    use os_path::{RenameError, RenamePlan};
+
+    let dir =
+        OsPath::from(std::env::temp_dir()).join(format!("os_path_rename_{}/", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("a"), "A").unwrap();
+    std::fs::write(dir.join("b"), "B").unwrap();
+    std::fs::write(dir.join("c"), "C").unwrap();
+
+    let mut plan = RenamePlan::new();
+    plan.add(dir.join("a"), dir.join("b"))
+        .add(dir.join("b"), dir.join("a"));
+    assert_eq!(plan.execute().unwrap().len(), 3);
+    assert_eq!(std::fs::read_to_string(dir.join("a")).unwrap(), "B");
+    assert_eq!(std::fs::read_to_string(dir.join("b")).unwrap(), "A");
+
+    let mut plan = RenamePlan::new();
+    plan.add(dir.join("a"), dir.join("c"));
+    assert!(matches!(plan.execute(), Err(RenameError::TargetExists(_))));
+
+    // A file already holding the first temporary name is passed over, not overwritten.
+    std::fs::write(dir.join("a.~rename0"), "T").unwrap();
+    std::fs::write(dir.join("b.~rename0"), "T").unwrap();
+    let mut plan = RenamePlan::new();
+    plan.add(dir.join("a"), dir.join("b"))
+        .add(dir.join("b"), dir.join("a"));
+    let ops = plan.execute().unwrap();
+    assert!(ops.iter().all(|op| op.to != dir.join("a.~rename0")));
+    assert_eq!(std::fs::read_to_string(dir.join("a")).unwrap(), "A");
+    assert_eq!(std::fs::read_to_string(dir.join("b")).unwrap(), "B");
+    assert_eq!(
+        std::fs::read_to_string(dir.join("a.~rename0")).unwrap(),
+        "T"
+    );
+    assert_eq!(
+        std::fs::read_to_string(dir.join("b.~rename0")).unwrap(),
+        "T"
+    );
+    std::fs::remove_file(dir.join("a.~rename0")).unwrap();
+    std::fs::remove_file(dir.join("b.~rename0")).unwrap();
+
+    let mut plan = RenamePlan::new();
+    plan.add(dir.join("a"), dir.join("x"))
+        .add(dir.join("missing"), dir.join("y"));
+    match plan.execute() {
+        Err(RenameError::Io(_, e)) => {
+            let context = os_path::PathError::from_io(&e).unwrap();
+            assert_eq!(context.path, dir.join("missing"));
+            assert_eq!(context.target, Some(dir.join("y")));
+        }
+        other => panic!("expected a failed rename, got {:?}", other),
+    }
+    assert!(dir.join("a").exists());
+    assert!(!dir.join("x").exists());
+
+    let mut plan = RenamePlan::new();
+    plan.add("a", "x").add("b", "x");
+    assert!(matches!(plan.ops(), Err(RenameError::DuplicateTarget(_))));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_fs_transaction() {
+    use os_path::FsTransaction;
+
+    let dir =
+        OsPath::from(std::env::temp_dir()).join(format!("os_path_txn_{}/", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("keep.txt"), "keep").unwrap();
+    std::fs::write(dir.join("gone.txt"), "gone").unwrap();
+
+    {
+        let mut txn = FsTransaction::new();
+        txn.create_dir_all(dir.join("new/nested/")).unwrap();
+        txn.copy(dir.join("keep.txt"), dir.join("new/nested/copy.txt"))
+            .unwrap();
+        txn.rename(dir.join("keep.txt"), dir.join("moved.txt"))
+            .unwrap();
+        txn.remove(dir.join("gone.txt")).unwrap();
+        assert!(txn.remove(dir.join("never.txt")).is_err());
+        // Dropped without commit.
+    }
+    assert!(!dir.join("new").exists());
+    assert!(!dir.join("moved.txt").exists());
+    assert_eq!(
+        std::fs::read_to_string(dir.join("keep.txt")).unwrap(),
+        "keep"
+    );
+    assert_eq!(
+        std::fs::read_to_string(dir.join("gone.txt")).unwrap(),
+        "gone"
+    );
+
+    let mut txn = FsTransaction::new();
+    txn.remove(dir.join("gone.txt")).unwrap();
+    txn.write(dir.join("keep.txt"), "changed").unwrap();
+    txn.commit().unwrap();
+    assert!(!dir.join("gone.txt").exists());
+    assert_eq!(
+        std::fs::read_to_string(dir.join("keep.txt")).unwrap(),
+        "changed"
+    );
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+    // Failures name the paths involved.
+    use os_path::PathError;
+    let mut txn = FsTransaction::new();
+    let err = txn.write(dir.join("no/file.txt"), "").unwrap_err();
+    assert_eq!(
+        PathError::from_io(&err).unwrap().path,
+        dir.join("no/file.txt")
+    );
+    let err = txn
+        .rename(dir.join("never.txt"), dir.join("moved.txt"))
+        .unwrap_err();
+    let context = PathError::from_io(&err).unwrap();
+    assert_eq!(context.path, dir.join("never.txt"));
+    assert_eq!(context.target, Some(dir.join("moved.txt")));
+    assert!(err.to_string().contains(&format!(
+        "{} to {}",
+        dir.join("never.txt"),
+        dir.join("moved.txt")
+    )));
+    txn.rollback().unwrap();
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_copy_tree_to() {
+    use os_path::{CopyOptions, LimitExceeded, Overwrite, PathFilter, TraversalLimits};
+
+    let tmp =
+        OsPath::from(std::env::temp_dir()).join(format!("os_path_copy_{}/", std::process::id()));
+    std::fs::create_dir_all(tmp.join("src/docs/")).unwrap();
+    std::fs::create_dir_all(tmp.join("dest/")).unwrap();
+    std::fs::write(tmp.join("src/main.rs"), "fn main() {}").unwrap();
+    std::fs::write(tmp.join("src/docs/notes.md"), "notes").unwrap();
+    std::fs::write(tmp.join("dest/main.rs"), "old").unwrap();
+
+    let src = tmp.join("src/");
+    let dest = tmp.join("dest/");
+    let err = src
+        .copy_tree_to(&dest, &CopyOptions::new(), |_, _, _| {})
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+
+    let options = CopyOptions::new()
+        .overwrite(Overwrite::Skip)
+        .filter(PathFilter::new().extensions(["md"]));
+    let mut calls = Vec::new();
+    let copied = src
+        .copy_tree_to(&dest, &options, |p, done, total| {
+            calls.push((p.name().unwrap().clone(), done, total))
+        })
+        .unwrap();
+    assert_eq!(copied, 5);
+    assert_eq!(calls, vec![("notes.md".to_string(), 5, 5)]);
+    assert_eq!(
+        std::fs::read_to_string(dest.join("main.rs")).unwrap(),
+        "old"
+    );
+
+    let options = CopyOptions::new().overwrite(Overwrite::Always);
+    assert_eq!(src.copy_tree_to(&dest, &options, |_, _, _| {}).unwrap(), 17);
+    assert_eq!(
+        std::fs::read_to_string(dest.join("main.rs")).unwrap(),
+        "fn main() {}"
+    );
+
+    let options = CopyOptions::new()
+        .overwrite(Overwrite::Always)
+        .limits(TraversalLimits::new().max_bytes(16));
+    let err = src
+        .copy_tree_to(tmp.join("limited/"), &options, |_, _, _| {})
+        .unwrap_err();
+    assert_eq!(
+        LimitExceeded::from_io(&err),
+        Some(&LimitExceeded::Bytes(16))
+    );
+    assert!(!tmp.join("limited/").to_path().exists());
+
+    std::fs::remove_dir_all(&tmp).unwrap();
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_async_queries() {
+    let tmp =
+        OsPath::from(std::env::temp_dir()).join(format!("os_path_async_{}/", std::process::id()));
+    std::fs::create_dir_all(tmp.join("sub/")).unwrap();
+    std::fs::write(tmp.join("file.txt"), "abc").unwrap();
+
+    assert!(tmp.exists_async().await);
+    assert!(!tmp.join("missing").exists_async().await);
+    assert_eq!(
+        tmp.join("file.txt").metadata_async().await.unwrap().len(),
+        3
+    );
+    assert_eq!(
+        tmp.read_dir_async().await.unwrap(),
+        vec![tmp.join("file.txt"), tmp.join("sub/")]
+    );
+    let canonical = tmp.join("sub/../sub").canonicalize_async().await.unwrap();
+    assert!(canonical.is_dir());
+    assert_eq!(canonical, tmp.join("sub/").canonicalize().unwrap());
+    assert!(tmp.join("missing/").read_dir_async().await.is_err());
+
+    std::fs::remove_dir_all(&tmp).unwrap();
+}
+
+#[test]
+fn test_flavors() {
+    use os_path::Flavor;
+
+    let win = OsPath::parse_as(Flavor::Windows, "d:/builds\\app/bin/");
+    assert!(win.is_absolute() && win.is_dir());
+    assert_eq!(win.to_string_as(Flavor::Windows), "D:\\builds\\app\\bin\\");
+    assert_eq!(win.to_string_as(Flavor::Posix), "D:/builds/app/bin/");
+    assert_eq!(
+        win.join("../lib/core.dll").to_string_as(Flavor::Windows),
+        "D:\\builds\\app\\lib\\core.dll"
+    );
+    assert_eq!(
+        win.parent().unwrap().to_string_as(Flavor::Windows),
+        "D:\\builds\\app\\"
+    );
+    assert_eq!(
+        OsPath::parse_as(Flavor::Windows, "C:").to_string_as(Flavor::Windows),
+        "C:\\"
+    );
+    assert!(!OsPath::parse_as(Flavor::Windows, "\\temp").is_absolute());
+
+    let posix = OsPath::parse_as(Flavor::Posix, "logs/2024/");
+    assert!(!posix.is_absolute() && posix.is_dir());
+    assert_eq!(posix.to_string_as(Flavor::Windows), "logs\\2024\\");
+    assert_eq!(
+        OsPath::parse_as(Flavor::Posix, "/").to_string_as(Flavor::Posix),
+        "/"
+    );
+
+    let native = OsPath::from("a/b.txt");
+    assert_eq!(
+        OsPath::parse_as(Flavor::native(), &native.to_string_as(Flavor::native())),
+        native
+    );
+}
+
+#[test]
+fn test_path_errors() {
+    use os_path::{Algorithm, PathError, TraversalLimits};
+    use std::error::Error;
+    use std::io::ErrorKind;
+
+    let missing = OsPath::from(std::env::temp_dir()).join("os_path_no_such_dir/");
+    let err = missing.walk().next().unwrap().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+    let context = PathError::from_io(&err).unwrap();
+    assert_eq!(context.path, missing);
+    assert_eq!(context.op, "read directory");
+    assert!(err.to_string().contains(&missing.to_string()));
+
+    let file = missing.join("a.txt");
+    for (err, op) in [
+        (file.metadata().unwrap_err(), "read metadata of"),
+        (file.len().unwrap_err(), "read metadata of"),
+        (file.hash_file(Algorithm::Xxh3).unwrap_err(), "open"),
+        (file.canonicalize().unwrap_err(), "canonicalize"),
+        (file.write_atomic("x").unwrap_err(), "write"),
+    ] {
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+        let context = PathError::from_io(&err).unwrap();
+        assert_eq!((context.op, &context.path), (op, &file));
+        assert_eq!(
+            context.source().unwrap().to_string(),
+            context.source.to_string()
+        );
+    }
+
+    let size = OsPath::from("src/").dir_size(TraversalLimits::new().max_bytes(1));
+    assert!(PathError::from_io(&size.unwrap_err()).is_none());
+}
+
+#[test]
+fn test_encode_components() {
+    for text in ["/srv/a b/ü?.txt", "rel/#hash/", "x=1&y=2", "/"] {
+        let path = OsPath::from(text);
+        let encoded = path.encode_components();
+        assert!(encoded
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"-._~%/".contains(&b)));
+        assert_eq!(OsPath::decode_components(&encoded).unwrap(), path);
+    }
+    assert_eq!(OsPath::from("x=1&y=2").encode_components(), "x%3D1%26y%3D2");
+    assert!(OsPath::decode_components("%zz").is_err());
+}
+
+#[test]
+fn test_file_urls() {
+    use os_path::FileUrlError;
+
+    let dir = OsPath::from(std::env::temp_dir()).join("naïve café/#1 [draft]/");
+    let url = dir.to_file_url().unwrap();
+    assert!(url.ends_with("/na%C3%AFve%20caf%C3%A9/%231%20%5Bdraft%5D/"));
+    assert_eq!(OsPath::from_file_url(&url).unwrap(), dir);
+    assert_eq!(
+        OsPath::from_file_url(&format!("{}notes.txt?v=1#top", url)).unwrap(),
+        dir.join("notes.txt")
+    );
+
+    #[cfg(unix)]
+    {
+        assert_eq!(OsPath::from("/").to_file_url().unwrap(), "file:///");
+        assert_eq!(OsPath::from_file_url("FILE:/").unwrap(), OsPath::from("/"));
+        assert_eq!(
+            OsPath::from_file_url("file:/tmp/a").unwrap(),
+            OsPath::from("/tmp/a")
+        );
+    }
+    assert_eq!(
+        OsPath::from_file_url("file:relative/path"),
+        Err(FileUrlError::NotFileUrl)
+    );
+    assert_eq!(
+        OsPath::from_file_url("file:///bad%zz"),
+        Err(FileUrlError::InvalidEncoding)
+    );
+    assert_eq!(
+        OsPath::from_file_url("file:///nul%00"),
+        Err(FileUrlError::InvalidEncoding)
+    );
+}
+
+#[test]
+fn test_dir_index() {
+    use os_path::{DirIndex, WatchEvent};
+
+    let tmp =
+        OsPath::from(std::env::temp_dir()).join(format!("os_path_index_{}/", std::process::id()));
+    std::fs::create_dir_all(tmp.join("docs/")).unwrap();
+    std::fs::write(tmp.join("docs/a.md"), "a").unwrap();
+    std::fs::write(tmp.join("b.txt"), "b").unwrap();
+
+    let mut index = DirIndex::new();
+    assert_eq!(index.rescan(&tmp).unwrap().len(), 3);
+    assert_eq!(
+        index.get(&OsPath::from("docs/a.md")).map(|e| e.len),
+        Some(1)
+    );
+    assert!(index.paths().contains(&OsPath::from("docs/")));
+
+    std::fs::remove_dir_all(tmp.join("docs/")).unwrap();
+    std::fs::write(tmp.join("b.txt"), "bigger").unwrap();
+    assert_eq!(
+        index.rescan(&tmp).unwrap(),
+        vec![
+            WatchEvent::Modified(tmp.join("b.txt")),
+            WatchEvent::Removed(tmp.join("docs/")),
+            WatchEvent::Removed(tmp.join("docs/a.md")),
+        ]
+    );
+    assert_eq!(index, DirIndex::scan(&tmp).unwrap());
+    assert!(index.rescan(&tmp.join("missing/")).is_err());
+    assert_eq!(index.len(), 1);
+
+    #[cfg(feature = "json")]
+    {
+        let saved = tmp.join("index.json");
+        index.rescan(&tmp).unwrap();
+        saved.write_json(&index).unwrap();
+        let mut loaded: DirIndex = saved.read_json().unwrap();
+        assert_eq!(loaded, index);
+        assert_eq!(
+            loaded.rescan(&tmp).unwrap(),
+            vec![WatchEvent::Created(saved.clone())]
+        );
+    }
+
+    std::fs::remove_dir_all(&tmp).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_dir_index_non_unicode_names() {
+    use os_path::{DirIndex, MemoryFs, WatchEvent};
+    use std::os::unix::ffi::OsStrExt;
+
+    // Both names display as `x\u{fffd}`, but they are different files and must be indexed apart.
+    let root = OsPath::from("/t/");
+    let odd = |bytes: &[u8]| OsPath::from(std::path::Path::new(std::ffi::OsStr::from_bytes(bytes)));
+    let (fe, ff) = (odd(b"x\xfe"), odd(b"x\xff"));
+
+    let mut fs = MemoryFs::new();
+    fs.add_file(root.join(&fe), "fe")
+        .unwrap()
+        .add_file(root.join(&ff), "ff!")
+        .unwrap();
+    let mut index = DirIndex::scan_in(&root, &fs).unwrap();
+    assert_eq!(index.len(), 2);
+    assert_eq!(index.get(&fe).map(|e| e.len), Some(2));
+    assert_eq!(index.get(&ff).map(|e| e.len), Some(3));
+    assert_eq!(index.paths(), vec![fe.clone(), ff.clone()]);
+
+    fs.add_file(root.join(&ff), "ff!!").unwrap();
+    assert_eq!(
+        index.rescan_in(&root, &fs).unwrap(),
+        vec![WatchEvent::Modified(root.join(&ff))]
+    );
+}
+
+#[test]
+fn test_par_walk() {
+    use os_path::{PathFilter, WalkOrder};
+
+    let tmp = OsPath::from(std::env::temp_dir())
+        .join(format!("os_path_par_walk_{}/", std::process::id()));
+    for dir in ["a/b/c/", "a/d/", "e/", "f/g/"] {
+        std::fs::create_dir_all(tmp.join(dir)).unwrap();
+        for n in 0..3 {
+            std::fs::write(tmp.join(dir).join(format!("{}.txt", n)), "").unwrap();
+        }
+    }
+    std::fs::write(tmp.join("f/g/skip.log"), "").unwrap();
+
+    let serial: Vec<OsPath> = tmp.walk().map(Result::unwrap).collect();
+    let sorted: Vec<OsPath> = tmp
+        .par_walk()
+        .threads(3)
+        .order(WalkOrder::Sorted)
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(sorted, serial);
+
+    let mut unordered: Vec<OsPath> = tmp.par_walk().map(Result::unwrap).collect();
+    unordered.sort();
+    assert_eq!(unordered, serial);
+
+    let filter = PathFilter::new().extensions(["log"]);
+    let logs: Vec<OsPath> = tmp
+        .par_walk()
+        .with_filter(filter)
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(logs, vec![tmp.join("f/g/skip.log")]);
+    assert_eq!(tmp.par_walk().max_depth(1).count(), 3);
+    assert_eq!(tmp.par_walk().max_depth(0).count(), 0);
+    assert!(tmp.join("missing/").par_walk().next().unwrap().is_err());
+
+    std::fs::remove_dir_all(&tmp).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_read_dir() {
+    let tmp = OsPath::from(std::env::temp_dir())
+        .join(format!("os_path_read_dir_{}/", std::process::id()));
+    std::fs::create_dir_all(tmp.join("sub.d/")).unwrap();
+    std::fs::write(tmp.join("Makefile"), "").unwrap();
+    std::os::unix::fs::symlink(tmp.join("sub.d/"), tmp.join("linked")).unwrap();
+
+    let mut entries: Vec<OsPath> = OsPath::from(tmp.to_string().trim_end_matches('/'))
+        .read_dir()
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    entries.sort();
+    assert_eq!(
+        entries,
+        vec![
+            tmp.join("Makefile"),
+            tmp.join("linked/"),
+            tmp.join("sub.d/")
+        ]
+    );
+    assert!(entries[0].is_file());
+    assert!(entries[2].is_dir());
+    assert!(tmp.join("missing/").read_dir().is_err());
+
+    std::fs::remove_dir_all(&tmp).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_link_cycles() {
+    use os_path::{CopyOptions, LinkCycle, Symlinks};
+
+    let tmp =
+        OsPath::from(std::env::temp_dir()).join(format!("os_path_cycle_{}/", std::process::id()));
+    let src = tmp.join("src/");
+    std::fs::create_dir_all(src.join("a/b/")).unwrap();
+    std::fs::create_dir_all(tmp.join("shared/")).unwrap();
+    std::fs::write(tmp.join("shared/data.txt"), "data").unwrap();
+    std::os::unix::fs::symlink(src.join("a/"), src.join("a/b/loop")).unwrap();
+    std::os::unix::fs::symlink(tmp.join("shared/"), src.join("a/shared")).unwrap();
+
+    let plain: Vec<OsPath> = src.walk().map(Result::unwrap).collect();
+    assert!(plain.contains(&src.join("a/b/loop")));
+
+    let mut found = Vec::new();
+    let mut cycles = Vec::new();
+    for result in src.walk().follow_links(true) {
+        match result {
+            Ok(path) => found.push(path),
+            Err(e) => cycles.push(LinkCycle::from_io(&e).unwrap().clone()),
+        }
+    }
+    assert!(found.contains(&src.join("a/shared/data.txt")));
+    assert!(found.contains(&src.join("a/b/loop/")));
+    assert_eq!(cycles.len(), 1);
+    assert_eq!(cycles[0].path(), &src.join("a/b/loop/"));
+    assert_eq!(cycles[0].target(), &src.join("a/"));
+
+    let err = src
+        .copy_tree_to(tmp.join("copy/"), &CopyOptions::new(), |_, _, _| {})
+        .unwrap_err();
+    assert_eq!(
+        LinkCycle::from_io(&err).map(|c| c.path()),
+        Some(&src.join("a/b/loop/"))
+    );
+    assert!(!tmp.join("copy/").to_path().exists());
+
+    let options = CopyOptions::new().symlinks(Symlinks::Preserve);
+    src.copy_tree_to(tmp.join("copy/"), &options, |_, _, _| {})
+        .unwrap();
+    assert!(tmp.join("copy/a/b/loop").to_path().is_symlink());
+
+    std::fs::remove_dir_all(&tmp).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_link_tree() {
+    use os_path::{link_tree, relink_tree, unlink_tree, LinkError, LinkOptions};
+
+    let tmp =
+        OsPath::from(std::env::temp_dir()).join(format!("os_path_link_{}/", std::process::id()));
+    let src = tmp.join("pkg/");
+    let dest = tmp.join("target/");
+    std::fs::create_dir_all(src.join(".config/app/")).unwrap();
+    std::fs::create_dir_all(&dest).unwrap();
+    std::fs::write(src.join(".config/app/settings"), "").unwrap();
+    std::fs::write(src.join(".profile"), "").unwrap();
+    std::fs::write(dest.join(".profile"), "mine").unwrap();
+
+    let options = LinkOptions::new();
+    match link_tree(&src, &dest, &options) {
+        Err(LinkError::Conflicts(paths)) => assert_eq!(paths, vec![dest.join(".profile")]),
+        other => panic!("expected a conflict, got {:?}", other),
+    }
+    assert!(!dest.join(".config").exists());
+
+    std::fs::remove_file(dest.join(".profile")).unwrap();
+    assert_eq!(link_tree(&src, &dest, &options).unwrap().len(), 2);
+    assert!(link_tree(&src, &dest, &options).unwrap().is_empty());
+    assert_eq!(
+        std::fs::read_link(dest.join(".profile")).unwrap(),
+        src.join(".profile").to_pathbuf()
+    );
+
+    std::fs::remove_file(src.join(".profile")).unwrap();
+    std::fs::write(src.join(".bashrc"), "").unwrap();
+    assert_eq!(relink_tree(&src, &dest, &options).unwrap().len(), 2);
+    assert!(dest.join(".bashrc").exists());
+    assert!(std::fs::symlink_metadata(dest.join(".profile")).is_err());
+
+    assert_eq!(unlink_tree(&src, &dest, &options).unwrap().len(), 2);
+    assert_eq!(std::fs::read_dir(&dest).unwrap().count(), 0);
+
+    // Relative links reach names that are not Unicode by their real bytes.
+    use std::os::unix::ffi::OsStrExt;
+    let odd = std::ffi::OsStr::from_bytes(b"caf\xe9");
+    std::fs::write(src.to_path().join(odd), "").unwrap();
+    let options = LinkOptions::new().relative(true);
+    assert_eq!(link_tree(&src, &dest, &options).unwrap().len(), 3);
+    let target = std::fs::read_link(dest.to_path().join(odd)).unwrap();
+    assert_eq!(target, std::path::Path::new("../pkg").join(odd));
+    assert!(dest.to_path().join(odd).exists());
+
+    std::fs::remove_dir_all(&tmp).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_stale_lockfile() {
+    let path =
+        OsPath::from(std::env::temp_dir()).join(format!("os_path_lock_{}.db", std::process::id()));
+    let lock = path.try_acquire_lockfile().unwrap();
+    let contents = std::fs::read_to_string(lock.path()).unwrap();
+    let host = contents.lines().nth(1).unwrap().to_string();
+    lock.release().unwrap();
+
+    // A lock left by a process that no longer exists is taken over.
+    std::fs::write(path.lock_path(), format!("{}\n{}\n", u32::MAX, host)).unwrap();
+    let lock = path.try_acquire_lockfile().unwrap();
+    assert!(std::fs::read_to_string(lock.path())
+        .unwrap()
+        .starts_with(&std::process::id().to_string()));
+    drop(lock);
+    assert!(!path.lock_path().exists());
+
+    // So is one left by a process that has exited, while one held by a live process is not.
+    let mut child = std::process::Command::new("true").spawn().unwrap();
+    let gone = child.id();
+    child.wait().unwrap();
+    std::fs::write(path.lock_path(), format!("{}\n{}\n", gone, host)).unwrap();
+    let lock = path.try_acquire_lockfile().unwrap();
+    drop(lock);
+    std::fs::write(path.lock_path(), format!("1\n{}\n", host)).unwrap();
+    assert!(matches!(
+        path.try_acquire_lockfile(),
+        Err(os_path::LockError::Held(Some(_)))
+    ));
+    assert!(path.lock_path().exists());
+    std::fs::remove_file(path.lock_path()).unwrap();
+}
+
+#[test]
+fn test_concurrent_atomic_writes() {
+    let path =
+        OsPath::from(std::env::temp_dir()).join(format!("os_path_atomic_{}/", std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+    std::fs::create_dir_all(&path).unwrap();
+    let file = path.join("shared.txt");
+
+    // Threads of one process each get a temporary file of their own, so every write lands whole.
+    let contents: Vec<String> = (0..8).map(|i| i.to_string().repeat(4096)).collect();
+    std::thread::scope(|scope| {
+        for text in &contents {
+            let file = &file;
+            scope.spawn(move || {
+                for _ in 0..20 {
+                    file.write_atomic(text).unwrap();
+                }
+            });
+        }
+    });
+    assert!(contents.contains(&std::fs::read_to_string(&file).unwrap()));
+    assert_eq!(std::fs::read_dir(&path).unwrap().count(), 1);
+
+    std::fs::remove_dir_all(&path).unwrap();
+}
+
+#[test]
+fn test_timestamped_backups() {
+    use os_path::BackupPolicy;
+
+    let dir =
+        OsPath::from(std::env::temp_dir()).join(format!("os_path_backup_{}/", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("notes.txt");
+    let policy = BackupPolicy::new().timestamped(true).keep(2);
+
+    assert_eq!(path.backup_then_write("v1", &policy).unwrap(), None);
+    for version in ["v2", "v3", "v4"] {
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        path.backup_then_write(version, &policy).unwrap().unwrap();
+    }
+    let mut backups: Vec<String> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().into_string().unwrap())
+        .filter(|n| n.ends_with(".bak"))
+        .collect();
+    backups.sort();
+    assert_eq!(backups.len(), 2);
+    assert_eq!(
+        std::fs::read_to_string(dir.join(&backups[0])).unwrap(),
+        "v2"
+    );
+    assert_eq!(
+        std::fs::read_to_string(dir.join(&backups[1])).unwrap(),
+        "v3"
+    );
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "v4");
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_sharded_layout() {
+    use os_path::Flavor;
+    use xxhash_rust::xxh3::xxh3_128;
+
+    let mut roots = vec![
+        OsPath::from("objects/"),
+        OsPath::from("/var/cache/objects/"),
+    ];
+    if cfg!(windows) {
+        roots.push(OsPath::from(r"C:\objects\"));
+        roots.push(OsPath::from(r"\\server\share\objects\"));
+    }
+    for root in &roots {
+        let path = root.sharded("0123abcd.blob", 2, 2).unwrap();
+        assert_eq!(path.parent().unwrap(), root.join("01/23/"));
+        assert_eq!(root.unsharded(&path, 2, 2).unwrap(), "0123abcd.blob");
+        assert_eq!(root.unsharded(&path, 1, 2), None);
+        assert_eq!(
+            root.unsharded(&root.join("01/24/0123abcd.blob"), 2, 2),
+            None
+        );
+
+        // The portable hash is the hash of the path as a Posix path, whatever its root.
+        let posix = path.to_string_as(Flavor::Posix);
+        assert_eq!(
+            path.portable_hash(),
+            xxh3_128(posix.as_bytes()),
+            "{}",
+            posix
+        );
+    }
+    let root = OsPath::from("objects/");
+    assert_eq!(root.sharded(".", 0, 0), None);
+    assert_eq!(root.sharded("..", 0, 0), None);
+    assert_eq!(root.sharded("ab", 2, 2), None);
+    assert_eq!(root.sharded("ab/cd", 1, 1), None);
+    assert_eq!(root.sharded("abc", 0, 0).unwrap(), root.join("abc"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_hash_non_unicode_paths() {
+    use os_path::{MemoryFs, SnapshotOptions};
+    use std::os::unix::ffi::OsStrExt;
+
+    // Both names display as `x\u{fffd}`, but they are different files and must hash and list apart.
+    let root = OsPath::from("/t/");
+    let odd = |bytes: &[u8]| root.join(std::ffi::OsStr::from_bytes(bytes));
+    let (fe, ff) = (odd(b"x\xfe"), odd(b"x\xff"));
+    assert_eq!(fe.to_string(), ff.to_string());
+    assert_ne!(fe.portable_hash(), ff.portable_hash());
+    assert_eq!(
+        OsPath::from("/t/a.txt").portable_hash(),
+        root.join("a.txt").portable_hash()
+    );
+
+    let mut fs = MemoryFs::new();
+    fs.add_file(&fe, "").unwrap().add_file(&ff, "").unwrap();
+    let snapshot = root.tree_snapshot_in(SnapshotOptions::new(), &fs).unwrap();
+    assert_eq!(snapshot.entries().len(), 2);
+    assert_ne!(snapshot.entries()[0].path, snapshot.entries()[1].path);
+}
+
+#[cfg(any(feature = "sha256", feature = "blake3"))]
+#[test]
+fn test_hash_file() {
+    use os_path::Algorithm;
+
+    let path =
+        OsPath::from(std::env::temp_dir()).join(format!("os_path_hash_{}.txt", std::process::id()));
+    std::fs::write(&path, "abc").unwrap();
+    #[cfg(feature = "sha256")]
+    assert_eq!(
+        path.hash_file(Algorithm::Sha256).unwrap().to_hex(),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    #[cfg(feature = "blake3")]
+    assert_eq!(
+        path.hash_file(Algorithm::Blake3).unwrap().to_hex(),
+        "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85"
+    );
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_read_json_invalid() {
+    let path =
+        OsPath::from(std::env::temp_dir()).join(format!("os_path_bad_{}.json", std::process::id()));
+    std::fs::write(&path, "{ not json").unwrap();
+    let err = path.read_json::<serde::de::IgnoredAny>().unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert!(err.to_string().starts_with(&path.to_string()));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_test_tree() {
+    use os_path::TestTree;
+
+    let tree = TestTree::new()
+        .file("a/b.txt", "contents")
+        .dir("c")
+        .build()
+        .unwrap();
+    let found: Vec<String> = tree
+        .walk()
+        .map(|p| p.unwrap().name().unwrap().clone())
+        .collect();
+    assert_eq!(found, vec!["a", "b.txt", "c"]);
+    assert!(TestTree::new().file("../escape.txt", "").build().is_err());
+}
+
+#[test]
+fn test_path_assertions() {
+    use os_path::{assert_paths_eq, assert_paths_ne, TestTree};
+
+    let tree = TestTree::new().file("pkg/lib.rs", "").build().unwrap();
+    let found = tree.walk().last().unwrap().unwrap();
+    assert_paths_eq!(found, tree.join("pkg/sub/../lib.rs"));
+    assert_paths_eq!(&found, tree.join("pkg/./lib.rs"), "walking {}", tree.path());
+    assert_paths_ne!(found, tree.join("pkg/"));
+
+    let message = std::panic::catch_unwind(|| {
+        assert_paths_eq!("/srv/www/a.txt", "srv/www/b/a.txt", "case {}", 1);
+    })
+    .unwrap_err()
+    .downcast::<String>()
+    .unwrap();
+    assert_eq!(
+        *message,
+        "assertion failed: paths are not equal: case 1\n   left: /srv/www/a.txt\n  right: srv/www/b/a.txt\n  \
+         left is absolute, right is relative\n    [0] srv\n    [1] www\n  - [2] a.txt\n  + [2] b\n  + [3] a.txt\n"
+    );
+
+    let message = std::panic::catch_unwind(|| assert_paths_ne!("a/./b", "a/b"))
+        .unwrap_err()
+        .downcast::<String>()
+        .unwrap();
+    assert_eq!(*message, "assertion failed: paths are equal\n  both: a/./b");
+}
+
+#[test]
+fn test_surplus_parent_components() {
+    let path = OsPath::from("a/b.txt").join("../../../c/");
+    assert_eq!(path.leading_parent_count(), 2);
+    assert!(path.is_dir());
+
+    let mut path = OsPath::from("../x/../../y");
+    path.resolve();
+    assert_eq!(path.leading_parent_count(), 2);
+    assert_eq!(path.name().unwrap(), "y");
+
+    let mut path = OsPath::from("/x/../../y");
+    path.resolve();
+    assert!(path.is_absolute());
+    assert_eq!(path.leading_parent_count(), 0);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_decode_options() {
+    use os_path::{DecodeOptions, InvalidBytes};
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    // A replacement character must stay inside its component.
+    let replace = DecodeOptions::new().invalid(InvalidBytes::Replace);
+    let lossy = OsPath::from_os_str_with(OsStr::from_bytes(b"dir/a\x80b"), &replace).unwrap();
+    assert_eq!(lossy.name().unwrap(), "a\u{FFFD}b");
+    assert_eq!(lossy.parent().unwrap().to_string(), "dir/");
+
+    // Escaping keeps names that differ only in invalid bytes apart.
+    let escape = DecodeOptions::new().invalid(InvalidBytes::PercentEscape);
+    let a = OsPath::from_os_str_with(OsStr::from_bytes(b"a\x80\x81"), &escape).unwrap();
+    let b = OsPath::from_os_str_with(OsStr::from_bytes(b"a\x81\x80"), &escape).unwrap();
+    assert_eq!(a.to_string(), "a%80%81");
+    assert_ne!(a, b);
+
+    let valid = DecodeOptions::new().invalid(InvalidBytes::Error);
+    assert!(OsPath::from_os_str_with("plain/name.txt", &valid).is_ok());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_non_unicode_names_round_trip() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let raw = OsStr::from_bytes(b"/tmp/\xff\xfe/na\xefve \xef\xbf\xbd.txt");
+    let path = OsPath::from(std::path::Path::new(raw));
+    assert_eq!(path.as_os_str(), raw);
+    assert_eq!(path.to_os_string(), raw.to_os_string());
+
+    // Two names that differ only in invalid bytes stay apart.
+    let other = OsPath::from(std::path::Path::new(OsStr::from_bytes(b"/tmp/\xff\xfd/")));
+    assert_ne!(path.parent().unwrap(), other);
+
+    let mut dir = path.parent().unwrap();
+    dir.push("x/../other/");
+    dir.resolve();
+    assert_eq!(dir.as_os_str(), OsStr::from_bytes(b"/tmp/\xff\xfe/other"));
+
+    // A real U+FFFD is an ordinary character, not a separator.
+    let real = OsPath::from("dir/a\u{FFFD}b.txt");
+    assert_eq!(real.name().unwrap(), "a\u{FFFD}b.txt");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_nul_cannot_forge_an_escape() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    // Text holding the spelling of an escape keeps its NULs, so it names neither `..` nor a separator.
+    let dots = OsPath::from("a/\u{0}2E\u{0}2E/x");
+    assert_eq!(dots.components().count(), 3);
+    assert_eq!(dots.as_os_str(), OsStr::from_bytes(b"a/\x002E\x002E/x"));
+    let slash = OsPath::from("safe\u{0}2Fetc");
+    assert_eq!(slash.components().count(), 1);
+    assert_eq!(slash.as_os_str(), OsStr::from_bytes(b"safe\x002Fetc"));
+
+    // The same holds for text added after parsing.
+    let concat = OsPath::from("a").concat("\u{0}2Fb");
+    assert_eq!(concat.as_os_str(), OsStr::from_bytes(b"a\x002Fb"));
+    let ext = OsPath::from("a.txt").with_extension("\u{0}2Fb");
+    assert_eq!(ext.as_os_str(), OsStr::from_bytes(b"a.\x002Fb"));
+
+    // A real invalid byte next to a NUL still round-trips.
+    let raw = OsStr::from_bytes(b"x\xff\x00y");
+    assert_eq!(OsPath::from(std::path::Path::new(raw)).as_os_str(), raw);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_escaped_parent_cannot_pass_checks() {
+    use os_path::{Effect, MountTable, PathIterExt, Policy, PrefixRouter};
+    use std::path::Component;
+
+    let climbs = |path: &OsPath| {
+        path.to_path()
+            .components()
+            .any(|c| c == Component::ParentDir)
+    };
+    let dots = "\u{0}2E\u{0}2E";
+
+    // Every check sees the forged `..` as the plain name the filesystem sees, so none can be told one thing while the
+    // real path does another.
+    let path = OsPath::from(format!("a/{0}/{0}/x", dots));
+    assert_eq!(path.max_escape_depth(), 0);
+    assert!(!climbs(&path));
+
+    let base = OsPath::from("/srv/www/");
+    let inside = OsPath::from(format!("/srv/www/{0}/{0}/etc/passwd", dots));
+    let tail = inside.strip_prefix(&base).unwrap();
+    assert!(!climbs(&tail));
+    assert!(inside.to_path().starts_with(base.to_path()));
+    assert_eq!(vec![inside.clone()].into_iter().under(&base).count(), 1);
+
+    let mut mounts = MountTable::new();
+    mounts.mount("assets://", "/opt/assets/");
+    let real = mounts
+        .resolve(&format!("assets://{0}/{0}/etc/passwd", dots))
+        .unwrap();
+    assert!(real.to_path().starts_with("/opt/assets"));
+    assert!(!climbs(&real));
+
+    let policy = Policy::new(Effect::Deny).allow_prefix("/srv/www");
+    assert!(policy.is_allowed(&inside));
+    assert!(!climbs(&inside));
+    let slash = OsPath::from("/srv\u{0}2Fwww/secret");
+    assert!(!policy.is_allowed(&slash));
+    assert!(!slash.to_path().starts_with("/srv/www"));
+
+    let mut router = PrefixRouter::new();
+    router.insert("/", "root");
+    router.insert("/static", "files");
+    assert_eq!(
+        router.lookup(&OsPath::from(format!("/static/{}/admin", dots))),
+        Some(&"files")
+    );
+    assert_eq!(
+        router.lookup(&OsPath::from("/static\u{0}2Fadmin")),
+        Some(&"root")
+    );
+}
+
+#[test]
+fn test_overlay_stays_inside_layers() {
+    use os_path::{MemoryFs, Overlay};
+
+    let mut fs = MemoryFs::new();
+    fs.add_file("/game/data/a.txt", "a")
+        .unwrap()
+        .add_file("/game/secrets.txt", "s")
+        .unwrap()
+        .add_file("/etc/passwd", "root")
+        .unwrap();
+    let overlay = Overlay::new().layer("/game/data/");
+
+    assert_eq!(
+        overlay.resolve_in("sub/../a.txt", &fs),
+        Some(OsPath::from("/game/data/a.txt"))
+    );
+    assert_eq!(overlay.resolve_in("../secrets.txt", &fs), None);
+    assert_eq!(overlay.resolve_in("a/../../secrets.txt", &fs), None);
+    assert_eq!(overlay.resolve_in("../../etc/passwd", &fs), None);
+    assert_eq!(overlay.list_merged_in("sub/../", &fs).unwrap().len(), 1);
+    assert!(overlay.list_merged_in("../", &fs).unwrap().is_empty());
+    assert!(overlay
+        .list_merged_in("../../etc/", &fs)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_relative_to() {
+    let rel = |a: &str, b: &str| {
+        OsPath::from(a)
+            .relative_to(&OsPath::from(b))
+            .map(|p| p.to_string())
+    };
+    #[cfg(unix)]
+    {
+        assert_eq!(
+            rel("/a/b/c.txt", "/a/x/y/"),
+            Some("../../b/c.txt".to_string())
+        );
+        assert_eq!(rel("/a/b/", "/a/b/"), Some("./".to_string()));
+        assert_eq!(rel("/a/", "/a/b/c/"), Some("../../".to_string()));
+        assert_eq!(rel("/a/b/../c", "/a/"), Some("c".to_string()));
+    }
+    assert_eq!(
+        OsPath::from("../x").relative_to(&OsPath::from("a")),
+        Some(OsPath::from("../../x"))
+    );
+    assert_eq!(rel("x", "../y"), None);
+
+    let path = OsPath::from("a/b/../../../c");
+    assert!(path.strip_prefix(&OsPath::from("a/b")).is_err());
+    assert_eq!(
+        OsPath::from("a/b/c/")
+            .strip_prefix(&OsPath::from("a"))
+            .unwrap(),
+        OsPath::from("b/c/")
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_canonicalize_follows_links() {
+    let tree = os_path::TestTree::new()
+        .file("real/data.txt", "x")
+        .build()
+        .unwrap();
+    std::os::unix::fs::symlink(tree.join("real"), tree.join("alias")).unwrap();
+
+    let dir = tree.join("alias").canonicalize().unwrap();
+    assert!(dir.is_dir());
+    assert_eq!(dir, tree.canonicalize().unwrap().join("real/"));
+
+    let file = tree.join("alias/../alias/data.txt").canonicalize().unwrap();
+    assert!(file.is_file());
+    assert_eq!(file.name().unwrap(), "data.txt");
+}
+
+#[test]
+fn test_detect_sequence() {
+    use os_path::detect_sequence;
+
+    let mut paths = OsPath::from("a/f_#.exr").expand_sequence(8..=12);
+    paths.extend(OsPath::from("a/g_###.exr").expand_sequence([1, 2, 999, 1000]));
+    paths.extend(OsPath::from("b/f_#.exr").expand_sequence([5]));
+    paths.push(OsPath::from("a/f_v2_7.exr"));
+
+    let found = detect_sequence(&paths);
+    let summary: Vec<(OsPath, Vec<u32>)> = found
+        .iter()
+        .map(|s| (s.pattern().clone(), s.frames().to_vec()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (OsPath::from("a/f_#.exr"), vec![8, 9, 10, 11, 12]),
+            (OsPath::from("a/g_###.exr"), vec![1, 2, 999, 1000]),
+        ]
+    );
+    assert_eq!(found[1].missing().len(), 996);
+    assert_eq!(found[1].paths()[3], OsPath::from("a/g_1000.exr"));
+}
+
+#[test]
+fn test_display_with() {
+    use os_path::{Flavor, Separator};
+
+    let path = OsPath::from("assets/icons/app.png");
+    assert_eq!(path.to_portable_string(), "assets/icons/app.png");
+    assert_eq!(
+        path.display_with(Separator::Backslash).to_string(),
+        "assets\\icons\\app.png"
+    );
+    assert_eq!(
+        path.display_with(Separator::Native).to_string(),
+        path.to_string()
+    );
+    assert_eq!(
+        format!("[{:}]", path.display_with(Separator::ForwardSlash)),
+        "[assets/icons/app.png]"
+    );
+
+    let win = OsPath::parse_as(Flavor::Windows, "C:\\Users\\me\\");
+    assert_eq!(win.to_portable_string(), "C:/Users/me/");
+    assert_eq!(
+        win.display_with(Separator::Backslash).to_string(),
+        "C:\\Users\\me\\"
+    );
+    assert_eq!(OsPath::new().to_portable_string(), "");
+}
+
+#[test]
+fn test_io_result_ext() {
+    use os_path::{IoResultExt, PathError};
+    use std::io;
+
+    let path = OsPath::from("data/records.csv");
+    let ok: io::Result<u32> = Ok(7);
+    assert_eq!(ok.path_context(&path).unwrap(), 7);
+
+    let failed: io::Result<()> = Err(io::ErrorKind::PermissionDenied.into());
+    let err = failed.path_context(&path).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+    let context = PathError::from_io(&err).unwrap();
+    assert_eq!(context.path, path);
+    assert_eq!(context.op, "access");
+
+    // The innermost path is kept.
+    let outer = OsPath::from("data/");
+    let err = Err::<(), _>(err)
+        .with_path_context("scan", &outer)
+        .unwrap_err();
+    assert_eq!(PathError::from_io(&err).unwrap().path, path);
+}
+
+#[cfg(all(feature = "miette", unix))]
+#[test]
+fn test_diagnostics() {
+    use miette::Diagnostic;
+    use os_path::{Glob, PathError};
+
+    let missing = OsPath::from("/no/such/config.toml");
+    let err = missing.metadata().unwrap_err();
+    let error = PathError::from_io(&err).unwrap();
+    assert_eq!(error.code().unwrap().to_string(), "os_path::io");
+    let label = error.labels().unwrap().next().unwrap();
+    assert_eq!((label.offset(), label.len()), (9, 11));
+    assert_eq!(label.label(), Some("entity not found"));
+    let source = error.source_code().unwrap();
+    let contents = source.read_span(label.inner(), 0, 0).unwrap();
+    assert_eq!(contents.data(), b"config.toml");
+
+    let error = Glob::new("src/[ab/*.rs").unwrap_err();
+    assert_eq!(error.code().unwrap().to_string(), "os_path::glob");
+    let label = error.labels().unwrap().next().unwrap();
+    assert_eq!((label.offset(), label.len()), (4, 3));
+    assert_eq!(label.label(), Some("unclosed character class"));
+}
+
+#[test]
+fn test_display_style() {
+    use os_path::Flavor;
+    use std::collections::HashSet;
+
+    let path = OsPath::from("/srv/data/").with_style(Flavor::Windows);
+    assert_eq!(path.to_string(), "\\srv\\data\\");
+    assert_eq!(String::from(&path), "\\srv\\data\\");
+    assert_eq!(path.parent().unwrap().to_string(), "\\srv\\");
+    assert_eq!(
+        path.join("../logs/app.log").to_string(),
+        "\\srv\\logs\\app.log"
+    );
+    assert_eq!(path.to_string_as(Flavor::Posix), "/srv/data/");
+
+    let plain = OsPath::from("/srv/data/");
+    assert_eq!(path, plain);
+    assert_eq!(HashSet::from([path.clone(), plain.clone()]).len(), 1);
+    assert_eq!(plain.style(), Flavor::native());
+    assert_eq!(
+        path.with_style(Flavor::native()).to_string(),
+        plain.to_string()
+    );
+}
+
+#[test]
+fn test_serde_forms() {
+    use os_path::{AsComponents, Lenient};
+    use serde::de::value::{self, BytesDeserializer};
+    use serde::Deserialize;
+
+    let json = |path: &str| serde_json::to_string(&AsComponents(OsPath::from(path))).unwrap();
+    #[cfg(unix)]
+    {
+        assert_eq!(json("/srv/www/"), r#"["/","srv","www",""]"#);
+        assert_eq!(json("/"), r#"["/"]"#);
+    }
+    assert_eq!(json("a/b.txt"), r#"["a","b.txt"]"#);
+    assert_eq!(json(""), "[]");
+
+    for text in ["/srv/www/", "/", "a/b.txt", "a/", "../up"] {
+        let path = OsPath::from(text);
+        let back: AsComponents = serde_json::from_str(&json(text)).unwrap();
+        assert_eq!(back.0, path, "{}", text);
+        assert_eq!(back.0.is_dir(), path.is_dir(), "{}", text);
+    }
+
+    let read: Lenient = serde_json::from_str(r#"["/","etc","hosts"]"#).unwrap();
+    assert!(read.0.is_absolute());
+    assert_eq!(read.0.name().unwrap(), "hosts");
+    assert!(serde_json::from_str::<Lenient>(r#"["a/b"]"#).is_err());
+    assert!(serde_json::from_str::<Lenient>(r#"["a","","b"]"#).is_err());
+    assert!(serde_json::from_str::<Lenient>("42").is_err());
+    assert!(serde_json::from_str::<OsPath>(r#"["a"]"#).is_err());
+
+    let bytes = BytesDeserializer::<value::Error>::new(b"logs/app.log");
+    let read = Lenient::deserialize(bytes).unwrap();
+    assert_eq!(read.0, OsPath::from("logs/app.log"));
+}
+
+#[test]
+fn test_join_keeps_pathbuf_in_step() {
+    let bases = [
+        "/srv/www/",
+        "/srv/www/index.html",
+        "/",
+        "rel/dir/",
+        "rel/file.txt",
+        "",
+        "../up/",
+    ];
+    let joined = [
+        "a.txt", "a/b/", "../c.txt", "./d", "/e/f", "..", "x/../y", "",
+    ];
+    for base in bases {
+        let base = OsPath::from(base);
+        for name in joined {
+            let result = base.join(name);
+            if result.components().count() > 0 {
+                let rebuilt = OsPath::from(result.to_string());
+                assert_eq!(result.to_path(), rebuilt.to_path(), "{} + {}", base, name);
+            }
+
+            let mut pushed = base.clone();
+            pushed.push(name);
+            assert_eq!(pushed.to_path(), result.to_path(), "{} + {}", base, name);
+            let joined_os = base.join_os(&OsPath::from(name));
+            assert_eq!(joined_os.to_path(), result.to_path(), "{} + {}", base, name);
+        }
+    }
+}
+
+#[test]
+fn test_deref_to_path() {
+    use std::path::Path;
+
+    fn takes_path(path: &Path) -> usize {
+        path.components().count()
+    }
+
+    let path = OsPath::from("assets/icons/app.png");
+    assert_eq!(takes_path(&path), 3);
+    assert_eq!(path.extension().unwrap(), "png");
+    assert_eq!(path.file_name().unwrap(), "app.png");
+    assert!(path.starts_with("assets"));
+    assert!(path.is_relative());
+    assert_eq!(
+        path.with_extension("svg"),
+        OsPath::from("assets/icons/app.svg")
+    );
+    assert_eq!(
+        path.to_path().with_extension("svg"),
+        Path::new("assets/icons/app.svg")
+    );
+}
+
+#[test]
+fn test_base_path() {
+    use os_path::BasePath;
+
+    let names = [
+        "index.html",
+        "css/site.css",
+        "/img/",
+        "a\\b.txt",
+        "",
+        "/",
+        "./x",
+        "../y",
+        "a//b",
+    ];
+    for base in ["/srv/www", "/srv/www/", "/", "static", ""] {
+        let root = BasePath::new(base);
+        assert!(root.path().is_dir() || root.path().components().count() == 0);
+        for name in names {
+            let appended = root.append(name);
+            let joined = root.path().join(name);
+            assert_eq!(appended, joined, "{} + {}", base, name);
+            assert_eq!(appended.to_path(), joined.to_path(), "{} + {}", base, name);
+        }
+    }
+}
+
+#[test]
+fn test_validation() {
+    use os_path::OsPathError;
+    use std::ffi::OsString;
+
+    for good in [
+        "uploads/2024/photo.jpg",
+        "../shared/notes.md",
+        "./a",
+        "console.log",
+        "com10",
+        ".hidden",
+    ] {
+        assert_eq!(
+            OsPath::parse_validated(good).map(|_| ()),
+            Ok(()),
+            "{}",
+            good
+        );
+    }
+    let bad = [
+        ("", OsPathError::Empty),
+        ("a\0b", OsPathError::NulByte(1)),
+        ("x/PRN", OsPathError::ReservedName("PRN".to_string())),
+        (
+            "x/Lpt3.tar.gz",
+            OsPathError::ReservedName("Lpt3.tar.gz".to_string()),
+        ),
+        (
+            "x/nul .txt",
+            OsPathError::ReservedName("nul .txt".to_string()),
+        ),
+        ("a|b", OsPathError::IllegalCharacter("a|b".to_string(), '|')),
+        (
+            "tab\there",
+            OsPathError::IllegalCharacter("tab\there".to_string(), '\t'),
+        ),
+        ("end.", OsPathError::TrailingDotOrSpace("end.".to_string())),
+    ];
+    for (input, error) in bad {
+        assert_eq!(OsPath::parse_validated(input), Err(error), "{:?}", input);
+    }
+    let long = "n".repeat(256);
+    assert_eq!(
+        OsPath::parse_validated(&long),
+        Err(OsPathError::NameTooLong(long.clone()))
+    );
+    assert!(OsPath::parse_validated(&long[1..]).is_ok());
+
+    assert_eq!(
+        OsPath::try_from(OsString::from("a/b.txt")),
+        Ok(OsPath::from("a/b.txt"))
+    );
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStringExt;
+        let raw = OsString::from_vec(b"ok/\xff".to_vec());
+        assert_eq!(OsPath::try_from(raw), Err(OsPathError::InvalidUtf8(3)));
+    }
+}
+
+#[cfg(feature = "synthetic")]
+#[test]
+fn test_synthetic_paths() {
+    use os_path::SyntheticPaths;
+
+    let generator = SyntheticPaths::new(42)
+        .max_depth(5)
+        .parent_refs(50)
+        .dirs(100);
+    let strings = generator.strings(200);
+    assert_eq!(strings, generator.strings(200));
+    assert_ne!(strings, SyntheticPaths::new(43).strings(200));
+    assert!(strings.iter().all(|s| s.ends_with('/')));
+    assert!(strings.iter().any(|s| s.contains("..")));
+    assert!(strings.iter().all(|s| s.split('/').count() <= 6));
+
+    let names = SyntheticPaths::new(1).names(50);
+    assert!(names.iter().all(|n| !n.contains('/') && n.contains('.')));
+}
+
+#[test]
+fn test_from_untrusted() {
+    let cases = [
+        ("../../etc/passwd", "etc/passwd"),
+        ("\\\\server\\share\\x.txt", "server/share/x.txt"),
+        ("D:/a/./b/../../../c", "c"),
+        ("nul/COM1.log/prn .txt", "_nul/_COM1.log/_prn .txt"),
+        ("tab\there/new\nline", "tabhere/newline"),
+        ("a|b<c>:d\"e*", "a_b_c__d_e_"),
+        ("dots.../ ...", "dots"),
+        ("keep/dir/", "keep/dir/"),
+        ("", ""),
+    ];
+    for (input, expected) in cases {
+        let path = OsPath::from_untrusted(input);
+        assert_eq!(path, OsPath::from(expected), "{:?}", input);
+        assert!(!path.is_absolute());
+        assert_eq!(path.max_escape_depth(), 0);
+        assert_eq!(path.validate(), Ok(()), "{:?}", input);
+    }
+
+    let long = format!("{}.txt", "é".repeat(200));
+    let name = OsPath::from_untrusted(&long);
+    assert!(name.name().unwrap().len() <= 255);
+    assert_eq!(name.validate(), Ok(()));
+
+    let base = OsPath::from("uploads/");
+    for input in ["../../x", "/x", "a/../../x", "C:\\x"] {
+        let joined = base.join_untrusted(input);
+        assert_eq!(joined, OsPath::from("uploads/x"), "{:?}", input);
+    }
+    assert_eq!(
+        OsPath::from("../a/../../b/aux").sanitize(),
+        OsPath::from("b/_aux")
+    );
+}
+
+#[test]
+fn test_short_name() {
+    use os_path::ShortName;
+
+    let name = ShortName::new("photo.JPG").unwrap();
+    assert!(name.is_inline());
+    assert_eq!(name.extension(), Some("JPG"));
+    assert!(name.has_extension("jpg") && name.has_extension(".Jpg"));
+    assert!(!name.has_extension("jpeg"));
+    assert_eq!(ShortName::new(".bashrc").unwrap().extension(), None);
+    assert_eq!(
+        ShortName::new(".bashrc").unwrap().with_extension("bak"),
+        ".bashrc.bak"
+    );
+    assert_eq!(name.with_extension(""), "photo");
+    assert_eq!(ShortName::new(""), None);
+    assert_eq!(ShortName::new("a\\b"), None);
+
+    // Names that outgrow the inline room move to the heap and keep working.
+    let stem = "x".repeat(22);
+    assert!(ShortName::new(&stem).unwrap().is_inline());
+    let long = ShortName::new(&stem).unwrap().with_extension("txt");
+    assert!(!long.is_inline());
+    assert_eq!(long, format!("{}.txt", stem).as_str());
+    assert_eq!(long.file_stem(), stem);
+    assert_eq!(long.with_extension(""), stem.as_str());
+
+    // A NUL is escaped as `OsPath` escapes it.
+    let nul = ShortName::new("a\u{0}b").unwrap();
+    assert_eq!(OsPath::from(nul.clone()), OsPath::from("a\u{0}b"));
+    assert_eq!(nul.to_string(), "a\u{fffd}b");
+
+    for input in ["what?\u{7}.pdf ", "aux.txt", "...", &"x".repeat(300)] {
+        let expected = OsPath::from_untrusted(input);
+        let sanitized = ShortName::new(input).and_then(|n| n.sanitize());
+        assert_eq!(sanitized.map(OsPath::from).unwrap_or_default(), expected);
+    }
+    assert_eq!(
+        OsPath::from("docs/Read Me.md").short_name().unwrap(),
+        ShortName::new("Read Me.md").unwrap()
+    );
+    assert_eq!(OsPath::new().short_name(), None);
+}
+
+#[test]
+fn test_pure_path() {
+    use os_path::PurePath;
+
+    // A `PurePath` parses, joins, resolves and displays as an `OsPath` does.
+    let bases = ["/srv/www/", "/srv/index.html", "a/b", "a/b/", "", "/"];
+    let others = ["c.txt", "../c/", "../../../c", "/d/e", "x/../y", ""];
+    for base in bases {
+        for other in others {
+            let pure = PurePath::from(base).join(other);
+            let os = OsPath::from(base).join(other);
+            assert_eq!(pure.to_string(), os.to_string(), "{:?} + {:?}", base, other);
+            assert_eq!(pure.is_dir(), os.is_dir());
+            assert_eq!(
+                pure.components().collect::<Vec<_>>(),
+                os.components().collect::<Vec<_>>()
+            );
+
+            let mut pure = PurePath::from(format!("{}/{}", base, other));
+            let mut os = OsPath::from(format!("{}/{}", base, other));
+            pure.resolve();
+            os.resolve();
+            assert_eq!(pure.to_string(), os.to_string());
+            assert_eq!(OsPath::from(pure.clone()), os);
+            assert_eq!(PurePath::from(&os), pure);
+        }
+    }
+
+    let mut path = PurePath::new();
+    assert_eq!(path.name(), None);
+    path.push("docs");
+    path.force_dir();
+    assert!(path.is_dir() && !path.is_absolute());
+    assert_eq!(path.join("a\u{0}b").name(), Some("a\u{0}00b"));
+    assert_eq!(path.join("a\u{0}b").to_string(), "docs/a\u{fffd}b");
+}
+
+#[test]
+fn test_path_iter_ext() {
+    use os_path::PathIterExt;
+
+    let paths = || {
+        [
+            "docs/",
+            "docs/page10.md",
+            "docs/page2.md",
+            "docs/img/logo.PNG",
+            "src/main.rs",
+            "src/../docs/notes.md",
+            "README.md",
+        ]
+        .into_iter()
+        .map(OsPath::from)
+    };
+    let names = |it: &mut dyn Iterator<Item = OsPath>| -> Vec<String> {
+        it.map(|p| p.to_string()).collect()
+    };
+
+    let md = names(&mut paths().filter_ext("md").under("docs").sorted_natural());
+    assert_eq!(md, vec!["docs/page2.md", "docs/page10.md"]);
+    assert_eq!(paths().filter_ext("png").count(), 0);
+    assert_eq!(paths().under("src/").count(), 1);
+
+    let relative = names(&mut paths().filter_ext("md").relative_to("docs/"));
+    assert_eq!(
+        relative,
+        vec!["page10.md", "page2.md", "notes.md", "../README.md"]
+    );
+
+    let top = names(&mut paths().dedup_ancestors());
+    assert_eq!(
+        top,
+        vec!["docs/", "src/main.rs", "src/../docs/notes.md", "README.md"]
+    );
+    let again = names(&mut paths().chain(paths()).dedup_ancestors());
+    assert_eq!(again, top);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_secure_join() {
+    use os_path::{LinkCycle, PathEscape};
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::fs::symlink;
+
+    let tmp =
+        OsPath::from(std::env::temp_dir()).join(format!("os_path_secure_{}/", std::process::id()));
+    let _ = std::fs::remove_dir_all(&tmp);
+    let root = tmp.join("root/");
+    std::fs::create_dir_all(root.join("docs/")).unwrap();
+    std::fs::create_dir_all(tmp.join("outside/")).unwrap();
+    std::fs::write(root.join("docs/a.txt"), "a").unwrap();
+    symlink("docs", root.join("relative")).unwrap();
+    symlink(root.join("docs/a.txt"), root.join("absolute")).unwrap();
+    symlink("../outside", root.join("up")).unwrap();
+    symlink(tmp.join("outside/"), root.join("away")).unwrap();
+    symlink("loop", root.join("loop")).unwrap();
+
+    let real = root.canonicalize().unwrap();
+    let joined = root.secure_join("relative/a.txt").unwrap();
+    assert_eq!(joined, real.join("docs/a.txt"));
+    assert_eq!(root.secure_join("absolute").unwrap(), joined);
+    assert_eq!(root.secure_join("/docs/./new/../a.txt").unwrap(), joined);
+    assert_eq!(root.secure_join("docs").unwrap(), real.join("docs/"));
+    assert_eq!(root.secure_join("").unwrap(), real);
+
+    for untrusted in ["..", "docs/../../x", "up/file", "away", "relative/../.."] {
+        let err = root.secure_join(untrusted).unwrap_err();
+        let escape = PathEscape::from_io(&err).unwrap_or_else(|| panic!("{}", untrusted));
+        assert_eq!(escape.base(), &real);
+        assert!(!root.is_secure_join(untrusted));
+    }
+    let err = root.secure_join("loop/x").unwrap_err();
+    assert!(LinkCycle::from_io(&err).is_some());
+    assert!(tmp.join("missing/").secure_join("a").is_err());
+
+    // The spelling of an escaped `..` or `/` is refused rather than taken as a name, or worse, as what it spells.
+    for forged in [
+        "\u{0}2E\u{0}2E/\u{0}2E\u{0}2E/etc/passwd",
+        "docs\u{0}2F..\u{0}2F..\u{0}2Fx",
+    ] {
+        let err = root.secure_join(forged).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(!root.is_secure_join(forged));
+    }
+    // A name that is not Unicode is still a name.
+    let odd = std::ffi::OsStr::from_bytes(b"docs/\xff.txt");
+    let joined = root.secure_join(odd).unwrap();
+    assert_eq!(joined.to_path(), real.to_path().join(odd));
+
+    std::fs::remove_dir_all(&tmp).unwrap();
+}
+
+#[test]
+fn test_dedup_ancestors() {
+    use os_path::dedup_ancestors;
+
+    let roots = dedup_ancestors([
+        "photos/2024/trip/",
+        "docs/",
+        "photos/2024/",
+        "docs/report.pdf",
+        "photos/20240/",
+        "docs/../notes.txt",
+        "photos/2024/",
+    ]);
+    let roots: Vec<String> = roots.iter().map(|p| p.to_string()).collect();
+    assert_eq!(
+        roots,
+        vec![
+            "docs/",
+            "docs/../notes.txt",
+            "photos/2024/",
+            "photos/20240/"
+        ]
+    );
+    assert!(dedup_ancestors(Vec::<OsPath>::new()).is_empty());
+    assert_eq!(dedup_ancestors(["a/b", "", "c"]), vec![OsPath::from("")]);
+}
+
+#[test]
+fn test_starts_with_ends_with() {
+    use os_path::CaseSensitivity::{Insensitive, Sensitive};
+
+    let path = OsPath::from("srv/www/img/Logo.png");
+    assert!(path.starts_with("srv/www"));
+    assert!(path.starts_with("srv/www/img/Logo.png/"));
+    assert!(path.starts_with(""));
+    assert!(!path.starts_with("srv/ww"));
+    assert!(!path.starts_with("srv/www/img/Logo.png/x"));
+    #[cfg(unix)]
+    assert!(!OsPath::from("/srv/www/img").starts_with("srv/www"));
+    assert!(!OsPath::from("srv/www/a/../../etc").starts_with("srv/www"));
+    assert!(OsPath::from("srv/www/a/../b").starts_with("srv/www"));
+
+    assert!(path.ends_with("img/Logo.png"));
+    assert!(path.ends_with(""));
+    assert!(!path.ends_with("go.png"));
+    assert!(!path.ends_with("x/srv/www/img/Logo.png"));
+
+    assert!(path.starts_with_case("SRV/www", Insensitive));
+    assert!(!path.starts_with_case("SRV/www", Sensitive));
+    assert!(path.ends_with_case("logo.PNG", Insensitive));
+    assert!(!path.ends_with_case("logo.PNG", Sensitive));
+    assert_eq!(
+        path.ends_with("img/logo.png"),
+        cfg!(any(windows, target_os = "macos"))
+    );
+}
+
+#[test]
+fn test_common_ancestor() {
+    use os_path::common_ancestor;
+
+    let a = OsPath::from("ws/core/src/lib.rs");
+    let b = OsPath::from("ws/core/src/../tests/it.rs");
+    assert_eq!(a.common_ancestor(&b), Some(OsPath::from("ws/core/")));
+    assert_eq!(a.common_ancestor(&a), Some(OsPath::from("ws/core/src/")));
+    assert_eq!(
+        a.common_ancestor(&OsPath::from("ws/core/src/lib.rs/")),
+        Some(OsPath::from("ws/core/src/"))
+    );
+    assert_eq!(
+        a.common_ancestor(&OsPath::from("ws/corelib/x")),
+        Some(OsPath::from("ws/"))
+    );
+    assert_eq!(a.common_ancestor(&OsPath::from("other/x")), None);
+    #[cfg(unix)]
+    assert_eq!(
+        a.common_ancestor(&OsPath::from("/ws/core/src/lib.rs")),
+        None
+    );
+
+    let files = ["ws/a/x.rs", "ws/a/b/y.rs", "ws/a/b/c/z.rs"];
+    assert_eq!(common_ancestor(files), Some(OsPath::from("ws/a/")));
+    assert_eq!(common_ancestor(["ws/a/x.rs"]), Some(OsPath::from("ws/a/")));
+    assert_eq!(common_ancestor(["ws/a/x.rs", "top.rs"]), None);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_expand_to_files() {
+    use os_path::{expand_to_files_in, MemoryFs, PathFilter};
+
+    let mut fs = MemoryFs::new();
+    fs.add_file("/in/a/1.txt", "")
+        .unwrap()
+        .add_file("/in/a/sub/2.txt", "")
+        .unwrap()
+        .add_file("/in/a/skip/3.txt", "")
+        .unwrap()
+        .add_file("/in/b.txt", "")
+        .unwrap()
+        .add_file("/in/.hidden.txt", "")
+        .unwrap()
+        .add_dir("/in/empty/")
+        .unwrap();
+
+    let filter = PathFilter::new().exclude("skip").unwrap();
+    let selection = [
+        "/in/a/sub/2.txt",
+        "/in/a",
+        "/in/empty/",
+        "/in/a/sub/../1.txt",
+        "/in/b.txt/",
+        "/in/.hidden.txt",
+    ];
+    let files = expand_to_files_in(selection, &filter, &fs).unwrap();
+    let files: Vec<String> = files.iter().map(|p| p.to_string()).collect();
+    assert_eq!(files, vec!["/in/a/sub/2.txt", "/in/a/1.txt", "/in/b.txt"]);
+
+    let all = PathFilter::new().hidden(true);
+    let files = expand_to_files_in(["/in/"], &all, &fs).unwrap();
+    assert_eq!(files.len(), 5);
+
+    assert!(expand_to_files_in(["/in/missing"], &all, &fs).is_err());
+}
+
+#[test]
+fn test_rel_id() {
+    use os_path::{OsPathError, RelId, RelIdError};
+
+    for id in ["a", "maps/level 1/terrain.bin", "é/ü.png"] {
+        let rel = RelId::new(id).unwrap();
+        assert_eq!(rel.to_string(), id);
+        assert_eq!(rel.names().count(), id.split('/').count());
+    }
+    let refused = [
+        ("", RelIdError::Empty),
+        ("/maps/a", RelIdError::Absolute),
+        ("maps/../a", RelIdError::ParentRef),
+        ("maps//a", RelIdError::NotCanonical),
+        ("maps/./a", RelIdError::NotCanonical),
+        ("maps/", RelIdError::NotCanonical),
+        (
+            "maps/con",
+            RelIdError::InvalidName(OsPathError::ReservedName("con".to_string())),
+        ),
+    ];
+    for (id, error) in refused {
+        assert_eq!(RelId::new(id), Err(error), "{:?}", id);
+    }
+
+    let root = OsPath::from("project/assets");
+    let id = RelId::from_path(&root, &OsPath::from("project/x/../assets/maps/a.bin")).unwrap();
+    assert_eq!(id.as_str(), "maps/a.bin");
+    assert_eq!(id.to_path(&root), OsPath::from("project/assets/maps/a.bin"));
+    assert_eq!(RelId::from_path(&root, &root), Err(RelIdError::Empty));
+    assert_eq!(
+        RelId::from_path(&root, &OsPath::from("project/assets/../b")),
+        Err(RelIdError::OutsideRoot)
+    );
+
+    let json = serde_json::to_string(&id).unwrap();
+    assert_eq!(json, r#""maps/a.bin""#);
+    assert_eq!(serde_json::from_str::<RelId>(&json).unwrap(), id);
+    assert!(serde_json::from_str::<RelId>(r#""../a""#).is_err());
+}
+
+#[test]
+fn test_normalization_levels() {
+    use os_path::Normalization;
+
+    let cases = [
+        ("a/./b/../c/", "a/b/../c/", "a/c/"),
+        ("./", "./", "./"),
+        ("a/.", "a/", "a/"),
+        ("../x/./y", "../x/y", "../x/y"),
+    ];
+    for (input, lexical, logical) in cases {
+        let path = OsPath::from(input);
+        assert_eq!(
+            path.normalize_lexical(),
+            OsPath::from(lexical),
+            "{:?}",
+            input
+        );
+        assert_eq!(
+            OsPath::from_normalized(input, Normalization::Lexical),
+            OsPath::from(lexical)
+        );
+        assert_eq!(
+            OsPath::from_normalized(input, Normalization::Logical),
+            OsPath::from(logical)
+        );
+        assert_eq!(
+            OsPath::from_normalized(input, Normalization::Verbatim),
+            path
+        );
+    }
+
+    #[cfg(unix)]
+    {
+        let tmp = OsPath::from(std::env::temp_dir())
+            .join(format!("os_path_normalize_{}/", std::process::id()));
+        std::fs::create_dir_all(tmp.join("real/inner/")).unwrap();
+        std::os::unix::fs::symlink(tmp.join("real/inner/"), tmp.join("link")).unwrap();
+        let real = tmp.canonicalize().unwrap();
+
+        let through = OsPath::from(format!("{}link/../file.txt", tmp));
+        let mut logical = through.clone();
+        logical.resolve();
+        assert_eq!(logical, tmp.join("file.txt"));
+        assert_eq!(
+            through.normalize_physical().unwrap(),
+            real.join("real/file.txt")
+        );
+        assert_eq!(
+            OsPath::from(format!("{}link/missing/../new/", tmp))
+                .normalize_physical()
+                .unwrap(),
+            real.join("real/inner/new/")
+        );
+        std::fs::remove_dir_all(&tmp).unwrap();
+    }
+}